@@ -1,17 +1,76 @@
-use crate::actions::get_project_walker;
-use crate::mutation::Mutation;
+use crate::actions::cache::{AnalysisCache, CachedFile};
+use crate::actions::coverage::Coverage;
+use crate::actions::dead_code::skip_dead_code;
+use crate::actions::get_project_entries;
+use crate::actions::probe::skip_not_compiled;
+use crate::actions::reporting::results::{DeadFunction, Exclusions, SkippedFile, SkippedMutant};
+use crate::actions::type_hints::{OperandType, TypeHints};
+use crate::actions::viability::ViabilityStats;
+use crate::cli::Order;
+use crate::config::Settings;
+use crate::mutation::{disambiguate_stable_ids, fnv1a, Complexity, Mutation, MutationChunk};
+use crate::plugin::{FunctionSpan, Plugin};
+use crate::report::SkipReason;
 use eyre::{eyre, WrapErr};
-use std::fs::File;
-use std::io::Read;
-use std::path::{Path, PathBuf};
-use syn::{Attribute, ItemFn};
-
-pub static FUNCTION_ITEM: &'static str = "function_item";
-static ATTRIBUTE_ITEM: &'static str = "attribute_item";
-pub static BLOCK_ITEM: &'static str = "block";
-static BINARY_EXPR_ITEM: &'static str = "binary_expression";
-static MINUS_ITEM: &'static str = "-";
-static PLUS_ITEM: &'static str = "+";
+use rayon::prelude::*;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use syn::{Attribute, Signature};
+
+pub static FUNCTION_ITEM: &str = "function_item";
+static TRAIT_ITEM: &str = "trait_item";
+pub static BLOCK_ITEM: &str = "block";
+static BINARY_EXPR_ITEM: &str = "binary_expression";
+static MINUS_ITEM: &str = "-";
+static PLUS_ITEM: &str = "+";
+/// Replacement of arithmetic binary operators
+pub static ARITHMETIC_OPERATOR: &str = "arithmetic";
+/// Replacement of the literals bound to local variables
+pub static LITERAL_OPERATOR: &str = "literal";
+/// Replacement of the lengths of array repeat expressions and the counts of `vec!`
+pub static LENGTH_OPERATOR: &str = "length";
+/// Replacement of the literal patterns of match arms
+pub static PATTERN_OPERATOR: &str = "pattern";
+/// Replacement of the variants of fieldless enums by their siblings
+pub static VARIANT_OPERATOR: &str = "variant";
+static MACRO_INVOCATION_ITEM: &str = "macro_invocation";
+static BYTE_ORDER_MARK: char = '\u{feff}';
+/// Macros whose arguments are comma separated expressions
+static EXPRESSION_MACROS: [&str; 17] = [
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "debug_assert",
+    "debug_assert_eq",
+    "debug_assert_ne",
+    "dbg",
+    "eprint",
+    "eprintln",
+    "format",
+    "panic",
+    "print",
+    "println",
+    "todo",
+    "vec",
+    "write",
+    "writeln",
+];
+
+/// Harnesses given to benchmark functions by criterion and the `test` crate
+static BENCHMARK_TYPES: [&str; 3] = ["Criterion", "BenchmarkGroup", "Bencher"];
+
+/// Kinds of syntax nodes opening a branch in a function
+static BRANCH_KINDS: [&str; 6] = [
+    "if_expression",
+    "match_arm",
+    "while_expression",
+    "loop_expression",
+    "for_expression",
+    "try_expression",
+];
 
 fn rust_source(entry: &walkdir::DirEntry) -> bool {
     entry
@@ -21,91 +80,265 @@ fn rust_source(entry: &walkdir::DirEntry) -> bool {
         .unwrap_or(false)
 }
 
-fn is_test_function(attrs: &Vec<Attribute>) -> eyre::Result<bool> {
+/// Whether one of the attributes of a function, wherever it stands among the others and the doc
+/// comments, marks it as a test, with or without arguments like `#[tokio::test(flavor = "..")]`
+fn is_test_function(attrs: &[Attribute], test_attributes: &[String]) -> eyre::Result<bool> {
     for attr in attrs {
-        if let syn::Meta::Path(path) = &attr.meta {
-            let merge_path = path
-                .segments
-                .iter()
-                .map(|x| x.ident.to_string())
-                .collect::<Vec<_>>()
-                .join("::");
-            let known_pattern = ["test", "tokio::test"];
-            if known_pattern.contains(&merge_path.as_str()) {
-                return Ok(true);
-            }
+        let merge_path = attr
+            .path()
+            .segments
+            .iter()
+            .map(|x| x.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+        if test_attributes.contains(&merge_path) {
+            return Ok(true);
         }
     }
     Ok(false)
 }
 
-pub fn check_function_is_test(
-    parent: &tree_sitter::Node,
-    function_item: &tree_sitter::Node,
-    index: usize,
-    file: &String,
-) -> eyre::Result<bool> {
-    if index == 0 {
-        return Ok(false);
+/// Names of the `#[ignore]` tests of some items, qualified with the inline modules declaring them
+fn ignored_tests(
+    items: &[syn::Item],
+    module: &str,
+    test_attributes: &[String],
+    ignored: &mut Vec<String>,
+) -> eyre::Result<()> {
+    for item in items {
+        match item {
+            syn::Item::Fn(item_fn)
+                if item_fn
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("ignore"))
+                    && is_test_function(&item_fn.attrs, test_attributes)? =>
+            {
+                ignored.push(format!("{module}{}", item_fn.sig.ident));
+            }
+            syn::Item::Mod(item_mod) => {
+                if let Some((_, items)) = &item_mod.content {
+                    let module = format!("{module}{}::", item_mod.ident);
+                    ignored_tests(items, &module, test_attributes, ignored)?;
+                }
+            }
+            _ => {}
+        }
     }
+    Ok(())
+}
 
-    // is there attribute on function
-    if let Some(attribute_node) = parent.child(index - 1) {
-        if attribute_node.kind() == ATTRIBUTE_ITEM {
-            let attribute_data = &file[attribute_node.start_byte()..function_item.end_byte()];
-            let item_fn: ItemFn = syn::parse_str(attribute_data)?;
-            return is_test_function(&item_fn.attrs);
+/// Names of the `#[ignore]` tests of the project, files which can't be parsed are left out
+fn project_ignored_tests(settings: &Settings) -> eyre::Result<Vec<String>> {
+    let mut ignored = vec![];
+    for entry in get_project_entries(&settings.root_path, settings)? {
+        if !rust_source(&entry) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if let Ok(file) = syn::parse_file(&content) {
+            ignored_tests(&file.items, "", &settings.test_attributes, &mut ignored)?;
         }
     }
+    Ok(ignored)
+}
 
-    Ok(false)
+/// Whether a function takes a benchmark harness, as criterion and `#[bench]` benchmarks do
+fn is_benchmark_function(sig: &Signature) -> bool {
+    sig.inputs.iter().any(|input| {
+        let syn::FnArg::Typed(pattern) = input else {
+            return false;
+        };
+        let syn::Type::Reference(reference) = &*pattern.ty else {
+            return false;
+        };
+        let syn::Type::Path(path) = &*reference.elem else {
+            return false;
+        };
+        path.path
+            .segments
+            .last()
+            .is_some_and(|segment| BENCHMARK_TYPES.iter().any(|name| segment.ident == name))
+    })
+}
+
+/// Attributes, signature and visibility of a function declared in a file
+#[derive(Clone, Copy)]
+struct FunctionDeclaration<'a> {
+    attrs: &'a [Attribute],
+    sig: &'a Signature,
+    /// Declared `pub`, or a default method of a `pub` trait
+    public: bool,
+}
+
+/// Functions and default methods of traits of a file, by byte offset of their name in `content`
+///
+/// The file is parsed once with syn, functions are found back from the tree-sitter nodes by span.
+/// syn drops the byte order mark and the shebang line before parsing, its spans are shifted by
+/// their length so that they match the offsets of the content it was given.
+fn index_functions<'a>(
+    file: &'a syn::File,
+    content: &str,
+) -> HashMap<usize, FunctionDeclaration<'a>> {
+    let offset = content
+        .strip_prefix(BYTE_ORDER_MARK)
+        .map_or(0, |_| BYTE_ORDER_MARK.len_utf8())
+        + file.shebang.as_ref().map_or(0, String::len);
+    file.items
+        .iter()
+        .flat_map(|item| match item {
+            syn::Item::Fn(item_fn) => vec![(&item_fn.attrs, &item_fn.sig, &item_fn.vis)],
+            syn::Item::Trait(item_trait) => item_trait
+                .items
+                .iter()
+                .filter_map(|trait_item| match trait_item {
+                    syn::TraitItem::Fn(method) => {
+                        Some((&method.attrs, &method.sig, &item_trait.vis))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        })
+        .map(|(attrs, sig, vis)| {
+            (
+                offset + sig.ident.span().byte_range().start,
+                FunctionDeclaration {
+                    attrs,
+                    sig,
+                    public: matches!(vis, syn::Visibility::Public(_)),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Enums declared among items, and in their inline modules
+fn declared_enums<'a>(items: &'a [syn::Item], enums: &mut Vec<&'a syn::ItemEnum>) {
+    for item in items {
+        match item {
+            syn::Item::Enum(item_enum) => enums.push(item_enum),
+            syn::Item::Mod(syn::ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => declared_enums(items, enums),
+            _ => {}
+        }
+    }
+}
+
+/// Variants of the fieldless enums of a file, with at least two of them, by enum name
+///
+/// Enums of the inline modules are indexed as well. Enums declared twice with the same name are
+/// left out, their variants can't be told apart without resolving paths.
+fn index_enums(file: &syn::File) -> HashMap<String, Vec<String>> {
+    let mut enums = HashMap::new();
+    let mut declared = HashSet::new();
+    let mut duplicated = HashSet::new();
+    let mut items = vec![];
+    declared_enums(&file.items, &mut items);
+    for item_enum in items {
+        let name = item_enum.ident.to_string();
+        let fieldless = item_enum
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.fields, syn::Fields::Unit));
+        let variants = item_enum
+            .variants
+            .iter()
+            .map(|variant| variant.ident.to_string())
+            .collect::<Vec<_>>();
+        if !declared.insert(name.clone()) {
+            duplicated.insert(name);
+        } else if fieldless && variants.len() > 1 {
+            enums.insert(name, variants);
+        }
+    }
+    enums.retain(|name, _| !duplicated.contains(name));
+    enums
+}
+
+/// Whether a node may decide when a loop ends: anywhere in a `loop` or a `while`, whose exit
+/// depends on the code of their body, or in the value iterated by a `for`
+fn controls_loop(node: tree_sitter::Node) -> bool {
+    let mut child = node;
+    while let Some(parent) = child.parent() {
+        match parent.kind() {
+            "loop_expression" | "while_expression" => return true,
+            "for_expression" if parent.child_by_field_name("value") == Some(child) => return true,
+            "function_item" | "closure_expression" => return false,
+            _ => {}
+        }
+        child = parent;
+    }
+    false
 }
 
 fn handle_binary_expression(
     child: tree_sitter::Node,
-    file: &String,
+    file: &str,
     mutations: &mut Vec<Mutation>,
     function_name: &String,
+    hints: &TypeHints,
 ) -> eyre::Result<()> {
-    let binary_expr_data = &file[child.start_byte()..child.end_byte()];
+    let context = child
+        .parent()
+        .map(|parent| parent.kind())
+        .unwrap_or_default();
 
     let mut binary_expr_cursor = child.walk();
     for component in child.children(&mut binary_expr_cursor) {
         if component.kind() == BINARY_EXPR_ITEM {
-            handle_binary_expression(component, file, mutations, function_name)?;
+            handle_binary_expression(component, file, mutations, function_name, hints)?;
+        } else if component.is_named() {
+            // Operands giving a value too, as `(a - b)` or `f(a - b).g()`
+            handle_value(component, file, mutations, function_name, hints)?;
         }
 
         if [MINUS_ITEM, PLUS_ITEM].contains(&component.kind()) {
             let operator_item = component;
 
-            let binary_expr: syn::ExprBinary = syn::parse_str(binary_expr_data)?;
-            let mutations_details = match binary_expr.op {
-                syn::BinOp::Sub(..) => {
-                    log::trace!(
-                        "Binary - operation found at line {}",
-                        operator_item.start_position().row + 1
-                    );
+            let mut mutations_details = if operator_item.kind() == MINUS_ITEM {
+                log::trace!(
+                    "Binary - operation found at line {}",
+                    operator_item.start_position().row + 1
+                );
 
-                    vec![
-                        ("+", "replace - by +"),
-                        ("*", "replace - by *"),
-                        ("&&", "replace - by &&"),
-                    ]
-                }
-                syn::BinOp::Add(..) => {
-                    log::trace!(
-                        "--> Binary + operation found at line {}",
-                        operator_item.start_position().row + 1
-                    );
-                    vec![("-", "replace + by -"), ("*", "replace + by *")]
-                }
-                _ => vec![],
+                vec![
+                    ("+", "replace - by +"),
+                    ("*", "replace - by *"),
+                    ("&&", "replace - by &&"),
+                ]
+            } else {
+                log::trace!(
+                    "--> Binary + operation found at line {}",
+                    operator_item.start_position().row + 1
+                );
+                vec![("-", "replace + by -"), ("*", "replace + by *")]
             };
+            // Skip replacements which can't type check
+            let operands = hints.binary_operands_type(child, file);
+            mutations_details.retain(|(mutation, reason)| {
+                let viable = match operands {
+                    OperandType::Numeric => *mutation != "&&",
+                    OperandType::Text => false,
+                    OperandType::Unknown => true,
+                };
+                if !viable {
+                    log::trace!("Skip {reason}, operands are {operands:?}");
+                }
+                viable
+            });
             for (mutation, reason) in mutations_details {
                 mutations.push(
                     Mutation::new(mutation, operator_item)
                         .with_reason(reason)
-                        .with_function_name(&function_name),
+                        .with_function_name(function_name)
+                        .with_operator(ARITHMETIC_OPERATOR)
+                        .with_context(context)
+                        .with_loop_control(controls_loop(operator_item)),
                 )
             }
         }
@@ -114,85 +347,1870 @@ fn handle_binary_expression(
     Ok(())
 }
 
+/// Mutations of the binary expressions giving a value: tail expressions of a block, `let`
+/// initializers and returned values, along with the receivers and arguments of the calls
+/// giving them, in the block and the blocks nested in it
 fn handle_block(
     node_block: tree_sitter::Node,
-    file: &String,
+    file: &str,
     mutations: &mut Vec<Mutation>,
-    function_name: String,
+    function_name: &String,
+    hints: &TypeHints,
 ) -> eyre::Result<()> {
     let mut cursor = node_block.walk();
-    for child in node_block.children(&mut cursor) {
-        if child.kind() == BINARY_EXPR_ITEM {
-            handle_binary_expression(child, file, mutations, &function_name)?;
+    for child in node_block.named_children(&mut cursor) {
+        handle_value(child, file, mutations, function_name, hints)?;
+    }
+    Ok(())
+}
+
+/// Mutations of a node in a position giving a value, or of the blocks nested in it
+fn handle_value(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+    hints: &TypeHints,
+) -> eyre::Result<()> {
+    match node.kind() {
+        kind if kind == BINARY_EXPR_ITEM => {
+            handle_binary_expression(node, file, mutations, function_name, hints)
+        }
+        kind if kind == BLOCK_ITEM => handle_block(node, file, mutations, function_name, hints),
+        "expression_statement" | "return_expression" | "parenthesized_expression" => {
+            match node.named_child(0) {
+                Some(value) => handle_value(value, file, mutations, function_name, hints),
+                None => Ok(()),
+            }
+        }
+        // Receivers of the methods of a chain, as `(a + b).to_string()`, and arguments of the calls
+        "call_expression" => {
+            if let Some(function) = node.child_by_field_name("function") {
+                handle_value(function, file, mutations, function_name, hints)?;
+            }
+            if let Some(arguments) = node.child_by_field_name("arguments") {
+                let mut cursor = arguments.walk();
+                for argument in arguments.named_children(&mut cursor) {
+                    handle_value(argument, file, mutations, function_name, hints)?;
+                }
+            }
+            Ok(())
+        }
+        "field_expression" => match node.child_by_field_name("value") {
+            Some(value) => handle_value(value, file, mutations, function_name, hints),
+            None => Ok(()),
+        },
+        "let_declaration" => {
+            for field in ["value", "alternative"] {
+                if let Some(value) = node.child_by_field_name(field) {
+                    handle_value(value, file, mutations, function_name, hints)?;
+                }
+            }
+            Ok(())
+        }
+        // Nested functions are mutated on their own
+        kind if kind == FUNCTION_ITEM => Ok(()),
+        _ => handle_nested_blocks(node, file, mutations, function_name, hints),
+    }
+}
+
+/// Mutations of the blocks nested in a node, as the branches of an `if` or the body of a loop
+fn handle_nested_blocks(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+    hints: &TypeHints,
+) -> eyre::Result<()> {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == BLOCK_ITEM {
+            handle_block(child, file, mutations, function_name, hints)?;
+        } else if child.kind() != FUNCTION_ITEM {
+            handle_nested_blocks(child, file, mutations, function_name, hints)?;
         }
     }
     Ok(())
 }
 
+/// Mutations of the arguments of a macro invocation, if they are comma separated expressions
+///
+/// The arguments are parsed again as the arguments of a call, then mutations are moved back to
+/// the position of the arguments in the file.
+fn handle_macro_invocation(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+    hints: &TypeHints,
+) -> eyre::Result<()> {
+    let name = node
+        .child_by_field_name("macro")
+        .map(|name| &file[name.start_byte()..name.end_byte()])
+        .unwrap_or_default();
+    let Some(token_tree) = node
+        .named_child(node.named_child_count().saturating_sub(1))
+        .filter(|token_tree| token_tree.kind() == "token_tree")
+    else {
+        return Ok(());
+    };
+    if !EXPRESSION_MACROS.contains(&name) || token_tree.end_byte() - token_tree.start_byte() < 2 {
+        return Ok(());
+    }
+    // Without delimiters
+    let arguments_start = token_tree.start_byte() + 1;
+    let arguments = &file[arguments_start..token_tree.end_byte() - 1];
+    let parser = syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated;
+    if syn::parse::Parser::parse_str(parser, arguments).is_err() {
+        log::trace!("Skip arguments of macro {name}!, not expressions");
+        return Ok(());
+    }
+
+    let prefix = "fn _darwin() { _darwin(";
+    let wrapper = format!("{prefix}{arguments}) }}");
+    let tree = parse_rust(&wrapper)?.ok_or(eyre!("Unable to parse arguments of macro {name}!"))?;
+    let Some(call) = find_node(tree.root_node(), "arguments") else {
+        return Ok(());
+    };
+
+    let mut wrapped_mutations = vec![];
+    let mut cursor = call.walk();
+    for argument in call.children(&mut cursor) {
+        if argument.kind() == BINARY_EXPR_ITEM {
+            handle_binary_expression(
+                argument,
+                &wrapper,
+                &mut wrapped_mutations,
+                function_name,
+                hints,
+            )?;
+        }
+    }
+    for mutation in wrapped_mutations {
+        let range = mutation.get_range();
+        let start = range.start - prefix.len() + arguments_start;
+        let end = range.end - prefix.len() + arguments_start;
+        mutations.push(
+            Mutation::new(
+                mutation.get_replacement(),
+                MutationChunk::from_range(file, start..end),
+            )
+            .with_reason(&mutation.reason)
+            .with_function_name(function_name)
+            .with_operator(&mutation.operator)
+            .with_context(MACRO_INVOCATION_ITEM)
+            .with_loop_control(controls_loop(node)),
+        );
+    }
+    Ok(())
+}
+
+/// First node of a kind, depth first
+fn find_node<'a>(node: tree_sitter::Node<'a>, kind: &str) -> Option<tree_sitter::Node<'a>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    let children = node.children(&mut cursor).collect::<Vec<_>>();
+    children
+        .into_iter()
+        .find_map(|child| find_node(child, kind))
+}
+
+/// Value and type suffix of a decimal integer literal, none for other literals
+fn decimal_integer(literal: &str) -> Option<(u128, &str)> {
+    let digits_end = literal
+        .find(|character: char| !character.is_ascii_digit() && character != '_')
+        .unwrap_or(literal.len());
+    let (digits, suffix) = literal.split_at(digits_end);
+    if !(suffix.is_empty() || suffix.starts_with(['i', 'u'])) {
+        return None;
+    }
+    Some((digits.replace('_', "").parse().ok()?, suffix))
+}
+
+/// Replacements of an integer or boolean literal: `0` and the next integer, the other boolean
+///
+/// The type suffix of integers is kept, integers other than decimal ones aren't replaced.
+fn literal_replacements(literal: &str) -> Vec<String> {
+    match literal {
+        "true" => return vec!["false".to_string()],
+        "false" => return vec!["true".to_string()],
+        _ => {}
+    }
+    let Some((value, suffix)) = decimal_integer(literal) else {
+        return vec![];
+    };
+    let mut replacements = vec![];
+    if value != 0 {
+        replacements.push(format!("0{suffix}"));
+    }
+    if let Some(next) = value.checked_add(1) {
+        replacements.push(format!("{next}{suffix}"));
+    }
+    replacements
+}
+
+/// Mutations of the literals bound to local variables by a `let` or a simple assignment, as
+/// `let retries = 3;` or `retries = 3;`, at any depth of a function body
+///
+/// Fields, statics and constants are left out, their literals may be used far from the function.
+fn handle_literal_bindings(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let literal = match child.kind() {
+            "let_declaration" => child
+                .child_by_field_name("pattern")
+                .filter(|pattern| matches!(pattern.kind(), "identifier" | "mut_pattern"))
+                .and_then(|_| child.child_by_field_name("value")),
+            "assignment_expression" => child
+                .child_by_field_name("left")
+                .filter(|left| left.kind() == "identifier")
+                .and_then(|_| child.child_by_field_name("right")),
+            kind if kind == FUNCTION_ITEM => continue,
+            _ => None,
+        };
+        if let Some(literal) = literal
+            .filter(|literal| matches!(literal.kind(), "integer_literal" | "boolean_literal"))
+        {
+            let original = &file[literal.start_byte()..literal.end_byte()];
+            for replacement in literal_replacements(original) {
+                mutations.push(
+                    Mutation::new(&replacement, literal)
+                        .with_reason(&format!("replace {original} by {replacement}"))
+                        .with_function_name(function_name)
+                        .with_operator(LITERAL_OPERATOR)
+                        .with_context(child.kind())
+                        .with_loop_control(controls_loop(literal)),
+                );
+            }
+        }
+        handle_literal_bindings(child, file, mutations, function_name);
+    }
+}
+
+/// Replacements of a length, one less and one more
+fn length_replacements(length: &str) -> Vec<String> {
+    if let Some((value, suffix)) = decimal_integer(length) {
+        return [value.checked_sub(1), value.checked_add(1)]
+            .into_iter()
+            .flatten()
+            .map(|value| format!("{value}{suffix}"))
+            .collect();
+    }
+    let simple = length
+        .chars()
+        .all(|character| character.is_alphanumeric() || "_:.".contains(character));
+    let length = if simple {
+        length.to_string()
+    } else {
+        format!("({length})")
+    };
+    vec![format!("{length} - 1"), format!("{length} + 1")]
+}
+
+/// Mutations of the length of a repeat expression, at a range of the file
+fn push_length_mutations(
+    file: &str,
+    range: std::ops::Range<usize>,
+    context: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &str,
+    loop_control: bool,
+) {
+    let original = &file[range.clone()];
+    for replacement in length_replacements(original) {
+        mutations.push(
+            Mutation::new(&replacement, MutationChunk::from_range(file, range.clone()))
+                .with_reason(&format!("replace length {original} by {replacement}"))
+                .with_function_name(function_name)
+                .with_operator(LENGTH_OPERATOR)
+                .with_context(context)
+                .with_loop_control(loop_control),
+        );
+    }
+}
+
+/// Range in the file of the count of a `vec![x; n]`, none for other macro invocations
+///
+/// The arguments are parsed again as an array, as the arguments of other macros are.
+fn vec_count(node: tree_sitter::Node, file: &str) -> eyre::Result<Option<std::ops::Range<usize>>> {
+    let name = node
+        .child_by_field_name("macro")
+        .map(|name| &file[name.start_byte()..name.end_byte()]);
+    let Some(token_tree) = node
+        .named_child(node.named_child_count().saturating_sub(1))
+        .filter(|token_tree| token_tree.kind() == "token_tree")
+    else {
+        return Ok(None);
+    };
+    // `vec!`, `std::vec!` or `alloc::vec!`
+    let is_vec = name.and_then(|name| name.rsplit("::").next()) == Some("vec");
+    if !is_vec || token_tree.end_byte() - token_tree.start_byte() < 2 {
+        return Ok(None);
+    }
+    let arguments_start = token_tree.start_byte() + 1;
+    let arguments = &file[arguments_start..token_tree.end_byte() - 1];
+    let prefix = "fn _darwin() { [";
+    let wrapper = format!("{prefix}{arguments}] }}");
+    let Some(tree) = parse_rust(&wrapper)? else {
+        return Ok(None);
+    };
+    if tree.root_node().has_error() {
+        return Ok(None);
+    }
+    let count = find_node(tree.root_node(), "array_expression")
+        .and_then(|array| array.child_by_field_name("length"))
+        .map(|count| {
+            count.start_byte() - prefix.len() + arguments_start
+                ..count.end_byte() - prefix.len() + arguments_start
+        });
+    Ok(count)
+}
+
+/// Mutations of the lengths of array repeat expressions, as `[0u8; 16]`, and of the counts of
+/// `vec![x; n]`, at any depth of a function body
+///
+/// Buffer sizes are classic untested boundaries, they are replaced by one less and one more.
+fn handle_repeat_lengths(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+) -> eyre::Result<()> {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "array_expression" => {
+                if let Some(length) = child.child_by_field_name("length") {
+                    push_length_mutations(
+                        file,
+                        length.start_byte()..length.end_byte(),
+                        child.kind(),
+                        mutations,
+                        function_name,
+                        controls_loop(child),
+                    );
+                }
+            }
+            kind if kind == MACRO_INVOCATION_ITEM => {
+                if let Some(count) = vec_count(child, file)? {
+                    push_length_mutations(
+                        file,
+                        count,
+                        MACRO_INVOCATION_ITEM,
+                        mutations,
+                        function_name,
+                        controls_loop(child),
+                    );
+                }
+                continue;
+            }
+            kind if kind == FUNCTION_ITEM => continue,
+            _ => {}
+        }
+        handle_repeat_lengths(child, file, mutations, function_name)?;
+    }
+    Ok(())
+}
+
+/// Literals of a pattern, itself or the ones of an or-pattern
+fn pattern_literals(pattern: tree_sitter::Node) -> Vec<tree_sitter::Node> {
+    match pattern.kind() {
+        "integer_literal" | "boolean_literal" => vec![pattern],
+        "or_pattern" => {
+            let mut cursor = pattern.walk();
+            let alternatives = pattern.named_children(&mut cursor).collect::<Vec<_>>();
+            alternatives
+                .into_iter()
+                .flat_map(pattern_literals)
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Largest value of the type of an integer literal
+///
+/// The type of an unsuffixed literal isn't known, it's bounded by the smallest type which could
+/// hold it, so that `255` isn't replaced by `256` in a match on a `u8`. `usize` and `isize` are
+/// bounded as on 32 bits targets.
+fn integer_max(value: u128, suffix: &str) -> u128 {
+    match suffix {
+        "i8" => i8::MAX as u128,
+        "u8" => u8::MAX as u128,
+        "i16" => i16::MAX as u128,
+        "u16" => u16::MAX as u128,
+        "i32" | "isize" => i32::MAX as u128,
+        "u32" | "usize" => u32::MAX as u128,
+        "i64" => i64::MAX as u128,
+        "u64" => u64::MAX as u128,
+        "i128" => i128::MAX as u128,
+        "u128" => u128::MAX,
+        _ => [
+            i8::MAX as u128,
+            u8::MAX as u128,
+            i16::MAX as u128,
+            u16::MAX as u128,
+            i32::MAX as u128,
+            u32::MAX as u128,
+            i64::MAX as u128,
+            u64::MAX as u128,
+            i128::MAX as u128,
+        ]
+        .into_iter()
+        .find(|max| value <= *max)
+        .unwrap_or(u128::MAX),
+    }
+}
+
+/// Replacement of a literal pattern matching none of the other patterns of the match: the other
+/// boolean, or the next integer not matched yet which fits the type of the literal
+fn pattern_replacement(literal: &str, matched: &[&str]) -> Option<String> {
+    let replacement = match literal {
+        "true" => "false".to_string(),
+        "false" => "true".to_string(),
+        _ => {
+            let (value, suffix) = decimal_integer(literal)?;
+            let matched = matched
+                .iter()
+                .filter_map(|literal| decimal_integer(literal).map(|(value, _)| value))
+                .collect::<HashSet<_>>();
+            let next = (value.checked_add(1)?..=integer_max(value, suffix))
+                .find(|value| !matched.contains(value))?;
+            format!("{next}{suffix}")
+        }
+    };
+    (!matched.contains(&replacement.as_str())).then_some(replacement)
+}
+
+/// Mutations of the integer and boolean literal patterns of match arms, at any depth of a
+/// function body
+///
+/// Only the arms before a `_` arm without guard are mutated, their replacement keeps the match
+/// exhaustive and matches none of the other patterns.
+fn handle_match_patterns(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "match_block" => {
+                let mut arms_cursor = child.walk();
+                // The pattern of each arm, and whether it's guarded
+                let patterns = child
+                    .named_children(&mut arms_cursor)
+                    .filter(|arm| arm.kind() == "match_arm")
+                    .filter_map(|arm| arm.child_by_field_name("pattern"))
+                    .filter_map(|pattern| {
+                        let guarded = pattern.child_by_field_name("condition").is_some();
+                        Some((pattern.child(0)?, guarded))
+                    })
+                    .collect::<Vec<_>>();
+                let wildcard = patterns
+                    .iter()
+                    .position(|(pattern, guarded)| pattern.kind() == "_" && !guarded)
+                    .unwrap_or_default();
+                let literals = patterns[..wildcard]
+                    .iter()
+                    .flat_map(|(pattern, _)| pattern_literals(*pattern))
+                    .collect::<Vec<_>>();
+                let matched = patterns
+                    .iter()
+                    .flat_map(|(pattern, _)| pattern_literals(*pattern))
+                    .map(|literal| &file[literal.start_byte()..literal.end_byte()])
+                    .collect::<Vec<_>>();
+                for literal in literals {
+                    let original = &file[literal.start_byte()..literal.end_byte()];
+                    let Some(replacement) = pattern_replacement(original, &matched) else {
+                        continue;
+                    };
+                    mutations.push(
+                        Mutation::new(&replacement, literal)
+                            .with_reason(&format!("replace pattern {original} by {replacement}"))
+                            .with_function_name(function_name)
+                            .with_operator(PATTERN_OPERATOR)
+                            .with_context("match_arm")
+                            .with_loop_control(controls_loop(literal)),
+                    );
+                }
+            }
+            kind if kind == FUNCTION_ITEM => continue,
+            _ => {}
+        }
+        handle_match_patterns(child, file, mutations, function_name);
+    }
+}
+
+/// Mutations of the variants of fieldless enums of the file used as values, as
+/// `Direction::Left`, at any depth of a function body
+///
+/// Each variant is replaced by each of its siblings. Patterns are left out, those of `match`
+/// arms, `let`, `if let`, `for` and parameters, at any depth, they don't construct the variant.
+fn handle_enum_variants(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+    enums: &HashMap<String, Vec<String>>,
+) {
+    let mut cursor = node.walk();
+    // The patterns of `let`, `if let`, `for` and parameters
+    let patterns = node
+        .children_by_field_name("pattern", &mut cursor)
+        .collect::<Vec<_>>();
+    for child in node.named_children(&mut cursor) {
+        if patterns.contains(&child) {
+            continue;
+        }
+        match child.kind() {
+            "scoped_identifier" => {
+                let text = |node: tree_sitter::Node| &file[node.start_byte()..node.end_byte()];
+                // Last segment of the path, the enum of `crate::Direction::Left`
+                let enum_name = child
+                    .child_by_field_name("path")
+                    .map(|path| match path.kind() {
+                        "scoped_identifier" => path.child_by_field_name("name").unwrap_or(path),
+                        _ => path,
+                    });
+                let variants = enum_name.and_then(|enum_name| enums.get(text(enum_name)));
+                let name = child.child_by_field_name("name");
+                if let (Some(variants), Some(name)) = (variants, name) {
+                    let original = text(name);
+                    if variants.iter().any(|variant| variant == original) {
+                        let enum_name = enum_name.map(text).unwrap_or_default();
+                        for sibling in variants.iter().filter(|variant| *variant != original) {
+                            mutations.push(
+                                Mutation::new(sibling, name)
+                                    .with_reason(&format!(
+                                        "replace {enum_name}::{original} by {enum_name}::{sibling}"
+                                    ))
+                                    .with_function_name(function_name)
+                                    .with_operator(VARIANT_OPERATOR)
+                                    .with_context(child.parent().map_or("", |parent| parent.kind()))
+                                    .with_loop_control(controls_loop(child)),
+                            );
+                        }
+                    }
+                }
+                continue;
+            }
+            "match_pattern" | "closure_parameters" => continue,
+            kind if kind == FUNCTION_ITEM => continue,
+            _ => {}
+        }
+        handle_enum_variants(child, file, mutations, function_name, enums);
+    }
+}
+
+/// Mutations of the arguments of the macro invocations of a function body, at any depth
+fn handle_macro_invocations(
+    node: tree_sitter::Node,
+    file: &str,
+    mutations: &mut Vec<Mutation>,
+    function_name: &String,
+    hints: &TypeHints,
+) -> eyre::Result<()> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == MACRO_INVOCATION_ITEM {
+            handle_macro_invocation(child, file, mutations, function_name, hints)?;
+        } else {
+            handle_macro_invocations(child, file, mutations, function_name, hints)?;
+        }
+    }
+    Ok(())
+}
+
+/// Mutants of a project and what was left out of them
+pub(crate) struct Analysis {
+    pub(crate) mutants: Vec<Mutation>,
+    /// Files which can't be mutated
+    pub(crate) skipped: Vec<SkippedFile>,
+    /// Functions never used, whose mutants are excluded
+    pub(crate) dead_code: Vec<DeadFunction>,
+    /// Number of mutants excluded by the settings
+    pub(crate) excluded: Exclusions,
+    /// Each mutant excluded, with the reason why
+    pub(crate) not_attempted: Vec<SkippedMutant>,
+}
+
+/// Outcome of the analysis of a single file
+enum FileAnalysis {
+    /// Excluded by the settings
+    Excluded,
+    /// Unreadable
+    Skipped(SkippedFile),
+    Mutated {
+        file: String,
+        /// Mutations of the file before selection
+        cached: CachedFile,
+        /// Mutations selected by the settings, mutating the file
+        prepared: Vec<Mutation>,
+        not_attempted: Vec<SkippedMutant>,
+    },
+}
+
+thread_local! {
+    /// Parser of each analysis thread, reused from a file to the next
+    static PARSER: RefCell<tree_sitter::Parser> = RefCell::new(tree_sitter::Parser::new());
+}
+
+/// Parse Rust code with the parser of the thread
+pub(crate) fn parse_rust(source: &str) -> eyre::Result<Option<tree_sitter::Tree>> {
+    PARSER.with_borrow_mut(|parser| {
+        if parser.language().is_none() {
+            parser.set_language(tree_sitter_rust::language())?;
+        }
+        Ok(parser.parse(source, None))
+    })
+}
+
+/// Find the mutations of a file, or reuse the ones of the previous analysis if it hasn't changed
+fn analyze_file(
+    path: &Path,
+    settings: &Settings,
+    plugins: &[Plugin],
+    previous_cache: &AnalysisCache,
+) -> eyre::Result<FileAnalysis> {
+    let relative_path = path.strip_prefix(&settings.root_path)?;
+    let file = relative_path.to_string_lossy().replace('\\', "/");
+    if settings.is_excluded(relative_path) {
+        log::debug!("Skip excluded file {file}");
+        return Ok(FileAnalysis::Excluded);
+    }
+    let content = match read_source(path) {
+        Ok(content) => content,
+        Err(error) => {
+            log::warn!("Skip file {file}: {error}");
+            return Ok(FileAnalysis::Skipped(SkippedFile {
+                file,
+                cause: SkipReason::Unreadable,
+                reason: error.to_string(),
+            }));
+        }
+    };
+    let file_mutants = match previous_cache.get(&file, &content) {
+        Some(file_mutants) => {
+            log::debug!("Reuse analysis of file {file}");
+            file_mutants
+        }
+        None => {
+            log::debug!("Handle file {file}");
+            match find_mutations(path, relative_path, &content, settings, plugins) {
+                Ok(file_mutants) => file_mutants,
+                // A file the compiler may never see, such as a fixture, doesn't stop the run
+                Err(error) if error.downcast_ref::<syn::Error>().is_some() => {
+                    log::warn!("Skip file {file}: {error:#}");
+                    let line = error
+                        .downcast_ref::<syn::Error>()
+                        .map_or(0, |error| error.span().start().line);
+                    return Ok(FileAnalysis::Skipped(SkippedFile {
+                        file,
+                        cause: SkipReason::ParseFailure,
+                        reason: format!("parse failure at line {line}, {}", error.root_cause()),
+                    }));
+                }
+                Err(error) => return Err(error.wrap_err("Unable to get mutations for file")),
+            }
+        }
+    };
+    let cached = CachedFile::new(&content, &file_mutants);
+    let mut not_attempted = vec![];
+    let prepared = prepare_mutations(
+        file_mutants,
+        path,
+        relative_path,
+        &content,
+        settings,
+        &mut not_attempted,
+    )?;
+    Ok(FileAnalysis::Mutated {
+        file,
+        cached,
+        prepared,
+        not_attempted,
+    })
+}
+
 /// Analyze a path
 ///
 /// Detect Rust files
 ///
 /// Generate in memory Mutations
-pub(crate) fn analyze(root_path: &PathBuf) -> eyre::Result<Vec<Mutation>> {
+pub(crate) fn analyze(settings: &Settings, plugins: &[Plugin]) -> eyre::Result<Analysis> {
+    let root_path = &settings.root_path;
     log::info!("Analyze project {}", dunce::simplified(root_path).display());
     let mut mutants = vec![];
-    let walker = get_project_walker(&root_path)?;
+    let mut skipped = vec![];
+    let mut excluded_files = 0;
+    let mut not_attempted = vec![];
+    let walker = get_project_entries(root_path, settings)?;
+    let previous_cache = AnalysisCache::load(settings);
+    let mut cache = AnalysisCache::new(settings);
 
-    for entry in walker {
-        if rust_source(&entry) {
-            let path = entry.path();
-            let mutated_files = get_mutations_for_file(path, &root_path)
-                .wrap_err("Unable to get mutations for file")?;
-            mutants.extend(mutated_files);
+    // Files are analyzed in parallel, their results merged in the order of the walk
+    let files = walker
+        .par_iter()
+        .filter(|entry| rust_source(entry) && !entry.file_type().is_symlink())
+        .map(|entry| analyze_file(entry.path(), settings, plugins, &previous_cache))
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    for analysis in files {
+        match analysis {
+            FileAnalysis::Excluded => excluded_files += 1,
+            FileAnalysis::Skipped(file) => skipped.push(file),
+            FileAnalysis::Mutated {
+                file,
+                cached,
+                prepared,
+                not_attempted: file_not_attempted,
+            } => {
+                cache.insert(&file, cached);
+                mutants.extend(prepared);
+                not_attempted.extend(file_not_attempted);
+            }
         }
     }
 
-    Ok(mutants)
+    // Mutants, and so their IDs, don't depend on the order files are walked on each platform
+    sort_mutations(&mut mutants);
+    disambiguate_stable_ids(&mut mutants)?;
+
+    if let Err(error) = cache.save(&settings.mutation_root) {
+        log::warn!("Unable to save analysis cache: {error}");
+    }
+
+    if settings.skip_unviable {
+        mutants = skip_left_out(
+            mutants,
+            SkipReason::Unviable,
+            &mut not_attempted,
+            |mutants| Ok(ViabilityStats::load(&settings.mutation_root).filter(mutants)),
+        )?;
+    }
+
+    if settings.skip_not_compiled {
+        mutants = skip_left_out(
+            mutants,
+            SkipReason::NotCompiled,
+            &mut not_attempted,
+            |mutants| skip_not_compiled(mutants, settings),
+        )?;
+    }
+
+    let mut dead_code = vec![];
+    if settings.skip_dead_code {
+        mutants = skip_left_out(
+            mutants,
+            SkipReason::DeadCode,
+            &mut not_attempted,
+            |mutants| {
+                let kept;
+                (kept, dead_code) = skip_dead_code(mutants, settings)?;
+                Ok(kept)
+            },
+        )?;
+    }
+
+    // Mutants only executed by tests which don't run would be missing anyway
+    if !settings.include_ignored {
+        if let Some(coverage) = Coverage::from_settings(settings)? {
+            mutants = skip_left_out(
+                mutants,
+                SkipReason::IgnoredTests,
+                &mut not_attempted,
+                |mutants| coverage.skip_ignored_only(mutants, &project_ignored_tests(settings)?),
+            )?;
+        }
+    }
+
+    if settings.sample < 1.0 {
+        mutants = skip_left_out(mutants, SkipReason::Sample, &mut not_attempted, |mutants| {
+            Ok(sample_mutants(mutants, settings.sample))
+        })?;
+    }
+
+    if let Some(budget) = settings.budget {
+        mutants = skip_left_out(mutants, SkipReason::Budget, &mut not_attempted, |mutants| {
+            spread_mutants(mutants, budget, settings.order)
+        })?;
+    }
+
+    for (mutation_id, mutation) in mutants.iter_mut().enumerate() {
+        mutation.set_mutation_id(mutation_id);
+    }
+
+    if !settings.mutants.is_empty() {
+        mutants = skip_left_out(mutants, SkipReason::Filter, &mut not_attempted, |mutants| {
+            select_mutants(mutants, settings)
+        })?;
+    }
+
+    Ok(Analysis {
+        mutants,
+        skipped,
+        dead_code,
+        excluded: Exclusions::new(excluded_files, &not_attempted),
+        not_attempted,
+    })
+}
+
+/// Keep the mutants returned by a step of the selection, the ones it leaves out are recorded as
+/// not attempted for the reason of the step
+fn skip_left_out(
+    mutants: Vec<Mutation>,
+    reason: SkipReason,
+    not_attempted: &mut Vec<SkippedMutant>,
+    step: impl FnOnce(Vec<Mutation>) -> eyre::Result<Vec<Mutation>>,
+) -> eyre::Result<Vec<Mutation>> {
+    let key = |mutation: &Mutation| {
+        (
+            mutation.relative_file().unwrap_or_default(),
+            mutation.get_range(),
+            mutation.operator.clone(),
+            mutation.get_replacement().to_string(),
+        )
+    };
+    let candidates = mutants
+        .iter()
+        .map(|mutation| Ok((key(mutation), SkippedMutant::new(mutation, reason)?)))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let kept = step(mutants)?;
+    // Counted, as identical mutants are left out but for one
+    let mut kept_keys = HashMap::new();
+    for mutation in &kept {
+        *kept_keys.entry(key(mutation)).or_insert(0) += 1;
+    }
+    for (key, skipped) in candidates {
+        match kept_keys.get_mut(&key) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => not_attempted.push(skipped),
+        }
+    }
+    Ok(kept)
+}
+
+/// Sort mutants by file, then span, then operator and replacement
+pub(crate) fn sort_mutations(mutants: &mut [Mutation]) {
+    mutants.sort_by_cached_key(|mutation| {
+        (
+            mutation.relative_file().unwrap_or_default(),
+            mutation.get_range().start,
+            mutation.get_range().end,
+            mutation.operator.clone(),
+            mutation.get_replacement().to_string(),
+        )
+    });
 }
 
-fn get_mutations_for_file(path: &Path, root_path: &PathBuf) -> eyre::Result<Vec<Mutation>> {
-    let relative_path = path.strip_prefix(root_path)?;
-    log::debug!("Handle file {}", relative_path.to_string_lossy());
-    let mut source_file = File::open(&path)?;
-    let mut content = String::new();
-    source_file.read_to_string(&mut content)?;
+/// Keep the mutants selected by their IDs, IDs matching no mutant are reported
+fn select_mutants(mutants: Vec<Mutation>, settings: &Settings) -> eyre::Result<Vec<Mutation>> {
+    let mut selected = vec![];
+    let mut found = HashSet::new();
+    for mutation in mutants {
+        let id = mutation.get_mutation_id();
+        let stable_id = mutation.stable_id()?;
+        if settings.mutant_selected(id, &stable_id) {
+            found.insert(id.to_string());
+            found.insert(stable_id);
+            selected.push(mutation);
+        }
+    }
+    for id in settings.mutants.iter().filter(|id| !found.contains(*id)) {
+        println!("No mutant {id}, it may have moved or been filtered out");
+    }
+    Ok(selected)
+}
 
-    let mut parser = tree_sitter::Parser::new();
-    parser.set_language(tree_sitter_rust::language())?;
+/// Keep an evenly spread fraction of the mutants
+///
+/// Deterministic, so that two runs with the same rate verify the same mutants
+fn sample_mutants(mutants: Vec<Mutation>, rate: f64) -> Vec<Mutation> {
+    let total = mutants.len();
+    let mutants = mutants
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| ((*index + 1) as f64 * rate).floor() > (*index as f64 * rate).floor())
+        .map(|(_, mutation)| mutation)
+        .collect::<Vec<_>>();
+    log::info!("Sampled {} mutants out of {total}", mutants.len());
+    mutants
+}
 
-    let tree = parser
-        .parse(&content, None)
-        .ok_or(eyre!("Unable to parse file {path:?}"))?;
+/// Items grouped by key, groups and items in order of first appearance
+fn group_by<T, K: Eq + std::hash::Hash>(items: Vec<T>, key: impl Fn(&T) -> K) -> Vec<Vec<T>> {
+    let mut indexes = HashMap::new();
+    let mut groups: Vec<Vec<T>> = vec![];
+    for item in items {
+        let index = *indexes.entry(key(&item)).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[index].push(item);
+    }
+    groups
+}
 
-    let mut root_cursor = tree.walk();
-    let mut file_mutants = vec![];
-    for (child_index, child_node) in tree.root_node().children(&mut root_cursor).enumerate() {
+/// Take items from each group in turn
+fn interleave<T>(groups: Vec<Vec<T>>) -> Vec<T> {
+    let mut groups = groups.into_iter().map(Vec::into_iter).collect::<Vec<_>>();
+    let mut items = vec![];
+    loop {
+        let before = items.len();
+        items.extend(groups.iter_mut().filter_map(Iterator::next));
+        if items.len() == before {
+            return items;
+        }
+    }
+}
+
+/// Keep at most `budget` mutants, spread across files, then functions, then operators families
+///
+/// Files are taken in turn, and in each file its functions in turn, and in each function its
+/// operators in turn, so that no file is over-sampled because it is walked first. In the complexity
+/// order, functions are rather taken whole, from the most complex one. Kept mutants stay in their
+/// order.
+fn spread_mutants(
+    mutants: Vec<Mutation>,
+    budget: usize,
+    order: Order,
+) -> eyre::Result<Vec<Mutation>> {
+    let total = mutants.len();
+    if total <= budget {
+        return Ok(mutants);
+    }
+    let mutants = mutants
+        .into_iter()
+        .enumerate()
+        .map(|(index, mutation)| Ok((index, mutation.relative_file()?, mutation)))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let files = group_by(mutants, |(_, file, _)| file.clone())
+        .into_iter()
+        .map(|file_mutants| {
+            group_by(file_mutants, |(_, _, mutation)| {
+                mutation.function_name.clone()
+            })
+            .into_iter()
+            .map(|function_mutants| {
+                interleave(group_by(function_mutants, |(_, _, mutation)| {
+                    mutation.operator.clone()
+                }))
+            })
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut mutants = match order {
+        Order::Spread => interleave(files.into_iter().map(interleave).collect()),
+        Order::Complexity => {
+            let mut functions = files.into_iter().flatten().collect::<Vec<_>>();
+            functions.sort_by_key(|function| Reverse(function[0].2.complexity));
+            functions.into_iter().flatten().collect()
+        }
+    };
+    mutants.truncate(budget);
+    mutants.sort_by_key(|(index, _, _)| *index);
+    log::info!("Spread a budget of {budget} mutants out of {total}");
+    Ok(mutants
+        .into_iter()
+        .map(|(_, _, mutation)| mutation)
+        .collect())
+}
+
+/// Content of a source file
+///
+/// Fails on files which can't be mutated: unreadable, binary, or not UTF-8 as rustc requires.
+fn read_source(path: &Path) -> eyre::Result<String> {
+    let bytes = std::fs::read(path).map_err(|error| eyre!("unreadable, {error}"))?;
+    if bytes.contains(&0) {
+        return Err(eyre!("binary file"));
+    }
+    String::from_utf8(bytes).map_err(|error| {
+        eyre!(
+            "not UTF-8, invalid byte at offset {}",
+            error.utf8_error().valid_up_to()
+        )
+    })
+}
+
+pub(crate) fn get_mutations_for_file(
+    path: &Path,
+    settings: &Settings,
+    plugins: &[Plugin],
+) -> eyre::Result<Vec<Mutation>> {
+    let relative_path = path.strip_prefix(&settings.root_path)?;
+    log::debug!(
+        "Handle file {}",
+        relative_path.to_string_lossy().replace('\\', "/")
+    );
+    let content = match read_source(path) {
+        Ok(content) => content,
+        Err(error) => {
+            log::warn!("Skip file {}: {error}", relative_path.display());
+            return Ok(vec![]);
+        }
+    };
+    let file_mutants = find_mutations(path, relative_path, &content, settings, plugins)?;
+    prepare_mutations(
+        file_mutants,
+        path,
+        relative_path,
+        &content,
+        settings,
+        &mut vec![],
+    )
+}
+
+/// Functions of a file which aren't tests, named `Trait::method` for default methods of traits
+fn mutable_functions<'a, 'syn>(
+    root: tree_sitter::Node<'a>,
+    content: &str,
+    functions: &HashMap<usize, FunctionDeclaration<'syn>>,
+    test_attributes: &[String],
+) -> eyre::Result<Vec<(tree_sitter::Node<'a>, String, FunctionDeclaration<'syn>)>> {
+    let mut mutable = vec![];
+    let mut push = |node: tree_sitter::Node<'a>, trait_name: Option<&str>| -> eyre::Result<()> {
+        let Some(declaration) = node
+            .child_by_field_name("name")
+            .and_then(|name| functions.get(&name.start_byte()))
+        else {
+            log::debug!(
+                "Skip function at line {}, not found by syn",
+                node.start_position().row + 1
+            );
+            return Ok(());
+        };
+        if is_benchmark_function(declaration.sig) {
+            log::debug!("Skip benchmark {}", declaration.sig.ident);
+            return Ok(());
+        }
+        if !is_test_function(declaration.attrs, test_attributes)? {
+            let name = match trait_name {
+                Some(trait_name) => format!("{trait_name}::{}", declaration.sig.ident),
+                None => declaration.sig.ident.to_string(),
+            };
+            mutable.push((node, name, *declaration));
+        }
+        Ok(())
+    };
+
+    let mut root_cursor = root.walk();
+    for child_node in root.children(&mut root_cursor) {
         if child_node.kind() == FUNCTION_ITEM {
-            if !check_function_is_test(&tree.root_node(), &child_node, child_index, &content)? {
-                let function_data = &content[child_node.start_byte()..child_node.end_byte()];
-                let item_fn: ItemFn = syn::parse_str(function_data)?;
-                log::debug!("-> Handle function {}", item_fn.sig.ident);
-
-                let mut cursor = tree.walk();
-                for node in child_node.children(&mut cursor) {
-                    if node.kind() == BLOCK_ITEM {
-                        handle_block(
-                            node,
-                            &content,
-                            &mut file_mutants,
-                            item_fn.sig.ident.to_string(),
-                        )?;
-                    }
+            push(child_node, None)?;
+        }
+
+        if child_node.kind() == TRAIT_ITEM {
+            let trait_name = child_node
+                .child_by_field_name("name")
+                .map(|name| &content[name.start_byte()..name.end_byte()]);
+            let Some(body) = child_node.child_by_field_name("body") else {
+                continue;
+            };
+            // Methods with a default body, other methods are function signatures
+            let mut cursor = body.walk();
+            for method in body.children(&mut cursor) {
+                if method.kind() == FUNCTION_ITEM {
+                    push(method, trait_name)?;
                 }
             }
         }
     }
+    Ok(mutable)
+}
 
-    for mutation in file_mutants.iter_mut() {
-        mutation.set_file_path(&path.to_path_buf());
-        mutation.mutate_file(&content);
+/// Content of a file and the symbols declared in it, shared by the analysis of its functions
+struct SourceFile<'a> {
+    content: &'a str,
+    /// Variants of its fieldless enums, by enum name
+    enums: HashMap<String, Vec<String>>,
+}
+
+/// Mutations of the body of a function
+fn handle_function(
+    function_node: tree_sitter::Node,
+    function_name: &String,
+    declaration: &FunctionDeclaration,
+    source: &SourceFile,
+    settings: &Settings,
+    file_mutants: &mut Vec<Mutation>,
+    function_spans: &mut Vec<FunctionSpan>,
+) -> eyre::Result<Complexity> {
+    log::debug!("-> Handle function {function_name}");
+    let SourceFile { content, enums } = source;
+    let hints = TypeHints::from_signature(declaration.sig);
+    let first_mutant = file_mutants.len();
+
+    let mut cursor = function_node.walk();
+    for node in function_node.children(&mut cursor) {
+        if node.kind() == BLOCK_ITEM {
+            function_spans.push(FunctionSpan {
+                function: function_name.clone(),
+                start: node.start_byte(),
+                end: node.end_byte(),
+            });
+            handle_block(node, content, file_mutants, function_name, &hints)?;
+            // Code in macros is skipped unless asked, it may not be an expression
+            if settings.mutate_macro_args {
+                handle_macro_invocations(node, content, file_mutants, function_name, &hints)?;
+            }
+            if settings.mutate_literals {
+                handle_literal_bindings(node, content, file_mutants, function_name);
+            }
+            handle_repeat_lengths(node, content, file_mutants, function_name)?;
+            handle_match_patterns(node, content, file_mutants, function_name);
+            handle_enum_variants(node, content, file_mutants, function_name, enums);
+        }
+    }
+    let complexity = function_complexity(function_node);
+    for mutation in &mut file_mutants[first_mutant..] {
+        mutation.public = declaration.public;
+        mutation.complexity = complexity;
+    }
+    Ok(complexity)
+}
+
+/// Complexity of a function, counting its named syntax nodes and the ones opening a branch
+fn function_complexity(function_node: tree_sitter::Node) -> Complexity {
+    let mut complexity = Complexity::default();
+    let mut cursor = function_node.walk();
+    loop {
+        let node = cursor.node();
+        if node.is_named() {
+            complexity.nodes += 1;
+        }
+        let lazy_boolean = node.kind() == "binary_expression"
+            && matches!(
+                node.child_by_field_name("operator")
+                    .map(|operator| operator.kind()),
+                Some("&&" | "||")
+            );
+        if lazy_boolean || BRANCH_KINDS.contains(&node.kind()) {
+            complexity.branches += 1;
+        }
+        // Depth first, the cursor never goes above the function
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        loop {
+            if !cursor.goto_parent() {
+                return complexity;
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Mutations of a file, before any filtering
+fn find_mutations(
+    path: &Path,
+    relative_path: &Path,
+    content: &str,
+    settings: &Settings,
+    plugins: &[Plugin],
+) -> eyre::Result<Vec<Mutation>> {
+    // The byte order mark is blanked out rather than removed, to keep offsets in the file
+    let unmarked;
+    let content = match content.strip_prefix(BYTE_ORDER_MARK) {
+        Some(rest) => {
+            unmarked = format!("{}{rest}", " ".repeat(BYTE_ORDER_MARK.len_utf8()));
+            &unmarked
+        }
+        None => content,
+    };
+
+    let tree = parse_rust(content)?.ok_or(eyre!("Unable to parse file {path:?}"))?;
+
+    let syntax = syn::parse_file(content).wrap_err(eyre!("Unable to parse file {path:?}"))?;
+    let functions = index_functions(&syntax, content);
+    let source = SourceFile {
+        content,
+        enums: index_enums(&syntax),
+    };
+
+    let mut file_mutants = vec![];
+    let mut function_spans = vec![];
+    // Visibility and complexity of the function of each span
+    let mut span_functions = vec![];
+    for (function_node, function_name, declaration) in mutable_functions(
+        tree.root_node(),
+        content,
+        &functions,
+        &settings.test_attributes,
+    )? {
+        let complexity = handle_function(
+            function_node,
+            &function_name,
+            &declaration,
+            &source,
+            settings,
+            &mut file_mutants,
+            &mut function_spans,
+        )?;
+        span_functions.resize(function_spans.len(), (declaration.public, complexity));
     }
 
+    for plugin in plugins {
+        for plugin_mutation in plugin.mutate(relative_path, content, &function_spans)? {
+            let span = function_spans.iter().position(|span| {
+                span.start <= plugin_mutation.start && plugin_mutation.end <= span.end
+            });
+            let function_name = span
+                .map(|span| function_spans[span].function.as_str())
+                .unwrap_or_default();
+            let (public, complexity) = span.map(|span| span_functions[span]).unwrap_or_default();
+            file_mutants.push(
+                Mutation::new(
+                    &plugin_mutation.replacement,
+                    MutationChunk::from_range(content, plugin_mutation.range()),
+                )
+                .with_reason(&format!("{} ({})", plugin_mutation.reason, plugin.name()))
+                .with_function_name(function_name)
+                .with_operator(plugin.name())
+                .with_public(public)
+                .with_complexity(complexity),
+            )
+        }
+    }
+
+    // Spans of parsed files are kept by the thread until invalidated
+    proc_macro2::extra::invalidate_current_thread_spans();
     Ok(file_mutants)
 }
+
+/// Keep the mutations enabled by the settings and mutate the file with them
+///
+/// Mutations left out are recorded in `not_attempted`
+fn prepare_mutations(
+    mut file_mutants: Vec<Mutation>,
+    path: &Path,
+    relative_path: &Path,
+    content: &str,
+    settings: &Settings,
+    not_attempted: &mut Vec<SkippedMutant>,
+) -> eyre::Result<Vec<Mutation>> {
+    for mutation in file_mutants.iter_mut() {
+        mutation.set_file_path(path, relative_path);
+    }
+
+    let file_mutants = skip_left_out(file_mutants, SkipReason::Filter, not_attempted, |mutants| {
+        Ok(mutants
+            .into_iter()
+            .filter(|mutation| {
+                settings.operator_selected(&mutation.operator)
+                    && settings.function_selected(&mutation.function_name)
+                    && settings.visibility.matches(mutation.public)
+                    && settings.line_selected(relative_path, mutation.chunk.start_point.row + 1)
+                    && content.get(mutation.get_range()).is_none_or(|original| {
+                        settings.replacement_selected(
+                            &mutation.operator,
+                            original,
+                            mutation.get_replacement(),
+                        )
+                    })
+            })
+            .collect())
+    })?;
+
+    let mut file_mutants =
+        skip_left_out(file_mutants, SkipReason::Config, not_attempted, |mutants| {
+            Ok(mutants
+                .into_iter()
+                .filter(|mutation| settings.operator_allowed(relative_path, &mutation.operator))
+                .collect())
+        })?;
+
+    let source = Arc::from(content);
+    for mutation in file_mutants.iter_mut() {
+        mutation.mutate_file(&source);
+    }
+
+    skip_left_out(
+        file_mutants,
+        SkipReason::Equivalent,
+        not_attempted,
+        |mutants| Ok(dedupe_mutations(mutants, content)),
+    )
+}
+
+/// Keep a single mutant per mutated content, and none leaving the content unchanged
+///
+/// Contents are compared by hash, mutants with the same hash are only collapsed when they replace
+/// the same range by the same text or when their contents are indeed the same.
+fn dedupe_mutations(file_mutants: Vec<Mutation>, content: &str) -> Vec<Mutation> {
+    let total = file_mutants.len();
+    let original = fnv1a(content.as_bytes());
+    // Mutants kept, by hash of their content
+    let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut kept: Vec<Mutation> = vec![];
+    for mutation in file_mutants {
+        let Ok(mutated) = mutation.get_mutated_file() else {
+            kept.push(mutation);
+            continue;
+        };
+        let hash = fnv1a(mutated.as_bytes());
+        if hash == original && mutated == content {
+            continue;
+        }
+        let same = seen.get(&hash).into_iter().flatten().any(|&index| {
+            let other = &kept[index];
+            (other.get_range() == mutation.get_range()
+                && other.get_replacement() == mutation.get_replacement())
+                || other.get_mutated_file().is_ok_and(|other| other == mutated)
+        });
+        if !same {
+            seen.entry(hash).or_default().push(kept.len());
+            kept.push(mutation);
+        }
+    }
+    if kept.len() < total {
+        log::info!("Collapse {} identical mutants", total - kept.len());
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::Path;
+
+    use clap::Parser;
+
+    use crate::actions::analyze::{
+        analyze, controls_loop, dedupe_mutations, find_node, function_complexity, handle_block,
+        handle_enum_variants, handle_literal_bindings, handle_macro_invocations,
+        handle_match_patterns, handle_repeat_lengths, ignored_tests, index_enums, index_functions,
+        length_replacements, literal_replacements, mutable_functions, parse_rust,
+        pattern_replacement, read_source, spread_mutants, BLOCK_ITEM, BYTE_ORDER_MARK,
+    };
+    use crate::actions::type_hints::TypeHints;
+    use crate::cli::{Cli, Order, VerifyArgs};
+    use crate::config::Settings;
+    use crate::mutation::{Complexity, Mutation, MutationChunk};
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_analyze_skips_mutation_root() {
+        let project = ScratchFolder::new("kept-run");
+        let lib = "pub fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let run = project.join("tmp").join("20261016-202950-21948");
+        // A kept mutant project and a source generated in a warmed target folder of a past run
+        let kept = run.join("mutant_src-lib-add-2-7-sub_ok").join("src");
+        let generated = run.join("targets/0/debug/build/a-0123/out");
+        for folder in [project.join("src"), kept.clone(), generated.clone()] {
+            std::fs::create_dir_all(folder).unwrap();
+        }
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"kept\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        for path in [project.join("src"), kept] {
+            std::fs::write(path.join("lib.rs"), lib).unwrap();
+        }
+        std::fs::write(generated.join("generated.rs"), lib).unwrap();
+
+        let args = ["cargo", "darwin", "--mutation-path"]
+            .map(OsString::from)
+            .into_iter()
+            .chain([project.join("tmp").into(), project.as_os_str().into()]);
+        let Cli::Darwin(darwin) = Cli::try_parse_from(args).unwrap();
+        let settings = Settings::new(darwin.run.project, VerifyArgs::default()).unwrap();
+        let analysis = analyze(&settings, &[]).unwrap();
+
+        let files = analysis
+            .mutants
+            .iter()
+            .map(|mutation| mutation.relative_file().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec!["src/lib.rs", "src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_read_source() {
+        let folder = ScratchFolder::new("sources");
+        let read = |content: &[u8]| {
+            let path = folder.join("lib.rs");
+            std::fs::write(&path, content).unwrap();
+            read_source(&path).map_err(|error| error.to_string())
+        };
+
+        let source = read(b"fn a() {}\n");
+        let bom = read(format!("{BYTE_ORDER_MARK}fn a() {{}}\n").as_bytes());
+        let binary = read(b"\x7fELF\0\0\x01");
+        let latin1 = read(b"// caf\xe9\nfn a() {}\n");
+        let missing = read_source(&folder.join("missing.rs")).map_err(|error| error.to_string());
+
+        assert_eq!(source.unwrap(), "fn a() {}\n");
+        // The byte order mark is kept, spans of the analysis are shifted by it
+        assert_eq!(bom.unwrap(), format!("{BYTE_ORDER_MARK}fn a() {{}}\n"));
+        assert_eq!(binary.unwrap_err(), "binary file");
+        assert_eq!(latin1.unwrap_err(), "not UTF-8, invalid byte at offset 6");
+        assert!(missing.unwrap_err().starts_with("unreadable, "));
+    }
+
+    #[test]
+    fn test_dedupe_mutations() {
+        let content = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let mutants = ["-", "-", "+", "*"]
+            .into_iter()
+            .map(|replacement| {
+                let mut mutation =
+                    Mutation::new(replacement, MutationChunk::from_range(content, 35..36));
+                mutation.mutate_file(&content.into());
+                mutation
+            })
+            .collect();
+        let mutants = dedupe_mutations(mutants, content);
+        assert_eq!(
+            mutants
+                .iter()
+                .map(Mutation::get_replacement)
+                .collect::<Vec<_>>(),
+            vec!["-", "*"]
+        );
+
+        // Overlapping spans mutated the same way
+        let mutants = [("-", 35..36), ("x - y", 33..38), ("x * y", 33..38)]
+            .into_iter()
+            .map(|(replacement, range)| {
+                let mut mutation =
+                    Mutation::new(replacement, MutationChunk::from_range(content, range));
+                mutation.mutate_file(&content.into());
+                mutation
+            })
+            .collect();
+        let mutants = dedupe_mutations(mutants, content);
+        assert_eq!(
+            mutants
+                .iter()
+                .map(Mutation::get_replacement)
+                .collect::<Vec<_>>(),
+            vec!["-", "x * y"]
+        );
+    }
+
+    #[test]
+    fn test_macro_arguments() {
+        let content =
+            "fn f(x: u8) {\n    println!(\"{}\", x + 1);\n    let v = vec![x - 1; 2];\n}\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(tree_sitter_rust::language()).unwrap();
+        let tree = parser.parse(content, None).unwrap();
+        let mut mutations = vec![];
+        handle_macro_invocations(
+            tree.root_node(),
+            content,
+            &mut mutations,
+            &"f".to_string(),
+            &TypeHints::default(),
+        )
+        .unwrap();
+        let replaced = mutations
+            .iter()
+            .map(|mutation| (&content[mutation.get_range()], mutation.get_replacement()))
+            .collect::<Vec<_>>();
+        assert_eq!(replaced, vec![("+", "-"), ("+", "*")]);
+        assert_eq!(mutations[0].context, "macro_invocation");
+    }
+
+    #[test]
+    fn test_value_positions() {
+        let content = "fn f(x: u8) -> u8 {\n    let a = x + 1;\n    if x - 2 > 0 {\n        return a - 3;\n    }\n    let Some(b) = Some(x + 4) else { return x - 5 };\n    fn g(y: u8) -> u8 { y + 6 }\n    { if a > 1 { a + 7 } else { a - 8 } }\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let body = find_node(tree.root_node(), BLOCK_ITEM).unwrap();
+        let mut mutations = vec![];
+        handle_block(
+            body,
+            content,
+            &mut mutations,
+            &"f".to_string(),
+            &TypeHints::default(),
+        )
+        .unwrap();
+        let mut mutated = mutations
+            .iter()
+            .map(|mutation| {
+                let range = mutation.get_range();
+                content[range.start..range.end + 2].to_string()
+            })
+            .collect::<Vec<_>>();
+        mutated.dedup();
+        // Not in the condition of the `if`, not in `g`
+        assert_eq!(mutated, vec!["+ 1", "- 3", "+ 4", "- 5", "+ 7", "- 8"]);
+        assert_eq!(mutations[0].context, "let_declaration");
+        assert_eq!(mutations[2].context, "return_expression");
+    }
+
+    #[test]
+    fn test_call_positions() {
+        let content = "fn f(a: u8, b: u8) -> String {\n    let c = (a + b).to_string();\n    g(a - b).h(a + 1).len() + (c.len() - 2)\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let body = find_node(tree.root_node(), BLOCK_ITEM).unwrap();
+        let mut mutations = vec![];
+        handle_block(
+            body,
+            content,
+            &mut mutations,
+            &"f".to_string(),
+            &TypeHints::default(),
+        )
+        .unwrap();
+        let mut mutated = mutations
+            .iter()
+            .map(|mutation| {
+                let range = mutation.get_range();
+                content[range.start - 2..range.end + 2].to_string()
+            })
+            .collect::<Vec<_>>();
+        mutated.dedup();
+        assert_eq!(mutated, vec!["a + b", "a - b", "a + 1", ") + (", ") - 2"]);
+        assert_eq!(mutations[0].context, "parenthesized_expression");
+    }
+
+    #[test]
+    fn test_literal_replacements() {
+        assert_eq!(literal_replacements("3"), vec!["0", "4"]);
+        assert_eq!(literal_replacements("0"), vec!["1"]);
+        assert_eq!(literal_replacements("1_000u32"), vec!["0u32", "1001u32"]);
+        assert_eq!(literal_replacements("true"), vec!["false"]);
+        assert!(literal_replacements("0xff").is_empty());
+    }
+
+    #[test]
+    fn test_literal_bindings() {
+        let content = "fn f(p: &mut P) -> u8 {\n    let retries = 3;\n    let mut done = false;\n    let (a, b) = (1, 2);\n    while !done { done = true; p.count = 5; }\n    const LIMIT: u8 = 7;\n    retries + 8\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let mut mutations = vec![];
+        let body = find_node(tree.root_node(), BLOCK_ITEM).unwrap();
+        handle_literal_bindings(body, content, &mut mutations, &"f".to_string());
+        let replaced = mutations
+            .iter()
+            .map(|mutation| (&content[mutation.get_range()], mutation.get_replacement()))
+            .collect::<Vec<_>>();
+        // Not the tuple, the field nor the constant
+        assert_eq!(
+            replaced,
+            vec![("3", "0"), ("3", "4"), ("false", "true"), ("true", "false")]
+        );
+        assert_eq!(mutations[0].operator, "literal");
+        assert!(mutations[3].loop_control);
+    }
+
+    #[test]
+    fn test_length_replacements() {
+        assert_eq!(length_replacements("16"), vec!["15", "17"]);
+        assert_eq!(length_replacements("0usize"), vec!["1usize"]);
+        assert_eq!(length_replacements("SIZE"), vec!["SIZE - 1", "SIZE + 1"]);
+        assert_eq!(
+            length_replacements("n * 2"),
+            vec!["(n * 2) - 1", "(n * 2) + 1"]
+        );
+    }
+
+    #[test]
+    fn test_repeat_lengths() {
+        let content = "fn f(n: usize) -> usize {\n    let buffer = [0u8; 16];\n    let list = [1, 2];\n    let counts = vec![0; n];\n    let items = vec![1, 2];\n    let inner = std::vec![[0; 4]; 2];\n    buffer.len() + list.len() + counts.len() + items.len() + inner.len()\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let mut mutations = vec![];
+        let body = find_node(tree.root_node(), BLOCK_ITEM).unwrap();
+        handle_repeat_lengths(body, content, &mut mutations, &"f".to_string()).unwrap();
+        let replaced = mutations
+            .iter()
+            .map(|mutation| (&content[mutation.get_range()], mutation.get_replacement()))
+            .collect::<Vec<_>>();
+        // Not the array and vec of elements, nor the inner array of a macro's arguments
+        assert_eq!(
+            replaced,
+            vec![
+                ("16", "15"),
+                ("16", "17"),
+                ("n", "n - 1"),
+                ("n", "n + 1"),
+                ("2", "1"),
+                ("2", "3")
+            ]
+        );
+        assert_eq!(mutations[0].operator, "length");
+        assert_eq!(mutations[2].context, "macro_invocation");
+    }
+
+    #[test]
+    fn test_enum_variants() {
+        let content = "enum Direction { Left, Right, Up }\nenum Shape { Dot, Line(u8) }\nenum Unit { One }\nmod nested { enum Turn { Clockwise, Counter } }\nfn f(d: Direction, t: Option<Direction>) -> Direction {\n    if let Direction::Up = d { return crate::Direction::Up; }\n    let Some(Direction::Left) = t else { return Direction::Right };\n    let g = |(Direction::Up, _): (Direction, u8)| 0;\n    for (Direction::Left, _) in [] {}\n    match d {\n        Direction::Left => Direction::Right,\n        _ => Shape::Dot.into(),\n    }\n}\n";
+        let syntax = syn::parse_file(content).unwrap();
+        let enums = index_enums(&syntax);
+        // Not the enum with fields nor the one with a single variant
+        let mut names = enums.keys().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["Direction", "Turn"]);
+
+        let tree = parse_rust(content).unwrap().unwrap();
+        let mut mutations = vec![];
+        let body = find_node(tree.root_node(), BLOCK_ITEM).unwrap();
+        handle_enum_variants(body, content, &mut mutations, &"f".to_string(), &enums);
+        let replaced = mutations
+            .iter()
+            .map(|mutation| (&content[mutation.get_range()], mutation.get_replacement()))
+            .collect::<Vec<_>>();
+        // Not in the patterns
+        assert_eq!(
+            replaced,
+            vec![
+                ("Up", "Left"),
+                ("Up", "Right"),
+                ("Right", "Left"),
+                ("Right", "Up"),
+                ("Right", "Left"),
+                ("Right", "Up")
+            ]
+        );
+        assert_eq!(mutations[0].operator, "variant");
+        assert_eq!(mutations[4].context, "match_arm");
+    }
+
+    #[test]
+    fn test_pattern_replacement() {
+        assert_eq!(pattern_replacement("1", &["1", "2", "4"]).unwrap(), "3");
+        assert_eq!(pattern_replacement("7u8", &["7u8"]).unwrap(), "8u8");
+        assert_eq!(pattern_replacement("true", &["true"]).unwrap(), "false");
+        assert_eq!(pattern_replacement("true", &["true", "false"]), None);
+        assert_eq!(pattern_replacement("0x1", &["0x1"]), None);
+        assert_eq!(pattern_replacement("255u8", &["255u8"]), None);
+        assert_eq!(pattern_replacement("254u8", &["254u8"]).unwrap(), "255u8");
+        assert_eq!(pattern_replacement("254u8", &["254u8", "255u8"]), None);
+        assert_eq!(pattern_replacement("127", &["127"]), None);
+        assert_eq!(pattern_replacement("128", &["128"]).unwrap(), "129");
+        let max = u128::MAX.to_string();
+        assert_eq!(pattern_replacement(&max, &[&max]), None);
+    }
+
+    #[test]
+    fn test_match_patterns() {
+        let content = "fn f(n: u8, b: bool) -> u8 {\n    let m = match n {\n        1 | 3 => 10,\n        2 if b => 20,\n        _ => 30,\n    };\n    let c = match b {\n        true => 1,\n        false => 0,\n    };\n    match n {\n        0 => 5,\n        x => x,\n    } + m + c\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let mut mutations = vec![];
+        let body = find_node(tree.root_node(), BLOCK_ITEM).unwrap();
+        handle_match_patterns(body, content, &mut mutations, &"f".to_string());
+        let replaced = mutations
+            .iter()
+            .map(|mutation| (&content[mutation.get_range()], mutation.get_replacement()))
+            .collect::<Vec<_>>();
+        // The next integer not matched yet, nothing in the matches without `_` arm
+        assert_eq!(replaced, vec![("1", "4"), ("3", "4"), ("2", "4")]);
+        assert_eq!(mutations[0].operator, "pattern");
+        assert_eq!(mutations[0].context, "match_arm");
+    }
+
+    #[test]
+    fn test_loop_control() {
+        let content = "fn f(n: u8) -> u8 {\n    let mut i = n + 1;\n    while i < n * 2 { i = i - 1; }\n    for j in 0..n + 1 { i = j * 2; }\n    loop { if i > 3 - n { break; } }\n    let g = |x: u8| x - 1;\n    i\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let controls = |operator: &str| {
+            content
+                .match_indices(operator)
+                .map(|(start, _)| {
+                    let node = tree
+                        .root_node()
+                        .descendant_for_byte_range(start, start + 1)
+                        .unwrap();
+                    controls_loop(node)
+                })
+                .collect::<Vec<_>>()
+        };
+        // Before the loops, in the range of the `for`
+        assert_eq!(controls(" + "), vec![false, true]);
+        // In the `while`, in the `loop`, in a closure
+        assert_eq!(controls(" - "), vec![true, true, false]);
+        // In the condition of the `while`, in the body of the `for`
+        assert_eq!(controls(" * "), vec![true, false]);
+    }
+
+    #[test]
+    fn test_trait_default_methods() {
+        let content = "trait Shape {\n    fn area(&self) -> u8;\n    fn double(&self) -> u8 {\n        self.area() + self.area()\n    }\n}\n\nfn free() {}\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(tree_sitter_rust::language()).unwrap();
+        let tree = parser.parse(content, None).unwrap();
+        let syntax = syn::parse_file(content).unwrap();
+        let functions = index_functions(&syntax, content);
+        let functions = mutable_functions(tree.root_node(), content, &functions, &[]).unwrap();
+        let names = functions
+            .iter()
+            .map(|(_, name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Shape::double", "free"]);
+    }
+
+    #[test]
+    fn test_generic_functions() {
+        let content = "#!/usr/bin/env run-cargo-script\nfn max<T>(a: T, b: T) -> T\nwhere\n    T: PartialOrd + Copy,\n{\n    if a > b { a } else { b }\n}\n\n#[cfg(test)]\n#[\n    test\n]\nfn check() {}\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(tree_sitter_rust::language()).unwrap();
+        let tree = parser.parse(content, None).unwrap();
+        let syntax = syn::parse_file(content).unwrap();
+        let functions = index_functions(&syntax, content);
+        let functions =
+            mutable_functions(tree.root_node(), content, &functions, &["test".to_string()])
+                .unwrap();
+        let names = functions
+            .iter()
+            .map(|(_, name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["max"]);
+
+        // Offsets shifted by a byte order mark
+        let marked = format!("{BYTE_ORDER_MARK}{content}");
+        let syntax = syn::parse_file(&marked).unwrap();
+        let functions = index_functions(&syntax, &marked);
+        let max = marked.find("max").unwrap();
+        assert_eq!(functions[&max].sig.ident, "max");
+    }
+
+    #[test]
+    fn test_test_detection() {
+        let content = "/// Doc\n#[test]\n#[ignore]\nfn a() {}\n\n#[ignore]\n/// Doc\n#[test]\nfn b() {}\n\n#[tokio::test(flavor = \"multi_thread\")]\nasync fn c() {}\n\n// Comment\n#[inline]\nfn d() {}\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(tree_sitter_rust::language()).unwrap();
+        let tree = parser.parse(content, None).unwrap();
+        let syntax = syn::parse_file(content).unwrap();
+        let functions = index_functions(&syntax, content);
+        let test_attributes = ["test".to_string(), "tokio::test".to_string()];
+        let functions =
+            mutable_functions(tree.root_node(), content, &functions, &test_attributes).unwrap();
+        let names = functions
+            .iter()
+            .map(|(_, name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["d"]);
+
+        let content = format!("{content}\nmod tests {{\n    #[test]\n    #[ignore]\n    fn e() {{}}\n\n    #[ignore]\n    fn f() {{}}\n}}\n");
+        let mut ignored = vec![];
+        ignored_tests(
+            &syn::parse_file(&content).unwrap().items,
+            "",
+            &test_attributes,
+            &mut ignored,
+        )
+        .unwrap();
+        assert_eq!(ignored, vec!["a", "b", "tests::e"]);
+    }
+
+    #[test]
+    fn test_function_visibility() {
+        let content =
+            "pub fn a() {}\nfn b() {}\npub(crate) fn c() {}\npub trait T {\n    fn d() {}\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let syntax = syn::parse_file(content).unwrap();
+        let functions = index_functions(&syntax, content);
+        let functions = mutable_functions(tree.root_node(), content, &functions, &[]).unwrap();
+        let visibilities = functions
+            .iter()
+            .map(|(_, name, declaration)| (name.as_str(), declaration.public))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            visibilities,
+            vec![("a", true), ("b", false), ("c", false), ("T::d", true)]
+        );
+    }
+
+    #[test]
+    fn test_benchmark_detection() {
+        let content = "fn a(c: &mut Criterion) {}\n\nfn b(b: &mut test::Bencher) {}\n\nfn c(group: &mut BenchmarkGroup<'_, WallTime>) {}\n\nfn d(criterion: Criterion) {}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let syntax = syn::parse_file(content).unwrap();
+        let functions = index_functions(&syntax, content);
+        let functions = mutable_functions(tree.root_node(), content, &functions, &[]).unwrap();
+        let names = functions
+            .iter()
+            .map(|(_, name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["d"]);
+    }
+
+    #[test]
+    fn test_spread_mutants() {
+        let content = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let mutant = |file: &str, function: &str, operator: &str| {
+            let branches = if function == "g" { 2 } else { 0 };
+            let mut mutation = Mutation::new("-", MutationChunk::from_range(content, 35..36))
+                .with_function_name(function)
+                .with_operator(operator)
+                .with_complexity(Complexity {
+                    branches,
+                    nodes: 10,
+                });
+            mutation.set_file_path(Path::new(file), Path::new(file));
+            mutation
+        };
+        let mutants = || {
+            vec![
+                mutant("src/a.rs", "f", "arithmetic"),
+                mutant("src/a.rs", "f", "arithmetic"),
+                mutant("src/a.rs", "f", "literal"),
+                mutant("src/a.rs", "g", "arithmetic"),
+                mutant("src/a.rs", "g", "arithmetic"),
+                mutant("src/b.rs", "h", "arithmetic"),
+                mutant("src/b.rs", "h", "arithmetic"),
+            ]
+        };
+        let kept = |order| {
+            spread_mutants(mutants(), 4, order)
+                .unwrap()
+                .iter()
+                .map(|mutation| {
+                    format!(
+                        "{} {} {}",
+                        mutation.relative_file().unwrap(),
+                        mutation.function_name,
+                        mutation.operator
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kept(Order::Spread),
+            vec![
+                "src/a.rs f arithmetic",
+                "src/a.rs g arithmetic",
+                "src/b.rs h arithmetic",
+                "src/b.rs h arithmetic",
+            ]
+        );
+        assert_eq!(
+            kept(Order::Complexity),
+            vec![
+                "src/a.rs f arithmetic",
+                "src/a.rs f literal",
+                "src/a.rs g arithmetic",
+                "src/a.rs g arithmetic",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_complexity() {
+        let content = "fn a(x: u8) -> u8 {\n    x + 1\n}\n\nfn b(x: Option<u8>) -> Option<u8> {\n    if x? > 1 && x? < 5 {\n        return None;\n    }\n    match x {\n        Some(0) => None,\n        _ => x,\n    }\n}\n";
+        let tree = parse_rust(content).unwrap().unwrap();
+        let mut cursor = tree.root_node().walk();
+        let complexities = tree
+            .root_node()
+            .children(&mut cursor)
+            .map(function_complexity)
+            .collect::<Vec<_>>();
+        assert_eq!(complexities[0].branches, 0);
+        // `if`, `&&`, two `?` and two match arms
+        assert_eq!(complexities[1].branches, 6);
+        assert!(complexities[1].nodes > complexities[0].nodes);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use eyre::{eyre, WrapErr};
+
+use crate::actions::cache::content_hash;
+use crate::actions::reporting::results::RunResults;
+use crate::mutation::MutationRecord;
+use crate::report::MutationStatus;
+
+/// Start of the comments marking surviving mutants
+static MARKER: &str = "// DARWIN:";
+
+/// Source with a comment above each line where mutants survived, indented as the line
+fn annotate_source(content: &str, survivors: &[&MutationRecord]) -> String {
+    let mut annotations: BTreeMap<usize, Vec<&MutationRecord>> = BTreeMap::new();
+    for mutation in survivors {
+        annotations.entry(mutation.line).or_default().push(mutation);
+    }
+    let mut annotated = String::with_capacity(content.len() + survivors.len() * 80);
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if let Some(mutations) = annotations.get_mut(&(index + 1)) {
+            mutations.sort_by_key(|mutation| mutation.column);
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let ending = if line.ends_with("\r\n") { "\r\n" } else { "\n" };
+            for mutation in mutations {
+                annotated.push_str(&format!(
+                    "{indent}{MARKER} survived {} (mutant {}){ending}",
+                    mutation.reason, mutation.stable_id
+                ));
+            }
+        }
+        annotated.push_str(line);
+    }
+    annotated
+}
+
+/// Patch adding the annotations of a file, applicable with `git apply`
+///
+/// `file` is relative to the project, the patch is relative to the root of its workspace, where
+/// `member_path` leads to the project.
+fn annotation_patch(member_path: &Path, file: &str, content: &str, annotated: &str) -> String {
+    let input = imara_diff::intern::InternedInput::new(content, annotated);
+    let diff = imara_diff::diff(
+        imara_diff::Algorithm::Myers,
+        &input,
+        imara_diff::UnifiedDiffBuilder::new(&input),
+    );
+    let file = member_path.join(file).to_string_lossy().replace('\\', "/");
+    format!("--- a/{file}\n+++ b/{file}\n{diff}")
+}
+
+/// Annotate the files of the project where mutants of the last run survived
+///
+/// Annotated copies are written in `output`, or else a patch relative to the workspace root is
+/// printed. Files changed since the run are left out, their lines may have moved.
+pub(crate) fn annotate(
+    project_path: &Path,
+    member_path: &Path,
+    mutation_root: &Path,
+    output: &Path,
+    patch: bool,
+) -> eyre::Result<()> {
+    let results = RunResults::load(mutation_root)?;
+    let mut files: BTreeMap<&str, Vec<&MutationRecord>> = BTreeMap::new();
+    for result in &results.mutants {
+        if result.status == MutationStatus::Success {
+            files
+                .entry(&result.mutation.file)
+                .or_default()
+                .push(&result.mutation);
+        }
+    }
+    if files.is_empty() {
+        println!("No mutant survived the last run, nothing to annotate");
+        return Ok(());
+    }
+
+    for (file, survivors) in files {
+        let path = project_path.join(file);
+        let content =
+            std::fs::read_to_string(&path).wrap_err(eyre!("Unable to read {}", path.display()))?;
+        if results
+            .hashes
+            .get(file)
+            .is_some_and(|hash| *hash != content_hash(&content))
+        {
+            println!("Skip {file}, changed since the last run");
+            continue;
+        }
+        let annotated = annotate_source(&content, &survivors);
+        if patch {
+            print!(
+                "{}",
+                annotation_patch(member_path, file, &content, &annotated)
+            );
+            continue;
+        }
+        let annotated_path = output.join(file);
+        if let Some(parent) = annotated_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&annotated_path, annotated)
+            .wrap_err(eyre!("Unable to write {}", annotated_path.display()))?;
+        println!(
+            "{file}: {} surviving mutants, annotated in {}",
+            survivors.len(),
+            annotated_path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::annotate::{annotate_source, annotation_patch};
+    use crate::mutation::MutationRecord;
+
+    #[test]
+    fn test_annotate_source() {
+        let mutant = |line: usize, column: usize, reason: &str, stable_id: &str| MutationRecord {
+            id: 0,
+            stable_id: stable_id.to_string(),
+            file: "src/lib.rs".to_string(),
+            function: "add".to_string(),
+            operator: "arithmetic".to_string(),
+            reason: reason.to_string(),
+            line,
+            column,
+            end_line: line,
+            end_column: column + 1,
+            start: 0,
+            end: 0,
+            original: "+".to_string(),
+            replacement: "-".to_string(),
+            context: "".to_string(),
+            loop_control: false,
+        };
+        let content = "fn add(x: u8, y: u8) -> u8 {\n    x + y + 1\n}\n";
+        let survivors = [
+            mutant(2, 11, "replace + by *", "src-lib-add-2-11-mul"),
+            mutant(2, 7, "replace + by -", "src-lib-add-2-7-sub"),
+        ];
+        let annotated = annotate_source(content, &survivors.iter().collect::<Vec<_>>());
+        assert_eq!(
+            annotated,
+            "fn add(x: u8, y: u8) -> u8 {\n    \
+             // DARWIN: survived replace + by - (mutant src-lib-add-2-7-sub)\n    \
+             // DARWIN: survived replace + by * (mutant src-lib-add-2-11-mul)\n    \
+             x + y + 1\n}\n"
+        );
+        assert_eq!(
+            annotation_patch(Path::new(""), "src/lib.rs", content, &annotated),
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,5 @@\n \
+             fn add(x: u8, y: u8) -> u8 {\n\
+             +    // DARWIN: survived replace + by - (mutant src-lib-add-2-7-sub)\n\
+             +    // DARWIN: survived replace + by * (mutant src-lib-add-2-11-mul)\n     \
+             x + y + 1\n }\n"
+        );
+        // Relative to the workspace root
+        assert!(
+            annotation_patch(Path::new("crates/math"), "src/lib.rs", content, &annotated)
+                .starts_with("--- a/crates/math/src/lib.rs\n+++ b/crates/math/src/lib.rs\n")
+        );
+    }
+}
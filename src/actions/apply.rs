@@ -0,0 +1,122 @@
+use std::io::Write;
+use std::path::Path;
+
+use eyre::{eyre, WrapErr};
+
+use crate::actions::reporting::results::RunResults;
+use crate::actions::worktree::ensure_clean;
+use crate::mutation::MutationRecord;
+
+fn confirm(question: &str) -> eyre::Result<bool> {
+    print!("{question} [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Swap `from` by `to` at the mutation position, checking the file hasn't changed
+fn swap(file_path: &Path, mutation: &MutationRecord, from: &str, to: &str) -> eyre::Result<()> {
+    let mut content = std::fs::read_to_string(file_path)
+        .wrap_err(eyre!("Unable to read {}", file_path.display()))?;
+    let range = mutation.start..mutation.start + from.len();
+    if content.get(range.clone()) != Some(from) {
+        return Err(eyre!(
+            "{} has changed since the run, `{from}` not found at line {}:{}",
+            mutation.file,
+            mutation.line,
+            mutation.column
+        ));
+    }
+    content.replace_range(range, to);
+    std::fs::write(file_path, content)?;
+    Ok(())
+}
+
+/// Apply a mutant of the last run on the working tree, or revert it
+///
+/// A mutant is only applied on a clean working tree unless `allow_dirty` is set, reverting it
+/// leaves the tree dirty by design.
+pub(crate) fn apply(
+    project_path: &Path,
+    mutation_root: &Path,
+    id: &str,
+    revert: bool,
+    yes: bool,
+    allow_dirty: bool,
+) -> eyre::Result<()> {
+    let results = RunResults::load(mutation_root)?;
+    let mutation = &results
+        .find(id)
+        .ok_or(eyre!("No mutant {id} in the last run"))?
+        .mutation;
+    let file_path = project_path.join(&mutation.file);
+
+    let (action, from, to) = if revert {
+        ("Revert", &mutation.replacement, &mutation.original)
+    } else {
+        ("Apply", &mutation.original, &mutation.replacement)
+    };
+
+    if !revert {
+        ensure_clean(project_path, Path::new(&mutation.file), allow_dirty)?;
+    }
+
+    let question = format!(
+        "{action} mutant #{} ({}) on {} at line {}:{}?",
+        mutation.id, mutation.reason, mutation.file, mutation.line, mutation.column
+    );
+    if !yes && !confirm(&question)? {
+        println!("Aborted");
+        return Ok(());
+    }
+
+    swap(&file_path, mutation, from, to)?;
+    if revert {
+        println!("Mutant #{} reverted", mutation.id);
+    } else {
+        println!(
+            "Mutant #{} applied, revert it with `cargo darwin apply --revert {}`",
+            mutation.id, mutation.stable_id
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::apply::apply;
+    use crate::actions::reporting::results::{RunResults, SUMMARY_V0};
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_apply_and_revert() {
+        let folder = ScratchFolder::new("apply");
+        let project_path = folder.join("project");
+        let mutation_root = folder.join("darwin");
+        std::fs::create_dir_all(project_path.join("src")).unwrap();
+        std::fs::create_dir_all(&mutation_root).unwrap();
+        let source = "pub fn add(x: u32, y: u32) -> u32 {\n    x + y\n}\n";
+        let file_path = project_path.join("src").join("lib.rs");
+        std::fs::write(&file_path, source).unwrap();
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        results.mutants[0].mutation.start = source.find('+').unwrap();
+        results.save(&mutation_root).unwrap();
+
+        let id = "src-lib-add-5-7-sub";
+        apply(&project_path, &mutation_root, id, false, true, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "pub fn add(x: u32, y: u32) -> u32 {\n    x - y\n}\n"
+        );
+        let error = apply(&project_path, &mutation_root, id, false, true, true).unwrap_err();
+        assert!(
+            error.to_string().contains("has changed since the run"),
+            "{error}"
+        );
+
+        apply(&project_path, &mutation_root, id, true, true, true).unwrap();
+        assert_eq!(std::fs::read(&file_path).unwrap(), source.as_bytes());
+    }
+}
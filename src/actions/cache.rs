@@ -0,0 +1,635 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use eyre::{eyre, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::actions::generate::project_entries;
+use crate::actions::reporting::results::MutantResult;
+use crate::actions::verify::compiler_version;
+use crate::config::Settings;
+use crate::mutation::{fnv1a, Complexity, Mutation, MutationChunk};
+use crate::report::MutationStatus;
+
+/// Directory of the mutation path kept between runs
+pub(crate) static CACHE_DIRECTORY: &str = "cache";
+static ANALYSIS_FILE: &str = "analysis.json";
+/// Bumped when the content of cached mutations changes
+static ANALYSIS_FORMAT: u32 = 14;
+static VERDICTS_DIRECTORY: &str = "verdicts";
+/// Time allowed to each request to an HTTP cache
+static CACHE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Mutation found by the analysis of a file, before any filtering
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedMutation {
+    start: usize,
+    end: usize,
+    replacement: String,
+    reason: String,
+    function: String,
+    #[serde(default)]
+    public: bool,
+    #[serde(default)]
+    complexity: Complexity,
+    operator: String,
+    #[serde(default)]
+    context: String,
+    #[serde(default)]
+    loop_control: bool,
+}
+
+/// Mutations found in a file
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CachedFile {
+    /// Hash of the file content
+    hash: u64,
+    mutations: Vec<CachedMutation>,
+}
+
+impl CachedFile {
+    pub(crate) fn new(content: &str, mutations: &[Mutation]) -> Self {
+        let mutations = mutations
+            .iter()
+            .map(|mutation| {
+                let range = mutation.get_range();
+                CachedMutation {
+                    start: range.start,
+                    end: range.end,
+                    replacement: mutation.get_replacement().to_string(),
+                    reason: mutation.reason.clone(),
+                    function: mutation.function_name.clone(),
+                    public: mutation.public,
+                    complexity: mutation.complexity,
+                    operator: mutation.operator.clone(),
+                    context: mutation.context.clone(),
+                    loop_control: mutation.loop_control,
+                }
+            })
+            .collect();
+        CachedFile {
+            hash: content_hash(content),
+            mutations,
+        }
+    }
+}
+
+/// Mutations of each file of the project, reused while the file content doesn't change
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct AnalysisCache {
+    /// Hash of the version and the settings changing the analysis
+    fingerprint: u64,
+    /// By path relative to the project
+    files: BTreeMap<String, CachedFile>,
+}
+
+/// Hash of a file content
+pub(crate) fn content_hash(content: &str) -> u64 {
+    fnv1a(content.as_bytes())
+}
+
+/// Path and hash of the content of a plugin, a rebuilt plugin may find other mutations
+fn plugin_fingerprint(path: &Path) -> String {
+    match std::fs::read(path) {
+        Ok(content) => format!("{}={}", path.display(), fnv1a(&content)),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+fn cache_path(mutation_root: &Path) -> PathBuf {
+    mutation_root.join(CACHE_DIRECTORY).join(ANALYSIS_FILE)
+}
+
+/// Write a file of the caches shared by runs and runners
+///
+/// The content is written aside then renamed, concurrent runs never read a partial file.
+pub(crate) fn write_aside(path: &Path, content: &[u8]) -> eyre::Result<()> {
+    let (Some(directory), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(eyre!("Invalid cache file {}", path.display()));
+    };
+    std::fs::create_dir_all(directory)?;
+    let partial = directory.join(format!(
+        "{}.{}.partial",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    std::fs::write(&partial, content).wrap_err(eyre!("Unable to write {}", partial.display()))?;
+    std::fs::rename(&partial, path).wrap_err(eyre!("Unable to write {}", path.display()))?;
+    Ok(())
+}
+
+impl AnalysisCache {
+    /// Cache matching the settings, empty if none
+    pub(crate) fn new(settings: &Settings) -> Self {
+        let mut fingerprint = vec![
+            env!("CARGO_PKG_VERSION").to_string(),
+            ANALYSIS_FORMAT.to_string(),
+        ];
+        fingerprint.extend(settings.test_attributes.iter().cloned());
+        fingerprint.push(format!("mutate_macro_args={}", settings.mutate_macro_args));
+        fingerprint.push(format!("mutate_literals={}", settings.mutate_literals));
+        fingerprint.extend(
+            settings
+                .plugins
+                .iter()
+                .map(|plugin| plugin_fingerprint(plugin)),
+        );
+        AnalysisCache {
+            fingerprint: fnv1a(fingerprint.join("\n").as_bytes()),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Load the cache of the previous analysis, empty if missing or made with other settings
+    pub(crate) fn load(settings: &Settings) -> Self {
+        let cache = AnalysisCache::new(settings);
+        let path = cache_path(&settings.mutation_root);
+        let Ok(file) = File::open(&path) else {
+            return cache;
+        };
+        match serde_json::from_reader::<_, AnalysisCache>(BufReader::new(file)) {
+            Ok(previous) if previous.fingerprint == cache.fingerprint => previous,
+            Ok(_) => {
+                log::debug!("Analysis settings have changed, ignore cache");
+                cache
+            }
+            Err(error) => {
+                log::warn!("Invalid analysis cache {}: {error}", path.display());
+                cache
+            }
+        }
+    }
+
+    pub(crate) fn save(&self, mutation_root: &Path) -> eyre::Result<()> {
+        write_aside(&cache_path(mutation_root), &serde_json::to_vec(self)?)
+    }
+
+    /// Mutations of a file if its content hasn't changed
+    pub(crate) fn get(&self, file: &str, content: &str) -> Option<Vec<Mutation>> {
+        let cached = self.files.get(file)?;
+        if cached.hash != content_hash(content) {
+            return None;
+        }
+        let mutations = cached
+            .mutations
+            .iter()
+            .map(|mutation| {
+                Mutation::new(
+                    &mutation.replacement,
+                    MutationChunk::from_range(content, mutation.start..mutation.end),
+                )
+                .with_reason(&mutation.reason)
+                .with_function_name(&mutation.function)
+                .with_public(mutation.public)
+                .with_complexity(mutation.complexity)
+                .with_operator(&mutation.operator)
+                .with_context(&mutation.context)
+                .with_loop_control(mutation.loop_control)
+            })
+            .collect();
+        Some(mutations)
+    }
+
+    pub(crate) fn insert(&mut self, file: &str, cached: CachedFile) {
+        self.files.insert(file.to_string(), cached);
+    }
+}
+
+/// Verdict of a mutant, with its detailed report
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CachedVerdict {
+    pub(crate) result: MutantResult,
+    pub(crate) report: String,
+}
+
+/// Storage of cached verdicts, by key
+pub(crate) trait VerdictStore: Send + Sync {
+    /// Content stored with the key, none if missing
+    fn get(&self, key: &str) -> eyre::Result<Option<Vec<u8>>>;
+    fn put(&self, key: &str, content: &[u8]) -> eyre::Result<()>;
+}
+
+/// Verdicts stored as files of a directory, local or shared
+pub(crate) struct DirectoryStore {
+    directory: PathBuf,
+}
+
+impl VerdictStore for DirectoryStore {
+    fn get(&self, key: &str) -> eyre::Result<Option<Vec<u8>>> {
+        match std::fs::read(self.directory.join(format!("{key}.json"))) {
+            Ok(content) => Ok(Some(content)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn put(&self, key: &str, content: &[u8]) -> eyre::Result<()> {
+        write_aside(&self.directory.join(format!("{key}.json")), content)
+    }
+}
+
+/// Verdicts stored on an HTTP server, read with `GET` and written with `PUT` at `{url}/{key}.json`
+///
+/// The token of `DARWIN_CACHE_TOKEN`, if defined, is sent as a bearer token. Once the server can't
+/// be reached, the store is left out for the rest of the run instead of waiting on each request.
+pub(crate) struct HttpStore {
+    url: String,
+    token: Option<String>,
+    /// The server couldn't be reached
+    unreachable: AtomicBool,
+}
+
+impl HttpStore {
+    fn request(&self, method: &str, key: &str) -> ureq::Request {
+        let request =
+            ureq::request(method, &format!("{}/{key}.json", self.url)).timeout(CACHE_TIMEOUT);
+        match &self.token {
+            Some(token) => request.set("Authorization", &format!("Bearer {token}")),
+            None => request,
+        }
+    }
+
+    /// Error of a request, the store is disabled if the server couldn't be reached
+    fn failed(&self, error: ureq::Error) -> eyre::Result<()> {
+        if let ureq::Error::Status(..) = error {
+            return Err(eyre!("{error}"));
+        }
+        if !self.unreachable.swap(true, Ordering::Relaxed) {
+            log::warn!(
+                "Cache server {} unreachable, not used for the rest of the run: {error}",
+                self.url
+            );
+        }
+        Ok(())
+    }
+}
+
+impl VerdictStore for HttpStore {
+    fn get(&self, key: &str) -> eyre::Result<Option<Vec<u8>>> {
+        if self.unreachable.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        match self.request("GET", key).call() {
+            Ok(response) => {
+                let mut content = vec![];
+                response.into_reader().read_to_end(&mut content)?;
+                Ok(Some(content))
+            }
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(error) => self.failed(error).map(|_| None),
+        }
+    }
+
+    fn put(&self, key: &str, content: &[u8]) -> eyre::Result<()> {
+        if self.unreachable.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        match self
+            .request("PUT", key)
+            .set("Content-Type", "application/json")
+            .send_bytes(content)
+        {
+            Ok(_) => Ok(()),
+            Err(error) => self.failed(error),
+        }
+    }
+}
+
+/// Store of a cache URL, `file://` for a shared directory or `http(s)://` for a server
+pub(crate) fn store_for_url(url: &str) -> eyre::Result<Box<dyn VerdictStore>> {
+    if let Some(directory) = url.strip_prefix("file://") {
+        return Ok(Box::new(DirectoryStore {
+            directory: PathBuf::from(directory),
+        }));
+    }
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return Ok(Box::new(HttpStore {
+            url: url.trim_end_matches('/').to_string(),
+            token: std::env::var("DARWIN_CACHE_TOKEN").ok(),
+            unreachable: AtomicBool::new(false),
+        }));
+    }
+    Err(eyre!(
+        "Unsupported cache URL {url}, expected file://, http:// or https://"
+    ))
+}
+
+/// Hash of the files of the workspace and of the manifests rewritten in mutant projects
+///
+/// Tests catching a mutant may be anywhere in the workspace, a verdict only holds for the exact
+/// same sources and tests.
+fn workspace_hash(settings: &Settings) -> eyre::Result<u64> {
+    let mut files = BTreeMap::new();
+    for entry in project_entries(settings)? {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(&settings.workspace_root)
+            .unwrap_or(entry.path());
+        let content = std::fs::read(entry.path())
+            .wrap_err(eyre!("Unable to read {}", entry.path().display()))?;
+        files.insert(path.to_path_buf(), fnv1a(&content));
+    }
+    for (path, content) in &settings.manifest_rewrites {
+        files.insert(path.clone(), content_hash(content));
+    }
+    let files = files
+        .iter()
+        .map(|(path, hash)| format!("{} {hash:016x}\n", path.display()))
+        .collect::<String>();
+    Ok(content_hash(&files))
+}
+
+/// Options of the settings changing the verdict of a mutant
+fn verification_options(settings: &Settings) -> String {
+    format!(
+        "{:?}\n{:?}\n{:?}\n{:?}\n{:?}\n{}\n{}\n{}",
+        settings.runner.test_args(),
+        settings.oracle,
+        settings.toolchain,
+        settings.env,
+        settings.rustflags,
+        settings.include_ignored,
+        settings.doctests,
+        settings.timeout.as_secs()
+    )
+}
+
+/// Verdicts of previous runs, reused while neither the project nor the way mutants are verified
+/// changes
+///
+/// A verdict is keyed by the stable ID of its mutant, the content of its file, the content of the
+/// other files of the workspace, the version of the compiler and the options of the verification.
+/// Only caught and killed mutants are cached, as with `--incremental`.
+///
+/// Verdicts are looked up in the stores in order, a verdict found in a store is copied to the
+/// previous ones, and written to all of them. Failing stores only lose verdicts.
+pub(crate) struct VerdictCache {
+    stores: Vec<Box<dyn VerdictStore>>,
+    /// Version of darwin, hash of the workspace, version of the compiler and options of the
+    /// verification
+    build: String,
+}
+
+impl VerdictCache {
+    /// Cache of the mutation path, then of the shared store of the settings, for the current
+    /// state of the build
+    pub(crate) fn new(settings: &Settings) -> eyre::Result<Self> {
+        let mut stores: Vec<Box<dyn VerdictStore>> = vec![Box::new(DirectoryStore {
+            directory: settings
+                .mutation_root
+                .join(CACHE_DIRECTORY)
+                .join(VERDICTS_DIRECTORY),
+        })];
+        if let Some(url) = &settings.cache_url {
+            stores.push(store_for_url(url)?);
+        }
+        Ok(VerdictCache {
+            stores,
+            build: format!(
+                "{}\n{:016x}\n{}\n{}",
+                env!("CARGO_PKG_VERSION"),
+                workspace_hash(settings)?,
+                compiler_version(settings)?,
+                verification_options(settings)
+            ),
+        })
+    }
+
+    fn key(&self, stable_id: &str, file_hash: u64) -> String {
+        let key = format!("{stable_id}\n{file_hash:016x}\n{}", self.build);
+        format!("{:016x}", fnv1a(key.as_bytes()))
+    }
+
+    /// Whether verdicts of this status are cached
+    pub(crate) fn caches(status: MutationStatus) -> bool {
+        matches!(
+            status,
+            MutationStatus::Fail | MutationStatus::CompilationFailed
+        )
+    }
+
+    /// Verdict of a mutant whose file has this hash, if cached
+    pub(crate) fn get(&self, stable_id: &str, file_hash: u64) -> Option<CachedVerdict> {
+        let key = self.key(stable_id, file_hash);
+        for (index, store) in self.stores.iter().enumerate() {
+            let content = match store.get(&key) {
+                Ok(Some(content)) => content,
+                Ok(None) => continue,
+                Err(error) => {
+                    log::warn!("Unable to read cached verdict {key}: {error}");
+                    continue;
+                }
+            };
+            match serde_json::from_slice(&content) {
+                Ok(verdict) => {
+                    for previous in &self.stores[..index] {
+                        if let Err(error) = previous.put(&key, &content) {
+                            log::warn!("Unable to cache verdict {key}: {error}");
+                        }
+                    }
+                    return Some(verdict);
+                }
+                Err(error) => log::warn!("Invalid cached verdict {key}: {error}"),
+            }
+        }
+        None
+    }
+
+    pub(crate) fn insert(
+        &self,
+        stable_id: &str,
+        file_hash: u64,
+        verdict: &CachedVerdict,
+    ) -> eyre::Result<()> {
+        let key = self.key(stable_id, file_hash);
+        let content = serde_json::to_vec(verdict)?;
+        for store in &self.stores {
+            if let Err(error) = store.put(&key, &content) {
+                log::warn!("Unable to cache verdict {key}: {error}");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use std::sync::{Arc, Mutex};
+
+    use crate::actions::cache::{
+        plugin_fingerprint, store_for_url, write_aside, AnalysisCache, CachedFile, CachedVerdict,
+        HttpStore, VerdictCache, VerdictStore,
+    };
+    use crate::actions::reporting::results::MutantResult;
+    use crate::mutation::{Mutation, MutationChunk};
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_analysis_cache() {
+        let content = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let mut cache = AnalysisCache {
+            fingerprint: 0,
+            files: BTreeMap::new(),
+        };
+        let mutation = Mutation::new("-", MutationChunk::from_range(content, 35..36))
+            .with_reason("replace + by -")
+            .with_function_name("add")
+            .with_operator("arithmetic");
+        cache.insert("src/lib.rs", CachedFile::new(content, &[mutation]));
+
+        let cached = cache.get("src/lib.rs", content).unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].get_range(), 35..36);
+        assert_eq!(cached[0].get_replacement(), "-");
+        assert_eq!(cached[0].function_name, "add");
+        assert!(cache.get("src/lib.rs", "fn add() {}").is_none());
+        // Offsets shifted by a byte order mark or a shebang line
+        assert!(cache
+            .get("src/lib.rs", &format!("\u{feff}{content}"))
+            .is_none());
+        assert!(cache
+            .get(
+                "src/lib.rs",
+                &format!("#!/usr/bin/env run-cargo-script\n{content}")
+            )
+            .is_none());
+        assert!(cache.get("src/main.rs", content).is_none());
+    }
+
+    #[test]
+    fn test_write_aside() {
+        let mutation_root = ScratchFolder::new("write-aside");
+        let path = mutation_root.join("cache").join("analysis.json");
+        write_aside(&path, b"{}").unwrap();
+        write_aside(&path, b"{\"files\": {}}").unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"{\"files\": {}}");
+        // Nothing is left aside
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_plugin_fingerprint() {
+        let folder = ScratchFolder::new("fingerprint");
+        let path = folder.join("plugin.so");
+        std::fs::write(&path, b"first build").unwrap();
+        let first = plugin_fingerprint(&path);
+        std::fs::write(&path, b"second build").unwrap();
+        let second = plugin_fingerprint(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(plugin_fingerprint(&path), path.display().to_string());
+    }
+
+    #[test]
+    fn test_verdict_key() {
+        let cache = |build: &str| VerdictCache {
+            stores: vec![],
+            build: build.to_string(),
+        };
+        let key = cache("0.3.0\nlock\nrustc 1.80.0").key("src-lib-add-2-7-sub", 1);
+        assert_eq!(key.len(), 16);
+        assert_eq!(
+            cache("0.3.0\nlock\nrustc 1.80.0").key("src-lib-add-2-7-sub", 1),
+            key
+        );
+        assert_ne!(
+            cache("0.3.0\nlock\nrustc 1.80.0").key("src-lib-add-2-7-mul", 1),
+            key
+        );
+        assert_ne!(
+            cache("0.3.0\nlock\nrustc 1.80.0").key("src-lib-add-2-7-sub", 2),
+            key
+        );
+        assert_ne!(
+            cache("0.3.0\nlock\nrustc 1.81.0").key("src-lib-add-2-7-sub", 1),
+            key
+        );
+    }
+
+    /// Store in memory, shared with the test
+    struct MemoryStore(Arc<Mutex<BTreeMap<String, Vec<u8>>>>);
+
+    impl VerdictStore for MemoryStore {
+        fn get(&self, key: &str) -> eyre::Result<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+
+        fn put(&self, key: &str, content: &[u8]) -> eyre::Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), content.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_verdict_stores() {
+        let local = Arc::new(Mutex::new(BTreeMap::new()));
+        let shared = Arc::new(Mutex::new(BTreeMap::new()));
+        let cache = |stores: Vec<Box<dyn VerdictStore>>| VerdictCache {
+            stores,
+            build: "0.3.0".to_string(),
+        };
+        let result: MutantResult = serde_json::from_value(serde_json::json!({
+            "id": 0, "stable_id": "src-lib-add-2-7-sub", "file": "src/lib.rs", "function": "add",
+            "operator": "arithmetic", "reason": "replace + by -", "line": 2, "column": 7,
+            "start": 35, "end": 36, "original": "+", "replacement": "-", "status": "ok",
+            "caught_by": ["tests::test_add"], "log": "reports/mutation_0.log", "diff": ""
+        }))
+        .unwrap();
+        let verdict = CachedVerdict {
+            result,
+            report: "caught".to_string(),
+        };
+
+        // Another runner caches the verdict in the shared store
+        cache(vec![Box::new(MemoryStore(shared.clone()))])
+            .insert("src-lib-add-2-7-sub", 1, &verdict)
+            .unwrap();
+        let cache = cache(vec![
+            Box::new(MemoryStore(local.clone())),
+            Box::new(MemoryStore(shared.clone())),
+        ]);
+        assert!(cache.get("src-lib-add-2-7-sub", 2).is_none());
+        let cached = cache.get("src-lib-add-2-7-sub", 1).unwrap();
+        assert_eq!(cached.report, "caught");
+        assert_eq!(cached.result.caught_by, vec!["tests::test_add"]);
+        assert_eq!(local.lock().unwrap().len(), 1);
+
+        assert!(store_for_url("file:///shared/darwin").is_ok());
+        assert!(store_for_url("https://cache.example.com/darwin/").is_ok());
+        assert!(store_for_url("redis://cache:6379").is_err());
+    }
+
+    #[test]
+    fn test_unreachable_store() {
+        // Nothing listens on the port of a listener once dropped
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let store = HttpStore {
+            url: format!("http://127.0.0.1:{port}"),
+            token: None,
+            unreachable: false.into(),
+        };
+        assert!(store.get("key").unwrap().is_none());
+        assert!(store.unreachable.load(std::sync::atomic::Ordering::Relaxed));
+        assert!(store.put("key", b"{}").is_ok());
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::VecDeque;
+use std::io::Read;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use eyre::eyre;
+use wait_timeout::ChildExt;
+
+/// Bytes kept from the start of each output of a command
+static OUTPUT_HEAD: usize = 256 * 1024;
+/// Bytes kept from the end of each output of a command
+static OUTPUT_TAIL: usize = 256 * 1024;
+
+/// Output of a command held in bounded memory, its start and its end
+#[derive(Debug)]
+struct BoundedOutput {
+    head_limit: usize,
+    tail_limit: usize,
+    head: Vec<u8>,
+    tail: VecDeque<u8>,
+    /// Bytes dropped between the start and the end
+    omitted: usize,
+}
+
+impl BoundedOutput {
+    fn new(head_limit: usize, tail_limit: usize) -> Self {
+        BoundedOutput {
+            head_limit,
+            tail_limit,
+            head: vec![],
+            tail: VecDeque::new(),
+            omitted: 0,
+        }
+    }
+
+    fn push(&mut self, data: &[u8]) {
+        let to_head = self
+            .head_limit
+            .saturating_sub(self.head.len())
+            .min(data.len());
+        self.head.extend_from_slice(&data[..to_head]);
+        self.tail.extend(&data[to_head..]);
+        let excess = self.tail.len().saturating_sub(self.tail_limit);
+        self.tail.drain(..excess);
+        self.omitted += excess;
+    }
+
+    /// Read a stream until its end
+    fn read(mut self, mut stream: impl Read) -> Self {
+        let mut buffer = [0; 8192];
+        loop {
+            match stream.read(&mut buffer) {
+                Ok(0) | Err(_) => return self,
+                Ok(read) => self.push(&buffer[..read]),
+            }
+        }
+    }
+
+    fn into_string(self) -> String {
+        let mut output = String::from_utf8_lossy(&self.head).to_string();
+        if self.omitted > 0 {
+            output.push_str(&format!(
+                "\n[... {} bytes of output omitted ...]\n",
+                self.omitted
+            ));
+        }
+        output.push_str(&String::from_utf8_lossy(&Vec::from(self.tail)));
+        output
+    }
+}
+
+/// Read a stream of a command in the background, keeping its start and its end
+fn capture(
+    stream: Option<impl Read + Send + 'static>,
+    (head_limit, tail_limit): (usize, usize),
+) -> eyre::Result<JoinHandle<String>> {
+    let stream = stream.ok_or(eyre!("Output of the command not captured"))?;
+    Ok(std::thread::spawn(move || {
+        BoundedOutput::new(head_limit, tail_limit)
+            .read(stream)
+            .into_string()
+    }))
+}
+
+/// Kill the processes started by a command, as the test binaries run by cargo, which would be left
+/// running otherwise
+///
+/// On Unix, the command leads a process group of its own, killed at once with all its descendants.
+fn kill_descendants(pid: u32) {
+    let pid = pid.to_string();
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{pid}")])
+        .status();
+    #[cfg(windows)]
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid])
+        .status();
+}
+
+/// Kill a command along with the processes it started
+fn kill_tree(child: &mut Child) -> eyre::Result<()> {
+    kill_descendants(child.id());
+    child.kill()?;
+    Ok(())
+}
+
+/// Commands running in a process group of their own, which an interruption of darwin doesn't reach
+struct Running {
+    interrupted: AtomicBool,
+    commands: Mutex<Vec<u32>>,
+}
+
+impl Running {
+    const fn new() -> Self {
+        Running {
+            interrupted: AtomicBool::new(false),
+            commands: Mutex::new(vec![]),
+        }
+    }
+
+    /// Start a command, unless the run is interrupted
+    fn spawn(&self, command: &mut Command) -> eyre::Result<Child> {
+        let mut commands = self
+            .commands
+            .lock()
+            .map_err(|_| eyre!("Poisoned commands"))?;
+        if self.interrupted.load(Ordering::SeqCst) {
+            return Err(eyre!("Interrupted"));
+        }
+        let child = command.spawn()?;
+        commands.push(child.id());
+        Ok(child)
+    }
+
+    /// Forget a command which has exited, fails if it was killed by an interruption
+    fn finish(&self, child: &Child) -> eyre::Result<()> {
+        if let Ok(mut commands) = self.commands.lock() {
+            commands.retain(|pid| *pid != child.id());
+        }
+        if self.interrupted.load(Ordering::SeqCst) {
+            return Err(eyre!("Interrupted"));
+        }
+        Ok(())
+    }
+
+    /// Kill the running commands and refuse to start others, returns whether it was already done
+    fn interrupt(&self) -> bool {
+        let interrupted = self.interrupted.swap(true, Ordering::SeqCst);
+        if let Ok(commands) = self.commands.lock() {
+            commands.iter().for_each(|pid| kill_descendants(*pid));
+        }
+        interrupted
+    }
+}
+
+/// Commands of the run
+static RUNNING: Running = Running::new();
+
+/// Kill the commands of the run and refuse to start others, returns whether the run was already
+/// interrupted
+pub(crate) fn interrupt() -> bool {
+    RUNNING.interrupt()
+}
+
+/// Whether the run has been interrupted
+pub(crate) fn interrupted() -> bool {
+    RUNNING.interrupted.load(Ordering::SeqCst)
+}
+
+/// Outcome of a command
+pub(crate) struct Captured {
+    /// Exit status, none if the command was killed at the timeout
+    pub(crate) status: Option<ExitStatus>,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+}
+
+/// Run a command, keeping the start and the end of its outputs, killed after the timeout if any
+///
+/// The command runs in a process group of its own, so that the processes it starts are killed with
+/// it. Outputs are read while the command runs, so that it never blocks on a full pipe. After a
+/// timeout they are left out, processes started by the command may still hold them open. Fails when
+/// the run is interrupted, as the command is killed.
+pub(crate) fn run_captured(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> eyre::Result<Captured> {
+    run_among(&RUNNING, command, timeout, (OUTPUT_HEAD, OUTPUT_TAIL))
+}
+
+/// Run a command as [`run_captured`] does, keeping its whole outputs, for commands whose every
+/// line may matter such as the JSON messages of cargo
+pub(crate) fn run_whole(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> eyre::Result<Captured> {
+    run_among(&RUNNING, command, timeout, (usize::MAX, 0))
+}
+
+fn run_among(
+    running: &Running,
+    command: &mut Command,
+    timeout: Option<Duration>,
+    limits: (usize, usize),
+) -> eyre::Result<Captured> {
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    let mut child = running.spawn(command.stdout(Stdio::piped()).stderr(Stdio::piped()))?;
+    let stdout = capture(child.stdout.take(), limits)?;
+    let stderr = capture(child.stderr.take(), limits)?;
+
+    let status = match timeout {
+        Some(timeout) => child.wait_timeout(timeout)?,
+        None => Some(child.wait()?),
+    };
+    let Some(status) = status else {
+        kill_tree(&mut child)?;
+        child.wait()?;
+        running.finish(&child)?;
+        return Ok(Captured {
+            status: None,
+            stdout: "".to_string(),
+            stderr: "".to_string(),
+        });
+    };
+
+    running.finish(&child)?;
+
+    let join = |output: JoinHandle<String>| {
+        output
+            .join()
+            .map_err(|_| eyre!("Unable to read the output of the command"))
+    };
+    Ok(Captured {
+        status: Some(status),
+        stdout: join(stdout)?,
+        stderr: join(stderr)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::capture::{run_among, run_captured, BoundedOutput, Running};
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_bounded_output() {
+        let mut output = BoundedOutput::new(6, 4);
+        output.push(b"test a");
+        assert_eq!(output.into_string(), "test a");
+
+        let mut output = BoundedOutput::new(6, 4);
+        for _ in 0..1000 {
+            output.push(b"loop\n");
+        }
+        output.push(b"end\n");
+        assert_eq!(
+            output.into_string(),
+            "loop\nl\n[... 4994 bytes of output omitted ...]\nend\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_at_timeout() {
+        let folder = ScratchFolder::new("capture");
+        let marker = folder.join("marker");
+        // The grandchild would create the marker once the command is killed
+        let script = format!(
+            "sh -c \"sh -c 'sleep 1; touch {}'; true\" & wait",
+            marker.display()
+        );
+        let captured = run_captured(
+            std::process::Command::new("sh").args(["-c", &script]),
+            Some(std::time::Duration::from_millis(200)),
+        )
+        .unwrap();
+        assert!(captured.status.is_none());
+        std::thread::sleep(std::time::Duration::from_secs(2));
+        assert!(!marker.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_interrupt() {
+        let running = Running::new();
+        let start = std::time::Instant::now();
+        std::thread::scope(|scope| {
+            let command = scope.spawn(|| {
+                run_among(
+                    &running,
+                    std::process::Command::new("sh").args(["-c", "sleep 10 & wait"]),
+                    None,
+                    (0, 0),
+                )
+            });
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            assert!(!running.interrupt());
+            assert!(command.join().unwrap().is_err());
+        });
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        assert!(running.interrupt());
+        let command = &mut std::process::Command::new("true");
+        assert!(run_among(&running, command, None, (0, 0)).is_err());
+    }
+}
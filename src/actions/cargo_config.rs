@@ -0,0 +1,258 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, WrapErr};
+
+use crate::actions::manifest::normalize;
+
+/// Keys of the `build` table holding executables
+static BUILD_EXECUTABLES: [&str; 4] = [
+    "rustc",
+    "rustc-wrapper",
+    "rustc-workspace-wrapper",
+    "rustdoc",
+];
+
+/// Configuration file of cargo in a folder, cargo reads the one without extension first
+fn config_file(folder: &Path) -> Option<PathBuf> {
+    ["config", "config.toml"]
+        .into_iter()
+        .map(|name| folder.join(".cargo").join(name))
+        .find(|path| path.is_file())
+}
+
+/// Home of cargo, whose configuration cargo reads wherever it runs
+fn cargo_home() -> Option<PathBuf> {
+    let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os(home).map(|home| Path::new(&home).join(".cargo")))
+}
+
+/// Sub-tables of a table of the configuration
+fn tables<'a>(config: &'a mut toml::Table, key: &str) -> Vec<&'a mut toml::Table> {
+    match config.get_mut(key) {
+        Some(toml::Value::Table(table)) => table
+            .iter_mut()
+            .filter_map(|(_, value)| value.as_table_mut())
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Make the paths of the configuration relative to the project, or absolute if outside of it
+///
+/// Cargo resolves them relative to the folder holding `.cargo`. Executables without separator
+/// are searched in `PATH` and left as is.
+fn rebase_paths(config: &mut toml::Table, config_root: &Path, project_root: &Path) {
+    let rebase = |value: &mut toml::Value, executable: bool| {
+        let toml::Value::String(path) = value else {
+            return;
+        };
+        if executable && !path.contains(std::path::is_separator) {
+            return;
+        }
+        let resolved = normalize(&config_root.join(&*path));
+        *path = match resolved.strip_prefix(project_root) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => relative.to_string_lossy().to_string(),
+            Err(_) => resolved.to_string_lossy().to_string(),
+        };
+    };
+
+    if let Some(toml::Value::Array(paths)) = config.get_mut("paths") {
+        paths.iter_mut().for_each(|path| rebase(path, false));
+    }
+    if let Some(toml::Value::Table(build)) = config.get_mut("build") {
+        for (key, value) in build.iter_mut() {
+            if BUILD_EXECUTABLES.contains(&key.as_str()) {
+                rebase(value, true);
+            }
+        }
+    }
+    for target in tables(config, "target") {
+        if let Some(linker) = target.get_mut("linker") {
+            rebase(linker, true);
+        }
+        match target.get_mut("runner") {
+            Some(toml::Value::Array(runner)) => {
+                if let Some(executable) = runner.first_mut() {
+                    rebase(executable, true);
+                }
+            }
+            Some(runner) => rebase(runner, true),
+            None => {}
+        }
+    }
+    for source in tables(config, "source") {
+        for key in ["directory", "local-registry"] {
+            if let Some(path) = source.get_mut(key) {
+                rebase(path, false);
+            }
+        }
+    }
+    for registry in tables(config, "patch") {
+        for (_, patch) in registry.iter_mut() {
+            if let Some(path) = patch.get_mut("path") {
+                rebase(path, false);
+            }
+        }
+    }
+    for variable in tables(config, "env") {
+        if variable.get("relative").and_then(toml::Value::as_bool) == Some(true) {
+            if let Some(value) = variable.get_mut("value") {
+                rebase(value, false);
+            }
+        }
+    }
+}
+
+/// Merge a configuration into another one, as cargo does with the one of a deeper folder
+///
+/// Tables are merged, arrays joined and other values replaced.
+fn merge(base: &mut toml::Table, config: toml::Table) {
+    for (key, value) in config {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(table)) => merge(base, table),
+            (Some(toml::Value::Array(base)), toml::Value::Array(values)) => base.extend(values),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Configuration of cargo of the project and of the folders above it, for copies of the project
+///
+/// Copies lose the configuration of the folders above the project, which is merged into the one of
+/// the project, with paths rebased. Folders above the mutation root are left out, as cargo reads
+/// them in copies as well. `build.target-dir` is dropped, so that mutants are built in the target
+/// folders of darwin instead of sharing one. Returns the path of the configuration relative to the project and its
+/// content, none if no folder above the project has a configuration to carry.
+pub(crate) fn carried_config(
+    project_root: &Path,
+    mutation_root: &Path,
+) -> eyre::Result<Option<(PathBuf, String)>> {
+    let cargo_home = cargo_home().map(|home| normalize(&home));
+    let ancestors = project_root
+        .ancestors()
+        .skip(1)
+        .take_while(|folder| !mutation_root.starts_with(folder))
+        .filter(|folder| cargo_home.as_deref() != Some(&folder.join(".cargo")))
+        .filter_map(config_file)
+        .collect::<Vec<_>>();
+    if ancestors.is_empty() {
+        return Ok(None);
+    }
+
+    let own = config_file(project_root);
+    let mut merged = toml::Table::new();
+    for path in ancestors.iter().rev().chain(&own) {
+        log::debug!("Carry cargo configuration {}", path.display());
+        let content = std::fs::read_to_string(path).wrap_err(eyre!(
+            "Unable to read cargo configuration {}",
+            path.display()
+        ))?;
+        let mut config: toml::Table = toml::from_str(&content)
+            .wrap_err(eyre!("Invalid cargo configuration {}", path.display()))?;
+        // The configuration is in `<folder>/.cargo/`
+        let config_root = path.parent().and_then(Path::parent).unwrap_or(project_root);
+        rebase_paths(&mut config, config_root, project_root);
+        merge(&mut merged, config);
+    }
+    if let Some(toml::Value::Table(build)) = merged.get_mut("build") {
+        build.remove("target-dir");
+    }
+    let relative_path = own
+        .as_deref()
+        .and_then(|path| path.strip_prefix(project_root).ok())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| Path::new(".cargo").join("config.toml"));
+    Ok(Some((relative_path, toml::to_string(&merged)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::cargo_config::{carried_config, merge, rebase_paths};
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_rebase_and_merge() {
+        let mut workspace: toml::Table = toml::from_str(
+            r#"
+            paths = ["vendor/fork"]
+
+            [build]
+            rustc-wrapper = "sccache"
+            rustflags = ["--cfg", "workspace"]
+
+            [patch.crates-io]
+            serde = { path = "crates/serde" }
+
+            [env]
+            FIXTURES = { value = "ws/crates/a/fixtures", relative = true }
+            "#,
+        )
+        .unwrap();
+        rebase_paths(
+            &mut workspace,
+            Path::new("/work"),
+            Path::new("/work/ws/crates/a"),
+        );
+        assert_eq!(workspace["paths"][0].as_str(), Some("/work/vendor/fork"));
+        assert_eq!(
+            workspace["build"]["rustc-wrapper"].as_str(),
+            Some("sccache")
+        );
+        assert_eq!(
+            workspace["patch"]["crates-io"]["serde"]["path"].as_str(),
+            Some("/work/crates/serde")
+        );
+        assert_eq!(
+            workspace["env"]["FIXTURES"]["value"].as_str(),
+            Some("fixtures")
+        );
+
+        let project: toml::Table = toml::from_str(
+            r#"
+            [build]
+            rustflags = ["--cfg", "project"]
+            "#,
+        )
+        .unwrap();
+        merge(&mut workspace, project);
+        assert_eq!(
+            workspace["build"]["rustflags"].as_array().unwrap(),
+            &vec![
+                "--cfg".into(),
+                "workspace".into(),
+                "--cfg".into(),
+                "project".into()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_carried_config_drops_target_dir() {
+        let root = ScratchFolder::new("carried");
+        let project = root.join("ws").join("a");
+        std::fs::create_dir_all(root.join("ws").join(".cargo")).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(
+            root.join("ws").join(".cargo").join("config.toml"),
+            "[build]\ntarget-dir = \"target/shared\"\nrustflags = [\"--cfg\", \"workspace\"]\n",
+        )
+        .unwrap();
+
+        let carried = carried_config(&project, &root.join("mutation"));
+        let (path, content) = carried.unwrap().unwrap();
+        assert_eq!(path, Path::new(".cargo").join("config.toml"));
+        let config: toml::Table = toml::from_str(&content).unwrap();
+        assert!(config["build"].get("target-dir").is_none());
+        assert_eq!(
+            config["build"]["rustflags"].as_array().unwrap(),
+            &vec!["--cfg".into(), "workspace".into()]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use eyre::{eyre, WrapErr};
+
+use crate::actions::reporting::results::{Execution, MutantResult, RunResults};
+use crate::config::Settings;
+use crate::mutation::Mutation;
+use crate::report::MutationStatus;
+
+/// Lines executed at least once, by file relative to the project
+type ExecutedLines = HashMap<String, HashSet<usize>>;
+
+/// Lines executed by the tests, read from LCOV files
+#[derive(Debug, Default)]
+pub(crate) struct Coverage {
+    /// Lines executed, and the test which executed them when coverage is recorded per test
+    runs: Vec<(Option<String>, ExecutedLines)>,
+}
+
+impl Coverage {
+    /// Coverage of the settings, if any
+    pub(crate) fn from_settings(settings: &Settings) -> eyre::Result<Option<Self>> {
+        settings
+            .coverage
+            .as_ref()
+            .map(|path| Coverage::load(path, &settings.root_path))
+            .transpose()
+    }
+
+    /// Load a LCOV file, or a folder of LCOV files named after the test they record
+    pub(crate) fn load(path: &Path, root_path: &Path) -> eyre::Result<Self> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path)
+                .wrap_err(eyre!("Unable to read coverage file {}", path.display()))
+        };
+        let mut coverage = Coverage::default();
+        if !path.is_dir() {
+            coverage
+                .runs
+                .push((None, parse_lcov(&read(path)?, root_path)));
+            return Ok(coverage);
+        }
+
+        let mut entries = std::fs::read_dir(path)
+            .wrap_err(eyre!("Unable to read coverage folder {}", path.display()))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            let is_lcov = entry
+                .extension()
+                .is_some_and(|extension| extension == "info" || extension == "lcov");
+            let Some(test) = entry.file_stem().filter(|_| is_lcov) else {
+                continue;
+            };
+            let test = test.to_string_lossy().to_string();
+            coverage
+                .runs
+                .push((Some(test), parse_lcov(&read(&entry)?, root_path)));
+        }
+        Ok(coverage)
+    }
+
+    /// Whether the tests executed one of the lines of a file, `None` if the file isn't covered
+    pub(crate) fn execution(&self, file: &str, lines: &[usize]) -> Option<Execution> {
+        let covering = self
+            .runs
+            .iter()
+            .filter_map(|(test, files)| Some((test, files.get(file)?)))
+            .collect::<Vec<_>>();
+        if covering.is_empty() {
+            return None;
+        }
+        let executing = covering
+            .into_iter()
+            .filter(|(_, executed)| lines.iter().any(|line| executed.contains(line)))
+            .collect::<Vec<_>>();
+        Some(Execution {
+            executed: !executing.is_empty(),
+            tests: executing
+                .into_iter()
+                .filter_map(|(test, _)| test.clone())
+                .collect(),
+        })
+    }
+
+    /// Whether lines of a file are executed, only by `#[ignore]` tests
+    ///
+    /// Only coverage recorded per test tells, a test is ignored when its name ends with the
+    /// qualified name of an ignored test, or the other way around.
+    fn ignored_only(&self, file: &str, lines: &[usize], ignored: &[String]) -> bool {
+        if self.runs.iter().any(|(test, _)| test.is_none()) {
+            return false;
+        }
+        let same = |test: &str, name: &str| {
+            test == name
+                || test.ends_with(&format!("::{name}"))
+                || name.ends_with(&format!("::{test}"))
+        };
+        self.execution(file, lines).is_some_and(|execution| {
+            execution.executed
+                && execution
+                    .tests
+                    .iter()
+                    .all(|test| ignored.iter().any(|name| same(test, name)))
+        })
+    }
+
+    /// Leave out the mutants only executed by `#[ignore]` tests, returns the mutants kept
+    pub(crate) fn skip_ignored_only(
+        &self,
+        mutants: Vec<Mutation>,
+        ignored: &[String],
+    ) -> eyre::Result<Vec<Mutation>> {
+        if ignored.is_empty() {
+            return Ok(mutants);
+        }
+        let mut kept = vec![];
+        for mutation in mutants {
+            let lines = (mutation.chunk.start_point.row + 1..=mutation.chunk.end_point.row + 1)
+                .collect::<Vec<_>>();
+            if !self.ignored_only(&mutation.relative_file()?, &lines, ignored) {
+                kept.push(mutation);
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Tell missing mutants never executed from those executed but not asserted, and find the
+    /// functions whose tests don't assert at all
+    pub(crate) fn annotate(&self, results: &mut RunResults) {
+        self.annotate_mutants(&mut results.mutants);
+        results.weak_oracles = results.find_weak_oracles();
+    }
+
+    fn annotate_mutants(&self, results: &mut [MutantResult]) {
+        for result in results {
+            if result.status != MutationStatus::Success {
+                continue;
+            }
+            let lines = (result.mutation.line..=result.mutation.end_line).collect::<Vec<_>>();
+            result.execution = self.execution(&result.mutation.file, &lines);
+        }
+    }
+}
+
+/// Lines executed according to a LCOV file, files outside of the project are ignored
+fn parse_lcov(content: &str, root_path: &Path) -> ExecutedLines {
+    let mut files = HashMap::new();
+    let mut file = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            let path = Path::new(path);
+            let relative_path = if path.is_absolute() {
+                path.strip_prefix(root_path).ok()
+            } else {
+                Some(path)
+            };
+            file = relative_path.map(|path| path.to_string_lossy().replace('\\', "/"));
+            if let Some(file) = &file {
+                files.entry(file.clone()).or_insert_with(HashSet::new);
+            }
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let mut fields = data.split(',');
+            let line = fields.next().and_then(|line| line.parse::<usize>().ok());
+            let count = fields.next().and_then(|count| count.parse::<u64>().ok());
+            if let (Some(file), Some(line), Some(1..)) = (&file, line, count) {
+                files.entry(file.clone()).or_default().insert(line);
+            }
+        } else if line == "end_of_record" {
+            file = None;
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::coverage::{parse_lcov, Coverage};
+    use crate::actions::reporting::results::Execution;
+
+    #[test]
+    fn test_coverage() {
+        let lcov = |lines: &str| {
+            format!(
+                "SF:/work/project/src/lib.rs\n{lines}end_of_record\n\
+                SF:/elsewhere/dep.rs\nDA:1,1\nend_of_record\n"
+            )
+        };
+        let root_path = Path::new("/work/project");
+        let files = parse_lcov(&lcov("DA:4,2\nDA:5,0\n"), root_path);
+        assert_eq!(files.len(), 1);
+
+        let coverage = Coverage {
+            runs: vec![
+                (Some("test_add".to_string()), files),
+                (
+                    Some("test_sub".to_string()),
+                    parse_lcov(&lcov("DA:4,1\nDA:9,3\n"), root_path),
+                ),
+            ],
+        };
+        assert_eq!(
+            coverage.execution("src/lib.rs", &[4]),
+            Some(Execution {
+                executed: true,
+                tests: vec!["test_add".to_string(), "test_sub".to_string()]
+            })
+        );
+        assert_eq!(
+            coverage.execution("src/lib.rs", &[5]),
+            Some(Execution {
+                executed: false,
+                tests: vec![]
+            })
+        );
+        assert_eq!(coverage.execution("src/other.rs", &[4]), None);
+
+        let ignored = ["tests::test_sub".to_string()];
+        assert!(coverage.ignored_only("src/lib.rs", &[9], &ignored));
+        assert!(!coverage.ignored_only("src/lib.rs", &[4], &ignored));
+        assert!(!coverage.ignored_only("src/lib.rs", &[5], &ignored));
+        let whole = Coverage {
+            runs: vec![(None, parse_lcov(&lcov("DA:9,3\n"), root_path))],
+        };
+        assert!(!whole.ignored_only("src/lib.rs", &[9], &ignored));
+    }
+}
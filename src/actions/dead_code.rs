@@ -0,0 +1,185 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr;
+
+use crate::actions::analyze::{parse_rust, FUNCTION_ITEM};
+use crate::actions::generate::{copy_workspace, project_entries};
+use crate::actions::reporting::results::DeadFunction;
+use crate::actions::verify::dead_code_messages;
+use crate::config::Settings;
+use crate::mutation::Mutation;
+
+/// Names reported as never used in the JSON messages of cargo, by file and offset
+///
+/// File names of the messages are relative to the workspace root.
+fn reported_names(messages: &str, workspace_root: &Path) -> HashSet<(PathBuf, usize)> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-message")
+        .filter(|message| message["message"]["code"]["code"] == "dead_code")
+        .flat_map(|message| message["message"]["spans"].as_array().cloned())
+        .flatten()
+        .filter(|span| span["is_primary"] == true)
+        .filter_map(|span| {
+            Some((
+                workspace_root.join(span["file_name"].as_str()?),
+                span["byte_start"].as_u64()? as usize,
+            ))
+        })
+        .collect()
+}
+
+/// Name of the outermost function enclosing a byte whose name is reported
+fn dead_function<'a>(
+    tree: &tree_sitter::Tree,
+    source: &'a str,
+    offset: usize,
+    reported: impl Fn(usize) -> bool,
+) -> Option<&'a str> {
+    let mut node = tree.root_node().descendant_for_byte_range(offset, offset)?;
+    let mut dead = None;
+    loop {
+        if node.kind() == FUNCTION_ITEM {
+            if let Some(name) = node.child_by_field_name("name") {
+                if reported(name.start_byte()) {
+                    dead = name.utf8_text(source.as_bytes()).ok();
+                }
+            }
+        }
+        match node.parent() {
+            Some(parent) => node = parent,
+            None => return dead,
+        }
+    }
+}
+
+/// Leave out the mutants of functions never used, returns the mutants kept and the functions
+///
+/// The dead code lint runs on a copy of the project, checked as is and as tests: a function is
+/// never used when both checks report it, functions only used by tests or by `main` are kept.
+/// Mutants outside of functions are kept, as well as every mutant if a check fails.
+pub(crate) fn skip_dead_code(
+    mutants: Vec<Mutation>,
+    settings: &Settings,
+) -> eyre::Result<(Vec<Mutation>, Vec<DeadFunction>)> {
+    if mutants.is_empty() {
+        return Ok((mutants, vec![]));
+    }
+
+    log::info!("Check which mutated functions are never used");
+    std::fs::create_dir_all(&settings.mutation_path)?;
+    let mutation_root = std::fs::canonicalize(&settings.mutation_path)
+        .wrap_err("Unable to get canonical mutation_root")?;
+    let check_path = mutation_root.join("dead_code");
+    if check_path.exists() {
+        std::fs::remove_dir_all(&check_path)?;
+    }
+    copy_workspace(&project_entries(settings)?, settings, &check_path)?;
+    let messages = dead_code_messages(&check_path.join(&settings.member_path), settings);
+    std::fs::remove_dir_all(&check_path)?;
+
+    let reported = match messages {
+        Ok([messages, test_messages]) => {
+            let test_reported = reported_names(&test_messages, &settings.workspace_root);
+            reported_names(&messages, &settings.workspace_root)
+                .into_iter()
+                .filter(|name| test_reported.contains(name))
+                .collect::<HashSet<_>>()
+        }
+        Err(error) => {
+            log::debug!("{error:#}");
+            println!("Unable to tell which functions are used, no mutant is skipped");
+            return Ok((mutants, vec![]));
+        }
+    };
+
+    let mut kept = vec![];
+    let mut functions: BTreeMap<(String, String), usize> = BTreeMap::new();
+    let mut trees = BTreeMap::new();
+    for mutation in mutants {
+        let path = mutation.get_file_path()?.clone();
+        let source = mutation.source()?;
+        let tree = match trees.entry(path.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(parse_rust(source)?),
+        };
+        let function = tree.as_ref().and_then(|tree| {
+            dead_function(tree, source, mutation.get_range().start, |offset| {
+                reported.contains(&(path.clone(), offset))
+            })
+        });
+        match function {
+            Some(function) => {
+                *functions
+                    .entry((mutation.relative_file()?, function.to_string()))
+                    .or_default() += 1;
+            }
+            None => kept.push(mutation),
+        }
+    }
+    let functions = functions
+        .into_iter()
+        .map(|((file, function), mutants)| DeadFunction {
+            file,
+            function,
+            mutants,
+        })
+        .collect::<Vec<_>>();
+    for function in &functions {
+        println!("{}", function.simple());
+    }
+    Ok((kept, functions))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::actions::analyze::parse_rust;
+    use crate::actions::dead_code::{dead_function, reported_names};
+
+    #[test]
+    fn test_dead_functions() {
+        let messages = [
+            r#"{"reason":"compiler-artifact","target":{"name":"a"}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"function `a` is never used","code":{"code":"dead_code"},"spans":[{"file_name":"src/lib.rs","byte_start":3,"is_primary":true}]}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"associated items `b` and `c` are never used","code":{"code":"dead_code"},"spans":[{"file_name":"src/lib.rs","byte_start":40,"is_primary":false},{"file_name":"src/lib.rs","byte_start":50,"is_primary":true}]}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`","code":{"code":"unused_variables"},"spans":[{"file_name":"src/lib.rs","byte_start":9,"is_primary":true}]}}"#,
+        ]
+        .join("\n");
+        let mut reported = reported_names(&messages, Path::new("/ws"))
+            .into_iter()
+            .collect::<Vec<_>>();
+        reported.sort();
+        assert_eq!(
+            reported,
+            vec![
+                (PathBuf::from("/ws/src/lib.rs"), 3),
+                (PathBuf::from("/ws/src/lib.rs"), 50)
+            ]
+        );
+
+        let source =
+            "fn a() -> u8 {\n    fn b() -> u8 { 2 * 2 }\n    b() - 1\n}\nfn c() -> u8 { 1 + 1 }\n";
+        let tree = parse_rust(source).unwrap().unwrap();
+        let inner = source.find("* 2").unwrap();
+        let a = source.find('a').unwrap();
+        let b = source.find("b()").unwrap();
+        assert_eq!(dead_function(&tree, source, inner, |_| false), None);
+        assert_eq!(
+            dead_function(&tree, source, inner, |name| name == b),
+            Some("b")
+        );
+        assert_eq!(
+            dead_function(&tree, source, inner, |name| name == a || name == b),
+            Some("a")
+        );
+        assert_eq!(
+            dead_function(&tree, source, source.find("+ 1").unwrap(), |name| name == a),
+            None
+        );
+    }
+}
@@ -1,16 +1,36 @@
+use crate::actions::cache::{content_hash, CachedVerdict, VerdictCache};
 use crate::actions::clean::clean_mutation_project;
-use crate::actions::get_project_walker;
-use crate::actions::verify::run_test_for_mutation;
-use crate::mutation::Mutation;
+use crate::actions::coverage::Coverage;
+use crate::actions::get_project_entries;
+use crate::actions::hooks::run_hook;
+use crate::actions::manifest::normalize;
+use crate::actions::reporting::results::{BatchTiming, MutantResult, RunResults, SkippedMutant};
+use crate::actions::reporting::{table, CarriedResult};
+use crate::actions::schedule::{batch_by_file, schedule};
+use crate::actions::verify::{
+    check_runner, clippy_warnings, fetch_dependencies, run_baseline_tests, run_test_for_mutation,
+    warm_target,
+};
+use crate::actions::viability::ViabilityStats;
+use crate::actions::{analyze, reporting, runs};
+use crate::cli::MessageFormat;
+use crate::config::Settings;
+use crate::mutation::{Mutation, MutationRecord};
+use crate::plugin::Plugin;
+use crate::report::{MutationStatus, SkipReason};
 use eyre::{eyre, WrapErr};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashSet};
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
 
 fn create_mutated_project(
-    entries: &Vec<globwalk::DirEntry>,
-    project_path: &PathBuf,
-    mutation_root: &PathBuf,
+    entries: &[globwalk::DirEntry],
+    project_path: &Path,
+    mutation_root: &Path,
     mutation: &Mutation,
 ) -> eyre::Result<()> {
     log::debug!("Create mutation {}", mutation.get_mutation_id());
@@ -18,72 +38,747 @@ fn create_mutated_project(
         "Create mutation {} in function {} of file {} at line {}:{}",
         mutation.reason,
         mutation.function_name,
-        dunce::simplified(mutation.get_file_path()?.strip_prefix(project_path)?).display(),
+        mutation.relative_file()?,
         mutation.chunk.start_point.row + 1,
-        mutation.chunk.start_point.column
+        mutation.chunk.start_point.column + 1
     );
-    std::fs::create_dir_all(mutation_root)?;
+    copy_project(entries, project_path, mutation_root)?;
+
+    let mutant_file_path =
+        mutation_root.join(mutation.get_file_path()?.strip_prefix(project_path)?);
+    // Never write the mutation through a link to the original sources
+    if mutant_file_path.is_symlink() {
+        std::fs::remove_file(&mutant_file_path)?;
+    }
+    let mut file_to_mutate = File::create(&mutant_file_path)
+        .wrap_err(eyre!("Unable to open file {mutant_file_path:?}"))?;
+    file_to_mutate
+        .write_all(mutation.get_mutated_file()?.as_bytes())
+        .wrap_err(eyre!("Unable to write file {mutant_file_path:?}"))?;
+    file_to_mutate.flush()?;
+
+    Ok(())
+}
+
+/// Copy the entries of the project to a new project
+fn copy_project(
+    entries: &[globwalk::DirEntry],
+    project_path: &Path,
+    new_project_path: &Path,
+) -> eyre::Result<()> {
+    std::fs::create_dir_all(new_project_path)?;
 
     for entry in entries {
         let old_path = entry.path();
-        let relative_path = entry.path().strip_prefix(project_path.as_path())?;
-        let new_path = mutation_root.join(Path::new(&relative_path).to_path_buf());
+        let relative_path = entry.path().strip_prefix(project_path)?;
+        let new_path = new_project_path.join(relative_path);
 
-        if entry.file_type().is_dir() {
+        if entry.file_type().is_symlink() {
+            copy_symlink(old_path, &new_path, project_path, new_project_path)?;
+        } else if entry.file_type().is_dir() {
             std::fs::create_dir_all(&new_path)?;
         } else {
             std::fs::copy(old_path, new_path)?;
         }
     }
+    Ok(())
+}
 
-    let relative_path = std::fs::canonicalize(mutation.get_file_path()?)?;
-    let mutant_file_path = relative_path.strip_prefix(project_path.as_path())?;
-    let mutant_file_path = std::fs::canonicalize(mutation_root.join(mutant_file_path))
-        .wrap_err(eyre!("Unable to canonicalize path {mutant_file_path:?}"))?;
-    let mut file_to_mutate = File::create(&mutant_file_path)
-        .wrap_err(eyre!("Unable to open file {mutant_file_path:?}"))?;
-    file_to_mutate
-        .write_all(mutation.get_mutated_file()?.as_bytes())
-        .wrap_err(eyre!("Unable to write file {mutant_file_path:?}"))?;
-    file_to_mutate.flush()?;
+/// Copy the workspace without mutation, with the manifests rewritten as in mutant projects
+pub(crate) fn copy_workspace(
+    entries: &[globwalk::DirEntry],
+    settings: &Settings,
+    new_project_path: &Path,
+) -> eyre::Result<()> {
+    copy_project(entries, &settings.workspace_root, new_project_path)?;
+    write_rewrites(settings, new_project_path)
+}
 
+/// Write the rewritten manifests and configuration files in a copy of the workspace
+fn write_rewrites(settings: &Settings, new_project_path: &Path) -> eyre::Result<()> {
+    for (relative_path, content) in &settings.manifest_rewrites {
+        let path = new_project_path.join(relative_path);
+        // Never write through a link to the original files
+        if path.is_symlink() {
+            std::fs::remove_file(&path)?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)
+            .wrap_err(eyre!("Unable to write file {}", path.display()))?;
+    }
     Ok(())
 }
 
-pub fn generate_and_verify_mutants(
-    mutants: &mut Vec<Mutation>,
-    project_path: &PathBuf,
-    mutation_root: &PathBuf,
-    keep: bool,
+/// Copy a folder and its content, keeping the modification time of files
+///
+/// Cargo compares them to tell whether an artifact is up to date.
+pub(crate) fn copy_folder(path: &Path, new_path: &Path) -> eyre::Result<()> {
+    for entry in walkdir::WalkDir::new(path) {
+        let entry = entry?;
+        let destination = new_path.join(entry.path().strip_prefix(path)?);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&destination)?;
+        } else if entry.file_type().is_file() {
+            std::fs::copy(entry.path(), &destination)?;
+            File::options()
+                .write(true)
+                .open(&destination)?
+                .set_modified(entry.metadata()?.modified()?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Target of the copy of a link in the mutant project
+///
+/// Relative targets are resolved from the folder of the link. Targets inside the project resolve
+/// to the mutant project, relative ones stay relative and absolute ones are moved there. Targets
+/// outside of the project are made absolute, the mutant project being elsewhere.
+fn symlink_target(
+    link: &Path,
+    target: &Path,
+    project_path: &Path,
+    mutation_root: &Path,
+) -> PathBuf {
+    let resolved = normalize(&link.parent().unwrap_or(project_path).join(target));
+    match resolved.strip_prefix(project_path) {
+        Ok(_) if target.is_relative() => target.to_path_buf(),
+        Ok(relative_target) => mutation_root.join(relative_target),
+        Err(_) => resolved,
+    }
+}
+
+/// Link of the mutant project to the target of a link of the project
+fn copy_symlink(
+    link: &Path,
+    new_path: &Path,
+    project_path: &Path,
+    mutation_root: &Path,
 ) -> eyre::Result<()> {
-    log::info!("Generate mutant projects");
+    let target = std::fs::read_link(link)
+        .wrap_err(eyre!("Unable to read symbolic link {}", link.display()))?;
+    let target = symlink_target(link, &target, project_path, mutation_root);
+    #[cfg(unix)]
+    let linked = std::os::unix::fs::symlink(&target, new_path);
+    #[cfg(windows)]
+    let linked = if link.is_dir() {
+        std::os::windows::fs::symlink_dir(&target, new_path)
+    } else {
+        std::os::windows::fs::symlink_file(&target, new_path)
+    };
+    linked.wrap_err(eyre!(
+        "Unable to create symbolic link {}",
+        new_path.display()
+    ))
+}
+
+/// Create, verify and clean a single mutant project
+fn process_mutant(
+    mutation: &mut Mutation,
+    walker: &[globwalk::DirEntry],
+    settings: &Settings,
+    target_dir: Option<&Path>,
+    baseline_warnings: Option<&HashSet<String>>,
+) -> eyre::Result<()> {
+    let project_path = &settings.root_path;
+    let hooks = &settings.hooks;
+    let mutation_path = mutation.get_mutation_project_path()?.clone();
+    create_mutated_project(walker, &settings.workspace_root, &mutation_path, mutation)?;
+    write_rewrites(settings, &mutation_path)?;
+    // The copy holds the whole workspace
+    let mutation_path = mutation_path.join(&settings.member_path);
+    let scratch_path = mutation.get_scratch_path()?;
+    std::fs::create_dir_all(&scratch_path)?;
+
+    let mut envs = vec![
+        ("DARWIN_MUTANT_ID", mutation.get_mutation_id().to_string()),
+        ("DARWIN_MUTANT_PATH", mutation_path.display().to_string()),
+        ("DARWIN_PROJECT_PATH", project_path.display().to_string()),
+        ("DARWIN_SCRATCH", scratch_path.display().to_string()),
+    ];
+    if let Err(error) = run_hook("pre_mutant", &hooks.pre_mutant, &mutation_path, &envs) {
+        // Left without verdict, the mutant is reported as not attempted
+        log::warn!("Skip mutant #{}: {error}", mutation.get_mutation_id());
+        clean_mutation_project(mutation)?;
+        return Ok(());
+    }
+    run_test_for_mutation(mutation, &mutation_path, settings, target_dir)?;
+    let missing = mutation
+        .get_report()
+        .is_some_and(|report| report.status == MutationStatus::Success);
+    if let Some(baseline_warnings) = baseline_warnings.filter(|_| missing) {
+        let warnings = clippy_warnings(&mutation_path, target_dir, settings)?
+            .into_iter()
+            .filter(|warning| !baseline_warnings.contains(warning))
+            .collect();
+        mutation.set_warnings(warnings);
+    }
+    if let Some(report) = mutation.get_report() {
+        envs.push(("DARWIN_MUTANT_STATUS", report.status.name().to_string()));
+    }
+    run_hook("post_mutant", &hooks.post_mutant, &mutation_path, &envs)?;
+    reporting::stream_report(mutation, &settings.mutation_path)?;
+    mutation.release_output();
+
+    let kept_status = mutation
+        .get_report()
+        .map(|report| report.status)
+        .filter(|status| settings.keep.is_some_and(|keep| keep.retains(*status)));
+    let Some(status) = kept_status else {
+        clean_mutation_project(mutation)?;
+        return Ok(());
+    };
 
-    // Clean previous run
-    if Path::exists(mutation_root) {
-        log::debug!("Cleaning {}", mutation_root.display());
-        std::fs::remove_dir_all(mutation_root)?;
+    // Kept projects are named after their mutant so they can be browsed without the summary
+    let project_path = mutation.get_mutation_project_path()?.clone();
+    let kept_path = project_path.with_file_name(format!(
+        "mutant_{}_{}",
+        mutation.stable_id()?,
+        status.name()
+    ));
+    if kept_path.exists() {
+        std::fs::remove_dir_all(&kept_path)?;
     }
+    std::fs::rename(&project_path, &kept_path).wrap_err(eyre!(
+        "Unable to rename {} to {}",
+        project_path.display(),
+        kept_path.display()
+    ))?;
+    mutation.set_mutation_project_path(&kept_path);
+    std::fs::write(kept_path.join("MUTATION.txt"), mutation.manifest()?)?;
+    Ok(())
+}
+
+/// Entries of the workspace copied in each mutant project
+pub(crate) fn project_entries(settings: &Settings) -> eyre::Result<Vec<globwalk::DirEntry>> {
+    get_project_entries(&settings.workspace_root, settings)
+}
+
+/// Run the tests once without mutation, in a copy of the project as mutants are
+///
+/// Returns the duration of the tests in seconds, none if they don't pass
+fn verify_baseline(settings: &Settings) -> eyre::Result<Option<f64>> {
+    log::info!("Run the tests without mutation");
+    std::fs::create_dir_all(&settings.mutation_path)?;
+    let mutation_root = std::fs::canonicalize(&settings.mutation_path)
+        .wrap_err("Unable to get canonical mutation_root")?;
+    let baseline_path = mutation_root.join("baseline");
+    copy_workspace(&project_entries(settings)?, settings, &baseline_path)?;
+    let scratch_path = baseline_path.join("target").join("darwin-scratch");
+    std::fs::create_dir_all(&scratch_path)?;
+
+    let report = run_baseline_tests(
+        &baseline_path.join(&settings.member_path),
+        &scratch_path,
+        settings,
+    )?;
+    std::fs::remove_dir_all(&baseline_path)?;
+    match report.status {
+        MutationStatus::Success => Ok(report.duration.map(|duration| duration.as_secs_f64())),
+        status => {
+            println!(
+                "Tests without mutation don't pass ({}), their duration isn't compared",
+                status.name()
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Target folders of the workers, pre-built from the project without mutation
+///
+/// The project is built once in the folder of the first worker, whose target folder is copied
+/// for the other workers. A worker always builds its mutants at the same path, so that cargo
+/// reuses the dependencies and only rebuilds the crates of the workspace.
+fn warm_targets(
+    walker: &[globwalk::DirEntry],
+    settings: &Settings,
+    mutation_root: &Path,
+) -> eyre::Result<Vec<PathBuf>> {
+    let targets = (0..settings.jobs)
+        .map(|worker| mutation_root.join("targets").join(worker.to_string()))
+        .collect::<Vec<_>>();
+    log::info!("Build the project without mutation");
+    let project_path = worker_path(mutation_root, 0);
+    copy_workspace(walker, settings, &project_path)?;
+    let warmed = warm_target(
+        &project_path.join(&settings.member_path),
+        &targets[0],
+        settings,
+    );
+    std::fs::remove_dir_all(&project_path)?;
+    if let Err(error) = warmed {
+        println!("Unable to build the project without mutation, mutants are built from scratch:\n{error}");
+        return Ok(targets);
+    }
+    for target in &targets[1..] {
+        if target.exists() {
+            std::fs::remove_dir_all(target)?;
+        }
+        copy_folder(&targets[0], target).wrap_err(eyre!(
+            "Unable to copy target folder to {}",
+            target.display()
+        ))?;
+    }
+    Ok(targets)
+}
+
+/// Fetch the dependencies of the project once, in a copy of the project
+fn fetch(
+    walker: &[globwalk::DirEntry],
+    settings: &Settings,
+    mutation_root: &Path,
+) -> eyre::Result<()> {
+    log::info!("Fetch the dependencies of the project");
+    let project_path = mutation_root.join("fetch");
+    copy_workspace(walker, settings, &project_path)?;
+    let fetched = fetch_dependencies(&project_path.join(&settings.member_path), settings);
+    std::fs::remove_dir_all(&project_path)?;
+    fetched
+}
+
+/// Warnings of clippy on the project without mutation, the ones of missing mutants are compared to
+fn baseline_warnings(
+    walker: &[globwalk::DirEntry],
+    settings: &Settings,
+    mutation_root: &Path,
+    target_dir: Option<&Path>,
+) -> eyre::Result<HashSet<String>> {
+    log::info!("Run clippy on the project without mutation");
+    let project_path = mutation_root.join("clippy");
+    copy_workspace(walker, settings, &project_path)?;
+    let warnings = clippy_warnings(
+        &project_path.join(&settings.member_path),
+        target_dir,
+        settings,
+    );
+    std::fs::remove_dir_all(&project_path)?;
+    Ok(warnings?.into_iter().collect())
+}
+
+/// Target folders warmed for the workers, removed once mutants are verified, whether they all were
+/// or not, so that past runs don't each keep a copy of the target folder per worker
+struct WarmedTargets(Option<PathBuf>);
 
-    let walker = get_project_walker(project_path)?;
+impl Drop for WarmedTargets {
+    fn drop(&mut self) {
+        let Some(path) = self.0.as_ref().filter(|path| path.exists()) else {
+            return;
+        };
+        if let Err(error) = std::fs::remove_dir_all(path) {
+            log::warn!(
+                "Unable to remove target folders {}: {error}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Folder of the mutant project being verified by a worker
+fn worker_path(mutation_root: &Path, worker: usize) -> PathBuf {
+    mutation_root.join(format!("worker_{worker}"))
+}
+
+/// Verify mutants file by file, the files of the mutants most likely to survive first according to
+/// the previous run
+pub fn generate_and_verify_mutants(
+    mutants: &mut [Mutation],
+    settings: &Settings,
+    previous: &RunResults,
+) -> eyre::Result<()> {
+    check_runner(settings)?;
+    log::info!("Generate mutant projects");
+    let mutation_root = &settings.mutation_path;
     log::debug!("Creating {}", mutation_root.display());
     std::fs::create_dir_all(mutation_root)?;
 
     let mutation_root = std::fs::canonicalize(Path::new(&mutation_root))
         .wrap_err("Unable to get canonical mutation_root")?;
+    let walker = project_entries(settings)?;
 
-    let mut mutation_id = 0;
+    if !mutants.is_empty() {
+        fetch(&walker, settings, &mutation_root)?;
+    }
+    let warmed = !settings.isolated_targets && !mutants.is_empty();
+    let _warmed_targets =
+        WarmedTargets((warmed && !settings.keep_targets).then(|| mutation_root.join("targets")));
+    let targets = if warmed {
+        Some(warm_targets(&walker, settings, &mutation_root)?)
+    } else {
+        None
+    };
+    let baseline_warnings = if settings.clippy_survivors && !mutants.is_empty() {
+        Some(baseline_warnings(
+            &walker,
+            settings,
+            &mutation_root,
+            targets.as_ref().map(|targets| targets[0].as_path()),
+        )?)
+    } else {
+        None
+    };
+    for mutation in mutants.iter_mut() {
+        let mutation_path = mutation_root.join(format!("{}", mutation.get_mutation_id()));
+        // Leftover of a previous verification of the same mutant
+        if mutation_path.exists() {
+            std::fs::remove_dir_all(&mutation_path)?;
+        }
+        mutation.set_mutation_project_path(&mutation_path);
+    }
+
+    // Workers pull the mutants of a file at once until none are left
+    let queue =
+        Mutex::new(batch_by_file(schedule(mutants, previous, settings.order)?)?.into_iter());
+    std::thread::scope(|scope| {
+        let workers = (0..settings.jobs)
+            .map(|worker| {
+                let queue = &queue;
+                let walker = &walker;
+                let mutation_root = &mutation_root;
+                let target_dir = targets.as_ref().map(|targets| targets[worker].as_path());
+                let baseline_warnings = baseline_warnings.as_ref();
+                scope.spawn(move || -> eyre::Result<()> {
+                    loop {
+                        let Some(batch) = queue.lock().map_err(|_| eyre!("Poisoned queue"))?.next()
+                        else {
+                            return Ok(());
+                        };
+                        for mutation in batch {
+                            if target_dir.is_some() {
+                                let project_path = worker_path(mutation_root, worker);
+                                if project_path.exists() {
+                                    std::fs::remove_dir_all(&project_path)?;
+                                }
+                                mutation.set_mutation_project_path(&project_path);
+                            }
+                            process_mutant(
+                                mutation,
+                                walker,
+                                settings,
+                                target_dir,
+                                baseline_warnings,
+                            )?;
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
 
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().map_err(|_| eyre!("Mutant worker panicked"))?)
+    })?;
+
+    let mut viability = ViabilityStats::load(&settings.mutation_root);
+    viability.record(mutants);
+    if let Err(error) = viability.save(&settings.mutation_root) {
+        log::warn!("Unable to save viability statistics: {error}");
+    }
+    Ok(())
+}
+
+/// Hash of the content of the mutated files, by path relative to the project
+fn file_hashes(mutants: &[Mutation]) -> eyre::Result<BTreeMap<String, u64>> {
+    let mut hashes = BTreeMap::new();
     for mutation in mutants {
-        let mutation_path = mutation_root.join(format!("{mutation_id}"));
-        mutation.set_mutation_project_path(&mutation_path);
-        mutation.set_mutation_id(mutation_id);
-        create_mutated_project(&walker, &project_path, &mutation_path, mutation)?;
-        run_test_for_mutation(mutation, project_path)?;
-        if !keep {
-            clean_mutation_project(mutation)?;
+        if let Entry::Vacant(entry) = hashes.entry(mutation.record()?.file) {
+            entry.insert(content_hash(mutation.source()?));
         }
-        mutation_id += 1;
     }
+    Ok(hashes)
+}
 
+/// Split mutants between the ones to verify and the ones keeping their previous verdict
+///
+/// A verdict is kept if the file of the mutant hasn't changed and the mutant was caught or killed
+fn carry_verdicts(
+    mutants: Vec<Mutation>,
+    previous: &RunResults,
+    hashes: &BTreeMap<String, u64>,
+    mutation_root: &Path,
+) -> eyre::Result<(Vec<Mutation>, Vec<CarriedResult>)> {
+    let mut pending = vec![];
+    let mut carried = vec![];
+    for mutation in mutants {
+        let record = mutation.record()?;
+        let unchanged = previous
+            .hashes
+            .get(&record.file)
+            .is_some_and(|hash| hashes.get(&record.file) == Some(hash));
+        let verdict = previous
+            .find(&record.stable_id)
+            .filter(|result| {
+                unchanged
+                    && matches!(
+                        result.status,
+                        MutationStatus::Fail | MutationStatus::CompilationFailed
+                    )
+            })
+            .and_then(|result| {
+                let report = std::fs::read_to_string(mutation_root.join(&result.log)).ok()?;
+                Some((result, report))
+            });
+        match verdict {
+            Some((result, report)) => carried.push(carried_result(record, result, report)),
+            None => pending.push(mutation),
+        }
+    }
+    Ok((pending, carried))
+}
+
+/// Result of a previous run given to the same mutant in this run
+fn carried_result(record: MutationRecord, result: &MutantResult, report: String) -> CarriedResult {
+    CarriedResult {
+        result: MutantResult {
+            log: Path::new("reports").join(format!("mutation_{}.log", record.id)),
+            mutation: record,
+            ..result.clone()
+        },
+        report,
+    }
+}
+
+/// Split mutants between the ones to verify and the ones whose verdict is cached
+fn cached_verdicts(
+    mutants: Vec<Mutation>,
+    cache: &VerdictCache,
+    hashes: &BTreeMap<String, u64>,
+) -> eyre::Result<(Vec<Mutation>, Vec<CarriedResult>)> {
+    let mut pending = vec![];
+    let mut carried = vec![];
+    for mutation in mutants {
+        let record = mutation.record()?;
+        let verdict = hashes
+            .get(&record.file)
+            .and_then(|hash| cache.get(&record.stable_id, *hash));
+        match verdict {
+            Some(verdict) => carried.push(carried_result(record, &verdict.result, verdict.report)),
+            None => pending.push(mutation),
+        }
+    }
+    Ok((pending, carried))
+}
+
+/// Cache the verdicts of the mutants verified by the run which can be reused, a failure only
+/// loses them
+fn cache_verdicts(
+    results: &RunResults,
+    verified: &[Mutation],
+    cache: &VerdictCache,
+    mutation_root: &Path,
+) -> eyre::Result<()> {
+    let verified = verified
+        .iter()
+        .map(Mutation::get_mutation_id)
+        .collect::<HashSet<_>>();
+    for result in &results.mutants {
+        let Some(hash) = results.hashes.get(&result.mutation.file) else {
+            continue;
+        };
+        if !verified.contains(&result.mutation.id) || !VerdictCache::caches(result.status) {
+            continue;
+        }
+        let verdict = CachedVerdict {
+            result: result.clone(),
+            report: std::fs::read_to_string(mutation_root.join(&result.log))?,
+        };
+        cache.insert(&result.mutation.stable_id, *hash, &verdict)?;
+    }
     Ok(())
 }
+
+/// Analyze the project then verify its mutants in the folder of a new run, which becomes the
+/// latest run once reported
+///
+/// In incremental mode, mutants of unchanged files keep the verdict of the previous run
+pub(crate) fn verify_project(settings: &Settings, plugins: &[Plugin]) -> eyre::Result<RunResults> {
+    let start = Instant::now();
+    let previous_path = settings.mutation_path.clone();
+    let settings = &settings.new_run()?;
+    let coverage = Coverage::from_settings(settings)?;
+    let analyze::Analysis {
+        mutants,
+        skipped,
+        dead_code,
+        mut excluded,
+        mut not_attempted,
+    } = analyze::analyze(settings, plugins)?;
+    for skipped in &skipped {
+        println!("{}", skipped.simple());
+    }
+    if let Some(line) = excluded.simple() {
+        println!("{line}");
+    }
+    let hashes = file_hashes(&mutants)?;
+    let previous = RunResults::load(&previous_path).unwrap_or_default();
+    let (mutants, mut carried) = if settings.incremental {
+        carry_verdicts(mutants, &previous, &hashes, &previous_path)?
+    } else {
+        (mutants, vec![])
+    };
+    if !carried.is_empty() {
+        println!(
+            "{} mutants keep the verdict of the previous run",
+            carried.len()
+        );
+    }
+    let cache = if settings.cache {
+        VerdictCache::new(settings)
+            .map_err(|error| log::warn!("Verdicts aren't cached: {error:#}"))
+            .ok()
+    } else {
+        None
+    };
+    let (mut mutants, cached) = match &cache {
+        Some(cache) => cached_verdicts(mutants, cache, &hashes)?,
+        None => (mutants, vec![]),
+    };
+    if !cached.is_empty() {
+        println!("{} mutants keep a cached verdict", cached.len());
+    }
+    carried.extend(cached);
+
+    let baseline = if settings.timing {
+        verify_baseline(settings)?
+    } else {
+        None
+    };
+    if let Some(baseline) = baseline {
+        println!("Tests without mutation ran in {baseline:.2}s");
+    }
+    generate_and_verify_mutants(&mut mutants, settings, &previous)?;
+    for mutation in mutants
+        .iter()
+        .filter(|mutation| mutation.get_report().is_none())
+    {
+        not_attempted.push(SkippedMutant::new(mutation, SkipReason::Hook)?);
+        excluded.hook += 1;
+    }
+    let batches = if settings.timing {
+        BatchTiming::from_results(&RunResults::from_mutations(&mutants)?.mutants)
+    } else {
+        vec![]
+    };
+    let results = RunResults {
+        skipped,
+        dead_code,
+        excluded,
+        not_attempted,
+        hashes,
+        baseline,
+        batches,
+        duration: Some(start.elapsed().as_secs_f64()),
+        ..Default::default()
+    };
+    let results = reporting::generate_reports(
+        &mutants,
+        carried,
+        results,
+        coverage.as_ref(),
+        &settings.mutation_path,
+        settings.sort,
+    )?;
+    runs::finish_run(
+        &settings.mutation_root,
+        &settings.mutation_path,
+        settings.keep_reports,
+    )?;
+    if let Some(cache) = &cache {
+        if let Err(error) = cache_verdicts(&results, &mutants, cache, &settings.mutation_path) {
+            log::warn!("Unable to cache verdicts: {error}");
+        }
+    }
+    if !results.weak_oracles.is_empty() {
+        println!("Tests execute but don't assert:");
+        for weak_oracle in &results.weak_oracles {
+            println!("{}", weak_oracle.simple());
+        }
+    }
+    let outliers = results.timing_outliers();
+    if !outliers.is_empty() {
+        println!("Tests much faster or slower than without mutation:");
+        for line in outliers {
+            println!("{line}");
+        }
+    }
+    if !results.batches.is_empty() {
+        println!(
+            "Estimated build time saved by verifying the mutants of a file one after the other:"
+        );
+        for batch in &results.batches {
+            println!("{}", batch.simple());
+        }
+    }
+    print!("\n{}", table::table(&results));
+    let kill_rates = table::kill_rates(&results);
+    if !kill_rates.is_empty() {
+        println!("\nKilled at compile time by operator:");
+        for line in kill_rates {
+            println!("  {line}");
+        }
+    }
+    for warning in table::unviable_operators(&results) {
+        println!("{warning}");
+    }
+    let weakest = table::weakest_files(&results, settings.weakest);
+    if !weakest.is_empty() {
+        println!("\nWeakest files, to test next:");
+        for line in weakest {
+            println!("  {line}");
+        }
+    }
+    print_survivors(&results, settings);
+    Ok(results)
+}
+
+/// Print the mutants which survived as rustc's short messages, if asked by the settings, in the
+/// order of the settings
+///
+/// Editors match such lines to jump to the mutated code, like the messages of a build.
+pub(crate) fn print_survivors(results: &RunResults, settings: &Settings) {
+    if settings.message_format != MessageFormat::Short {
+        return;
+    }
+    for result in results.sorted(settings.sort) {
+        if result.status == MutationStatus::Success {
+            println!("{}", result.diagnostic());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::generate::symlink_target;
+
+    #[test]
+    fn test_symlink_target() {
+        let target = |link: &str, target: &str| {
+            symlink_target(
+                Path::new(link),
+                Path::new(target),
+                Path::new("/project"),
+                Path::new("/darwin/0"),
+            )
+        };
+        assert_eq!(
+            target("/project/src/data", "../assets"),
+            Path::new("../assets")
+        );
+        assert_eq!(
+            target("/project/src/data", "/project/assets"),
+            Path::new("/darwin/0/assets")
+        );
+        assert_eq!(
+            target("/project/src/data", "../../shared/assets"),
+            Path::new("/shared/assets")
+        );
+        assert_eq!(
+            target("/project/data", "/shared/assets"),
+            Path::new("/shared/assets")
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+use std::process::Command;
+
+use eyre::eyre;
+
+/// User defined shell commands run around mutants verification
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Hooks {
+    /// Run before each mutant is built and tested
+    pub(crate) pre_mutant: Option<String>,
+    /// Run after each mutant verdict is known
+    pub(crate) post_mutant: Option<String>,
+    /// Run once all reports are generated
+    pub(crate) post_run: Option<String>,
+}
+
+/// Command run through the platform shell
+pub(crate) fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    }
+}
+
+/// Run a hook command through the platform shell
+///
+/// The hook inherits darwin's stdout and stderr, variables are exported
+/// in the hook environment. A non-zero exit code is an error.
+pub(crate) fn run_hook(
+    name: &str,
+    command: &Option<String>,
+    current_dir: &Path,
+    envs: &[(&str, String)],
+) -> eyre::Result<()> {
+    let Some(command) = command else {
+        return Ok(());
+    };
+
+    log::debug!("Run {name} hook: {command}");
+    let status = shell(command)
+        .current_dir(current_dir)
+        .envs(envs.iter().map(|(key, value)| (key, value)))
+        .status()?;
+
+    if !status.success() {
+        return Err(eyre!("Hook {name} `{command}` failed with {status}"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::hooks::run_hook;
+    use crate::scratch::ScratchFolder;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_environment() {
+        let folder = ScratchFolder::new("hooks");
+        let command =
+            Some("echo \"$DARWIN_MUTANT_ID $DARWIN_MUTANT_STATUS\" > hook.txt".to_string());
+        let envs = [
+            ("DARWIN_MUTANT_ID", "12".to_string()),
+            ("DARWIN_MUTANT_STATUS", "missing".to_string()),
+        ];
+        run_hook("post_mutant", &command, &folder, &envs).unwrap();
+        let content = std::fs::read_to_string(folder.join("hook.txt")).unwrap();
+        assert_eq!(content, "12 missing\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_hook() {
+        let folder = ScratchFolder::new("hooks");
+        let command = Some("exit 3".to_string());
+        let error = run_hook("pre_mutant", &command, &folder, &[]).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Hook pre_mutant `exit 3` failed"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_no_hook() {
+        let folder = ScratchFolder::new("hooks");
+        run_hook("post_run", &None, &folder, &[]).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use eyre::{eyre, WrapErr};
+
+use crate::actions::get_project_walker;
+use crate::actions::verify::DEFAULT_PROPERTY_CASES;
+
+/// Directories holding code which is rarely worth mutating
+static EXCLUDED_DIRECTORIES: [&str; 4] = ["benches", "examples", "generated", "vendor"];
+/// Number of Rust files above which a directory is considered large
+static LARGE_DIRECTORY: usize = 100;
+
+/// Test frameworks dependencies and the attribute marking their tests
+static TEST_FRAMEWORKS: [(&str, &str); 3] = [
+    ("rstest", "rstest"),
+    ("test-case", "test_case"),
+    ("async-std", "async_std::test"),
+];
+/// Dependencies making test suites slow
+static SLOW_FRAMEWORKS: [&str; 2] = ["proptest", "quickcheck"];
+
+/// What has been learned about the project
+#[derive(Debug, Default)]
+struct ProjectInspection {
+    workspace_members: Vec<String>,
+    dependencies: Vec<String>,
+    excluded_directories: Vec<String>,
+    large_directories: Vec<(String, usize)>,
+}
+
+fn inspect(root_path: &Path) -> eyre::Result<ProjectInspection> {
+    let manifest_path = root_path.join("Cargo.toml");
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .wrap_err(eyre!("Unable to read {manifest_path:?}"))?;
+    let manifest: toml::Table =
+        toml::from_str(&manifest).wrap_err(eyre!("Invalid manifest {manifest_path:?}"))?;
+
+    let workspace_members = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(toml::Value::as_array)
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|member| member.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let dependencies = ["dependencies", "dev-dependencies"]
+        .iter()
+        .filter_map(|section| manifest.get(*section).and_then(toml::Value::as_table))
+        .flat_map(|table| table.keys().cloned())
+        .collect();
+
+    let mut rust_files_per_directory: BTreeMap<String, usize> = BTreeMap::new();
+    let mut excluded_directories = vec![];
+    for entry in get_project_walker(&root_path.to_path_buf(), false)? {
+        let relative_path = entry.path().strip_prefix(root_path)?;
+        if entry.file_type().is_dir() {
+            let is_excluded = relative_path.file_name().is_some_and(|name| {
+                EXCLUDED_DIRECTORIES.contains(&name.to_string_lossy().as_ref())
+            });
+            if is_excluded {
+                excluded_directories.push(relative_path.to_string_lossy().replace('\\', "/"));
+            }
+        } else if relative_path
+            .extension()
+            .is_some_and(|extension| extension == "rs")
+        {
+            if let Some(parent) = relative_path.parent() {
+                *rust_files_per_directory
+                    .entry(parent.to_string_lossy().replace('\\', "/"))
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let large_directories = rust_files_per_directory
+        .into_iter()
+        .filter(|(_, count)| *count > LARGE_DIRECTORY)
+        .collect();
+
+    Ok(ProjectInspection {
+        workspace_members,
+        dependencies,
+        excluded_directories,
+        large_directories,
+    })
+}
+
+fn render(inspection: &ProjectInspection) -> Result<String, std::fmt::Error> {
+    let mut config = String::new();
+    writeln!(config, "# Darwin configuration, CLI flags take precedence")?;
+    writeln!(config, "# See https://github.com/Akanoa/cargo-darwin")?;
+    writeln!(config)?;
+
+    if !inspection.workspace_members.is_empty() {
+        writeln!(config, "# Workspace members:")?;
+        for member in &inspection.workspace_members {
+            writeln!(config, "#   - {member}")?;
+        }
+        writeln!(config)?;
+    }
+
+    writeln!(
+        config,
+        "# Root path to mutated projects, relative to the project"
+    )?;
+    writeln!(config, "mutation_path = \"target/darwin\"")?;
+    writeln!(
+        config,
+        "# Keep project folders after test, `true` or only the \"missing\" or \"killed\" mutants"
+    )?;
+    writeln!(config, "keep = false")?;
+
+    let slow_frameworks = SLOW_FRAMEWORKS
+        .iter()
+        .filter(|framework| inspection.dependencies.iter().any(|dep| dep == *framework))
+        .collect::<Vec<_>>();
+    writeln!(config, "# Tests timeout in seconds")?;
+    if slow_frameworks.is_empty() {
+        writeln!(config, "timeout = 60")?;
+    } else {
+        let frameworks = slow_frameworks
+            .iter()
+            .map(|framework| framework.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(config, "# Raised because of {frameworks}")?;
+        writeln!(config, "timeout = 180")?;
+        writeln!(config, "# Number of cases of property tests of mutants")?;
+        writeln!(config, "property_cases = {DEFAULT_PROPERTY_CASES}")?;
+    }
+    writeln!(config, "# Number of mutants verified in parallel")?;
+    writeln!(config, "jobs = 1")?;
+    writeln!(config, "# Run doctests along other tests")?;
+    writeln!(config, "doctests = true")?;
+    writeln!(config)?;
+
+    writeln!(config, "# Files never mutated, relative to the project")?;
+    writeln!(config, "exclude = [")?;
+    writeln!(config, "    \"build.rs\",")?;
+    for directory in &inspection.excluded_directories {
+        writeln!(config, "    \"{directory}/**\",")?;
+    }
+    for (directory, count) in &inspection.large_directories {
+        writeln!(config, "    # {count} Rust files")?;
+        writeln!(config, "    # \"{directory}/**\",")?;
+    }
+    writeln!(config, "]")?;
+    writeln!(config)?;
+
+    writeln!(config, "# Operators families enabled, all if not defined")?;
+    writeln!(config, "# operators = [\"arithmetic\"]")?;
+    writeln!(config)?;
+
+    let test_attributes = TEST_FRAMEWORKS
+        .iter()
+        .filter(|(dependency, _)| inspection.dependencies.iter().any(|dep| dep == dependency))
+        .map(|(_, attribute)| format!("\"{attribute}\""))
+        .collect::<Vec<_>>();
+    writeln!(
+        config,
+        "# Additional attributes marking a function as a test, `test` and `tokio::test` are built-in"
+    )?;
+    writeln!(config, "test_attributes = [{}]", test_attributes.join(", "))?;
+    writeln!(config)?;
+
+    writeln!(config, "# Operators rules per path")?;
+    writeln!(config, "# [[paths]]")?;
+    writeln!(config, "# glob = \"src/math/**\"")?;
+    writeln!(config, "# operators = [\"arithmetic\"]")?;
+    writeln!(config)?;
+
+    writeln!(config, "[hooks]")?;
+    writeln!(config, "# pre_mutant = \"docker compose up -d\"")?;
+    writeln!(config, "# post_mutant = \"./upload.sh\"")?;
+    writeln!(config, "# post_run = \"./notify.sh\"")?;
+
+    Ok(config)
+}
+
+/// Inspect the project and write a starter `darwin.toml`
+pub(crate) fn init(root_path: &Path, force: bool) -> eyre::Result<()> {
+    let root_path =
+        std::fs::canonicalize(root_path).wrap_err(eyre!("Unable to find project {root_path:?}"))?;
+    let config_path = root_path.join("darwin.toml");
+    if config_path.exists() && !force {
+        return Err(eyre!(
+            "{} already exists, use --force to overwrite it",
+            config_path.display()
+        ));
+    }
+
+    let inspection = inspect(&root_path)?;
+    log::debug!("Project inspection {inspection:?}");
+    std::fs::write(&config_path, render(&inspection)?)?;
+    println!("Configuration written to {}", config_path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::init::init;
+    use crate::config::Config;
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_init() {
+        let project = ScratchFolder::new("init");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::create_dir_all(project.join("benches")).unwrap();
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"init\"\n\n[dev-dependencies]\nproptest = \"1\"\nrstest = \"0.18\"\n",
+        )
+        .unwrap();
+        std::fs::write(project.join("src/lib.rs"), "").unwrap();
+        std::fs::write(project.join("benches/bench.rs"), "").unwrap();
+
+        let written = init(&project, false);
+        let content = std::fs::read_to_string(project.join("darwin.toml")).unwrap();
+        let existing = init(&project, false);
+        let forced = init(&project, true);
+
+        assert!(written.is_ok());
+        assert!(existing.unwrap_err().to_string().contains("--force"));
+        assert!(forced.is_ok());
+        // The starter configuration is a valid one
+        let config: Config = toml::from_str(&content).unwrap();
+        assert_eq!(config.exclude, vec!["build.rs", "benches/**"]);
+        assert_eq!(config.timeout, Some(180));
+        assert!(config.property_cases.is_some());
+        assert_eq!(config.test_attributes, vec!["rstest"]);
+    }
+}
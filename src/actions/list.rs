@@ -0,0 +1,129 @@
+use serde::Serialize;
+
+use crate::actions::reporting::results::SCHEMA_VERSION;
+use crate::cli::Format;
+use crate::mutation::{Mutation, MutationRecord};
+
+/// JSON document of `list --format json`
+#[derive(Serialize)]
+struct MutantList {
+    schema_version: u32,
+    mutants: Vec<ListedMutant>,
+}
+
+/// Mutant of `list --format json`
+#[derive(Serialize)]
+struct ListedMutant {
+    #[serde(flatten)]
+    record: MutationRecord,
+    /// Unified diff of the mutant, only with `--diff`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<String>,
+}
+
+/// Print the mutants with their stable IDs, and their diff if asked
+pub(crate) fn list(mutations: &[Mutation], format: Format, diff: bool) -> eyre::Result<()> {
+    match format {
+        Format::Text => {
+            for mutation in mutations {
+                let MutationRecord {
+                    id,
+                    stable_id,
+                    file,
+                    function,
+                    reason,
+                    line,
+                    column,
+                    ..
+                } = mutation.record()?;
+                println!(
+                    "#{id} {stable_id} : {reason} in function \"{function}\" of file {file} at line {line}:{column}"
+                );
+                if diff {
+                    println!("{}", mutation.diff(true)?)
+                }
+            }
+        }
+        Format::Json => println!("{}", json_list(mutations, diff)?),
+    }
+
+    Ok(())
+}
+
+/// JSON document of the mutants, the same on every platform for the same sources
+fn json_list(mutations: &[Mutation], diff: bool) -> eyre::Result<String> {
+    let mutants = mutations
+        .iter()
+        .map(|mutation| {
+            Ok(ListedMutant {
+                record: mutation.record()?,
+                diff: diff.then(|| mutation.diff(false)).transpose()?,
+            })
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let list = MutantList {
+        schema_version: SCHEMA_VERSION,
+        mutants,
+    };
+    Ok(serde_json::to_string_pretty(&list)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::analyze::sort_mutations;
+    use crate::actions::list::json_list;
+    use crate::mutation::{Mutation, MutationChunk};
+
+    #[test]
+    fn test_json_list() {
+        let content = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let mutant = |relative_path: &str, replacement: &str| {
+            let mut mutation =
+                Mutation::new(replacement, MutationChunk::from_range(content, 35..36))
+                    .with_reason(&format!("replace + by {replacement}"))
+                    .with_function_name("add")
+                    .with_operator("arithmetic")
+                    .with_context("binary_expression");
+            mutation.set_file_path(
+                &Path::new("/work").join(relative_path),
+                Path::new(relative_path),
+            );
+            mutation
+        };
+        // Walked in another order and with other separators on Windows
+        let mut mutants = vec![
+            mutant("src\\b.rs", "-"),
+            mutant("src/a.rs", "-"),
+            mutant("src/a.rs", "*"),
+        ];
+        sort_mutations(&mut mutants);
+        for (id, mutation) in mutants.iter_mut().enumerate() {
+            mutation.set_mutation_id(id);
+        }
+
+        let list: serde_json::Value =
+            serde_json::from_str(&json_list(&mutants, false).unwrap()).unwrap();
+        let ids = list["mutants"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|mutant| mutant["stable_id"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                "src-a-add-2-7-mul",
+                "src-a-add-2-7-sub",
+                "src-b-add-2-7-sub"
+            ]
+        );
+        assert_eq!(list["mutants"][2]["file"], "src/b.rs");
+        assert_eq!(list["mutants"][2]["id"], 2);
+        assert_eq!(
+            json_list(&mutants, false).unwrap(),
+            json_list(&mutants, false).unwrap()
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use std::path::{Component, Path, PathBuf};
+
+use eyre::{eyre, WrapErr};
+
+use crate::actions::validate::{Metadata, Package};
+
+/// Resolve `.` and `..` components without accessing the file system
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Path with symbolic links resolved, or only normalized if it doesn't exist
+fn canonical(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| normalize(path))
+}
+
+/// Make absolute the `path` keys of the manifest pointing outside of the workspace
+///
+/// Paths are compared once their symbolic links are resolved, a workspace reached through a link
+/// contains the paths of its real folder. Returns whether the manifest has changed.
+fn rewrite_paths(table: &mut toml::Table, manifest_dir: &Path, workspace_root: &Path) -> bool {
+    rewrite_canonical_paths(table, manifest_dir, &canonical(workspace_root))
+}
+
+fn rewrite_canonical_paths(
+    table: &mut toml::Table,
+    manifest_dir: &Path,
+    workspace_root: &Path,
+) -> bool {
+    let mut changed = false;
+    for (key, value) in table.iter_mut() {
+        match value {
+            toml::Value::String(path) if key == "path" => {
+                let resolved = normalize(&manifest_dir.join(&*path));
+                if !canonical(&resolved).starts_with(workspace_root) {
+                    *path = resolved.to_string_lossy().to_string();
+                    changed = true;
+                }
+            }
+            toml::Value::Table(table) => {
+                changed |= rewrite_canonical_paths(table, manifest_dir, workspace_root);
+            }
+            toml::Value::Array(values) => {
+                for value in values {
+                    if let toml::Value::Table(table) = value {
+                        changed |= rewrite_canonical_paths(table, manifest_dir, workspace_root);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    changed
+}
+
+/// Manifests of the workspace referencing paths outside of it, rewritten with absolute paths
+///
+/// As mutant projects are copies of the workspace, relative paths to sibling crates would break.
+/// Returns the manifests paths relative to the workspace root and their new content.
+pub(crate) fn rewrite_external_paths(metadata: &Metadata) -> eyre::Result<Vec<(PathBuf, String)>> {
+    let workspace_root = &metadata.workspace_root;
+    let mut manifests = vec![workspace_root.join("Cargo.toml")];
+    for package in &metadata.packages {
+        if !manifests.contains(&package.manifest_path) {
+            manifests.push(package.manifest_path.clone());
+        }
+    }
+
+    let mut rewrites = vec![];
+    for manifest_path in manifests {
+        let Ok(relative_path) = manifest_path.strip_prefix(workspace_root) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(&manifest_path) else {
+            continue;
+        };
+        let mut manifest: toml::Table = toml::from_str(&content)
+            .wrap_err(eyre!("Invalid manifest {}", manifest_path.display()))?;
+        let manifest_dir = manifest_path.parent().unwrap_or(workspace_root);
+        if rewrite_paths(&mut manifest, manifest_dir, workspace_root) {
+            log::debug!(
+                "Rewrite paths outside of the workspace in {}",
+                manifest_path.display()
+            );
+            rewrites.push((relative_path.to_path_buf(), toml::to_string(&manifest)?));
+        }
+    }
+    Ok(rewrites)
+}
+
+/// Dependencies tables of a manifest, including the target specific ones
+fn dependencies_tables(manifest: &mut toml::Table) -> Vec<&mut toml::Table> {
+    let mut tables = vec![];
+    let mut targets = None;
+    for (key, value) in manifest.iter_mut() {
+        match (key.as_str(), value) {
+            (
+                "dependencies" | "dev-dependencies" | "build-dependencies",
+                toml::Value::Table(table),
+            ) => tables.push(table),
+            ("target", toml::Value::Table(table)) => targets = Some(table),
+            _ => {}
+        }
+    }
+    for (_, target) in targets.into_iter().flat_map(|targets| targets.iter_mut()) {
+        if let toml::Value::Table(target) = target {
+            tables.extend(dependencies_tables(target));
+        }
+    }
+    tables
+}
+
+/// Whether the value is `{ workspace = true, .. }`
+fn inherited(value: &toml::Value) -> bool {
+    value
+        .get("workspace")
+        .and_then(toml::Value::as_bool)
+        .unwrap_or_default()
+}
+
+/// Replace the fields and dependencies inherited from the workspace by their value
+fn inherit_workspace(manifest: &mut toml::Table, workspace: &toml::Table) -> eyre::Result<()> {
+    let empty = toml::Table::new();
+    let table = |key: &str| {
+        workspace
+            .get(key)
+            .and_then(toml::Value::as_table)
+            .unwrap_or(&empty)
+    };
+
+    if let Some(toml::Value::Table(package)) = manifest.get_mut("package") {
+        package.remove("workspace");
+        for (key, value) in package.iter_mut() {
+            if inherited(value) {
+                *value = table("package")
+                    .get(key)
+                    .cloned()
+                    .ok_or(eyre!("Field package.{key} isn't defined by the workspace"))?;
+            }
+        }
+    }
+
+    if manifest.get("lints").is_some_and(inherited) {
+        manifest.insert(
+            "lints".to_string(),
+            toml::Value::Table(table("lints").clone()),
+        );
+    }
+
+    for dependencies in dependencies_tables(manifest) {
+        for (name, dependency) in dependencies.iter_mut() {
+            if !inherited(dependency) {
+                continue;
+            }
+            let mut resolved = match table("dependencies").get(name) {
+                Some(toml::Value::String(version)) => {
+                    toml::Table::from_iter([("version".to_string(), version.clone().into())])
+                }
+                Some(toml::Value::Table(resolved)) => resolved.clone(),
+                _ => return Err(eyre!("Dependency {name} isn't defined by the workspace")),
+            };
+            if let toml::Value::Table(member) = dependency {
+                for (key, value) in member.iter() {
+                    match (key.as_str(), resolved.get_mut(key)) {
+                        ("workspace", _) => {}
+                        ("features", Some(toml::Value::Array(features))) => {
+                            for feature in value.as_array().into_iter().flatten() {
+                                if !features.contains(feature) {
+                                    features.push(feature.clone());
+                                }
+                            }
+                        }
+                        _ => {
+                            resolved.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+            *dependency = toml::Value::Table(resolved);
+        }
+    }
+    Ok(())
+}
+
+/// Manifest of a member of the workspace, which builds without the rest of the workspace
+///
+/// Fields and dependencies inherited from the workspace are resolved, paths outside of the
+/// member made absolute, and the `patch` and `profile` sections of the workspace carried over.
+pub(crate) fn standalone_manifest(metadata: &Metadata, package: &Package) -> eyre::Result<String> {
+    let workspace_root = &metadata.workspace_root;
+    let package_root = package.manifest_path.parent().unwrap_or(workspace_root);
+    let workspace_path = workspace_root.join("Cargo.toml");
+    let mut workspace_manifest: toml::Table =
+        toml::from_str(&std::fs::read_to_string(&workspace_path).wrap_err(eyre!(
+            "Unable to read manifest {}",
+            workspace_path.display()
+        ))?)
+        .wrap_err(eyre!("Invalid manifest {}", workspace_path.display()))?;
+    rewrite_paths(&mut workspace_manifest, workspace_root, package_root);
+
+    let mut manifest: toml::Table = toml::from_str(
+        &std::fs::read_to_string(&package.manifest_path).wrap_err(eyre!(
+            "Unable to read manifest {}",
+            package.manifest_path.display()
+        ))?,
+    )
+    .wrap_err(eyre!(
+        "Invalid manifest {}",
+        package.manifest_path.display()
+    ))?;
+    if let Some(toml::Value::Table(workspace)) = workspace_manifest.get("workspace") {
+        inherit_workspace(&mut manifest, workspace)?;
+    }
+    rewrite_paths(&mut manifest, package_root, package_root);
+
+    for key in ["patch", "profile"] {
+        if let Some(value) = workspace_manifest.get(key) {
+            manifest.entry(key).or_insert_with(|| value.clone());
+        }
+    }
+    // Never look for a workspace in the parents of the mutant project
+    manifest.insert("workspace".to_string(), toml::Table::new().into());
+
+    Ok(toml::to_string(&manifest)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::manifest::{inherit_workspace, rewrite_paths};
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_rewrite_paths() {
+        let mut manifest: toml::Table = toml::from_str(
+            r#"
+            [dependencies]
+            sibling = { path = "../../../sibling" }
+            member = { path = "../member" }
+            serde = "1"
+
+            [[bin]]
+            name = "tool"
+            path = "src/main.rs"
+            "#,
+        )
+        .unwrap();
+        assert!(rewrite_paths(
+            &mut manifest,
+            Path::new("/work/ws/crates/a"),
+            Path::new("/work/ws")
+        ));
+        let dependencies = manifest["dependencies"].as_table().unwrap();
+        assert_eq!(
+            dependencies["sibling"]["path"].as_str(),
+            Some("/work/sibling")
+        );
+        assert_eq!(dependencies["member"]["path"].as_str(), Some("../member"));
+        assert_eq!(manifest["bin"][0]["path"].as_str(), Some("src/main.rs"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rewrite_linked_paths() {
+        let root = ScratchFolder::new("linked");
+        let workspace = root.join("ws");
+        std::fs::create_dir_all(workspace.join("crates").join("a")).unwrap();
+        std::fs::create_dir_all(workspace.join("crates").join("member")).unwrap();
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&workspace, &link).unwrap();
+        let rewritten = |manifest_dir: &Path, workspace_root: &Path| {
+            let mut manifest: toml::Table =
+                toml::from_str("[dependencies]\nmember = { path = \"../member\" }\n").unwrap();
+            rewrite_paths(&mut manifest, manifest_dir, workspace_root)
+        };
+
+        // The workspace and the manifest reached through the link or not
+        let from_link = rewritten(&link.join("crates").join("a"), &workspace);
+        let to_link = rewritten(&workspace.join("crates").join("a"), &link);
+
+        assert!(!from_link);
+        assert!(!to_link);
+    }
+
+    #[test]
+    fn test_inherit_workspace() {
+        let workspace: toml::Table = toml::from_str(
+            r#"
+            [package]
+            version = "1.2.0"
+
+            [dependencies]
+            serde = { version = "1", features = ["derive"] }
+            log = "0.4"
+            "#,
+        )
+        .unwrap();
+        let mut manifest: toml::Table = toml::from_str(
+            r#"
+            [package]
+            name = "a"
+            version.workspace = true
+
+            [dependencies]
+            serde = { workspace = true, features = ["rc"], optional = true }
+
+            [target.'cfg(unix)'.dev-dependencies]
+            log.workspace = true
+            "#,
+        )
+        .unwrap();
+        inherit_workspace(&mut manifest, &workspace).unwrap();
+        assert_eq!(manifest["package"]["version"].as_str(), Some("1.2.0"));
+        let serde = &manifest["dependencies"]["serde"];
+        assert_eq!(serde.get("workspace"), None);
+        assert_eq!(serde["optional"].as_bool(), Some(true));
+        assert_eq!(
+            serde["features"].as_array().unwrap(),
+            &vec!["derive".into(), "rc".into()]
+        );
+        assert_eq!(
+            manifest["target"]["cfg(unix)"]["dev-dependencies"]["log"]["version"].as_str(),
+            Some("0.4")
+        );
+    }
+}
@@ -1,18 +1,90 @@
 use std::path::PathBuf;
 
+use crate::config::Settings;
+
 pub(crate) mod analyze;
+pub(crate) mod annotate;
+pub(crate) mod apply;
+pub(crate) mod cache;
+pub(crate) mod capture;
+pub(crate) mod cargo_config;
 pub(crate) mod clean;
+pub(crate) mod coverage;
+pub(crate) mod dead_code;
 pub(crate) mod generate;
+pub(crate) mod hooks;
+pub(crate) mod init;
+pub(crate) mod list;
+pub(crate) mod manifest;
+pub(crate) mod mutants_out;
+pub(crate) mod notify;
+pub(crate) mod probe;
 pub(crate) mod reporting;
+pub(crate) mod rerun;
+pub(crate) mod runs;
+pub(crate) mod schedule;
+pub(crate) mod serve;
+pub(crate) mod show;
+pub(crate) mod single;
+pub(crate) mod sonarqube;
+pub(crate) mod stryker;
+pub(crate) mod type_hints;
+pub(crate) mod validate;
 pub(crate) mod verify;
+pub(crate) mod viability;
+pub(crate) mod warnings_ng;
+pub(crate) mod watch;
+pub(crate) mod worktree;
 
-pub(crate) fn get_project_walker(project_path: &PathBuf) -> eyre::Result<Vec<globwalk::DirEntry>> {
+/// Files and folders of the project, except `target` and `.git`
+///
+/// Symbolic links are yielded as links unless `follow_symlinks` is set, in which case the walk goes
+/// through them. Link cycles and broken links are skipped with a warning.
+pub(crate) fn get_project_walker(
+    project_path: &PathBuf,
+    follow_symlinks: bool,
+) -> eyre::Result<Vec<globwalk::DirEntry>> {
     let project_path = std::fs::canonicalize(project_path)?;
-    let entries =
-        globwalk::GlobWalkerBuilder::from_patterns(&project_path, &["*", "*/**", "!target"])
-            .build()?
-            .into_iter()
-            .filter_map(Result::ok)
-            .collect::<Vec<globwalk::DirEntry>>();
+    let mut entries = globwalk::GlobWalkerBuilder::from_patterns(
+        &project_path,
+        &["*", "*/**", "!target", "!.git"],
+    )
+    .follow_links(follow_symlinks)
+    .build()?
+    .filter_map(|entry| match entry {
+        Ok(entry) if entry.path_is_symlink() && !entry.path().exists() => {
+            log::warn!("Skip broken symbolic link {}", entry.path().display());
+            None
+        }
+        Ok(entry) => Some(entry),
+        Err(error) => {
+            match error.path() {
+                Some(path) if path.is_symlink() && !path.exists() => {
+                    log::warn!("Skip broken symbolic link {}", path.display())
+                }
+                _ => log::warn!("Skip project entry: {error}"),
+            }
+            None
+        }
+    })
+    .collect::<Vec<globwalk::DirEntry>>();
+    // The order of the walk depends on the file system
+    entries.sort_by(|entry, other| entry.path().cmp(other.path()));
     Ok(entries)
 }
+
+/// Files and folders of the project, except `target`, `.git` and the mutation root
+///
+/// Mutant projects, warmed target folders and caches of every run may be created inside the
+/// project, their sources must be neither mutated nor copied.
+pub(crate) fn get_project_entries(
+    project_path: &PathBuf,
+    settings: &Settings,
+) -> eyre::Result<Vec<globwalk::DirEntry>> {
+    let mutation_root = std::fs::canonicalize(&settings.mutation_root)
+        .unwrap_or_else(|_| settings.mutation_root.clone());
+    Ok(get_project_walker(project_path, settings.follow_symlinks)?
+        .into_iter()
+        .filter(|entry| !entry.path().starts_with(&mutation_root))
+        .collect())
+}
@@ -0,0 +1,627 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Component, Path, PathBuf};
+
+use eyre::{eyre, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::actions::analyze::ARITHMETIC_OPERATOR;
+use crate::actions::reporting::results::{MutantResult, RunResults};
+use crate::actions::reporting::save_results;
+use crate::actions::runs::{finish_run, start_run, LATEST};
+use crate::cli::SortKey;
+use crate::mutation::{stable_id, MutationRecord};
+use crate::report::MutationStatus;
+
+static OUTCOMES_FILE: &str = "outcomes.json";
+static MUTANTS_FILE: &str = "mutants.json";
+/// Names of mutants by outcome, caught, missed, timeout and unviable
+static LIST_FILES: [&str; 4] = ["caught.txt", "missed.txt", "timeout.txt", "unviable.txt"];
+/// Genre of cargo-mutants for replaced binary operators
+static BINARY_OPERATOR: &str = "BinaryOperator";
+/// Operators of the arithmetic family, an imported binary operator among them belongs to it
+static ARITHMETIC: [&str; 5] = ["+", "-", "*", "/", "%"];
+
+/// Content of `outcomes.json`
+#[derive(Debug, Serialize, Deserialize)]
+struct Outcomes {
+    outcomes: Vec<Outcome>,
+    #[serde(default)]
+    total_mutants: usize,
+    #[serde(default)]
+    missed: usize,
+    #[serde(default)]
+    caught: usize,
+    #[serde(default)]
+    timeout: usize,
+    #[serde(default)]
+    unviable: usize,
+    #[serde(default)]
+    success: usize,
+    #[serde(default)]
+    failure: usize,
+}
+
+/// Outcome of a scenario, the baseline or a mutant
+#[derive(Debug, Serialize, Deserialize)]
+struct Outcome {
+    scenario: Scenario,
+    /// Log of the scenario, relative to the output folder
+    log_path: Option<PathBuf>,
+    /// Diff of the mutant, relative to the output folder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    diff_path: Option<PathBuf>,
+    summary: Summary,
+    #[serde(default)]
+    phase_results: Vec<PhaseResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Scenario {
+    Baseline,
+    Mutant(Mutant),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Mutant {
+    #[serde(default)]
+    package: String,
+    file: String,
+    #[serde(default)]
+    function: Option<Function>,
+    span: Span,
+    replacement: String,
+    #[serde(default)]
+    genre: String,
+}
+
+/// Function of a mutant, a name alone in older versions of cargo-mutants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Function {
+    Name(String),
+    Function {
+        function_name: String,
+        #[serde(default)]
+        return_type: String,
+    },
+}
+
+impl Function {
+    fn name(&self) -> &str {
+        match self {
+            Function::Name(name) => name,
+            Function::Function { function_name, .. } => function_name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Span {
+    start: LineColumn,
+    end: LineColumn,
+}
+
+/// 1-based line and column
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct LineColumn {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Summary {
+    Success,
+    CaughtMutant,
+    MissedMutant,
+    Unviable,
+    Timeout,
+    Failure,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PhaseResult {
+    /// `Build` or `Test`
+    phase: String,
+    /// Duration in seconds
+    duration: f64,
+    /// `"Success"`, `"Timeout"` or `{"Failure": code}`
+    process_status: serde_json::Value,
+}
+
+/// Mutant of cargo-mutants describing a darwin result
+fn mutant(result: &MutantResult) -> Mutant {
+    let record = &result.mutation;
+    let genre = if record.operator == ARITHMETIC_OPERATOR {
+        BINARY_OPERATOR.to_string()
+    } else {
+        record.operator.clone()
+    };
+    Mutant {
+        package: String::new(),
+        file: record.file.clone(),
+        function: Some(Function::Function {
+            function_name: record.function.clone(),
+            return_type: String::new(),
+        }),
+        span: Span {
+            start: LineColumn {
+                line: record.line,
+                column: record.column,
+            },
+            end: LineColumn {
+                line: record.end_line,
+                column: record.end_column,
+            },
+        },
+        replacement: record.replacement.clone(),
+        genre,
+    }
+}
+
+/// Phases of cargo-mutants from the durations and the status of a darwin result
+fn phase_results(result: &MutantResult) -> Vec<PhaseResult> {
+    let failure = serde_json::json!({ "Failure": 101 });
+    let (build, test) = match result.status {
+        MutationStatus::Success => ("Success".into(), Some("Success".into())),
+        MutationStatus::Fail => ("Success".into(), Some(failure)),
+        MutationStatus::Timeout => ("Success".into(), Some("Timeout".into())),
+        MutationStatus::CompilationFailed => (failure, None),
+    };
+    let mut phases = vec![PhaseResult {
+        phase: "Build".to_string(),
+        duration: result.build_duration.unwrap_or_default(),
+        process_status: build,
+    }];
+    if let Some(test) = test {
+        phases.push(PhaseResult {
+            phase: "Test".to_string(),
+            duration: result.duration.unwrap_or_default(),
+            process_status: test,
+        });
+    }
+    phases
+}
+
+/// Name of a mutant in the text files of cargo-mutants
+fn mutant_name(record: &MutationRecord) -> String {
+    format!(
+        "{}:{}:{}: replace {} with {} in {}",
+        record.file,
+        record.line,
+        record.column,
+        record.original,
+        record.replacement,
+        record.function
+    )
+}
+
+/// Remove a file of a previous export if it's still there
+fn remove_exported(path: &Path) -> eyre::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            Err(error).wrap_err(eyre!("Unable to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Remove the files written by a previous export, as listed by its outcomes, other files of the
+/// folder are left alone
+fn remove_export(output: &Path) -> eyre::Result<()> {
+    let path = output.join(OUTCOMES_FILE);
+    let file = File::open(&path)?;
+    let previous: Outcomes = serde_json::from_reader(BufReader::new(file))
+        .wrap_err(eyre!("Unable to read outcomes {}", path.display()))?;
+    for outcome in &previous.outcomes {
+        for file in outcome.log_path.iter().chain(&outcome.diff_path) {
+            // Only files of the folder itself
+            if file
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                remove_exported(&output.join(file))?;
+            }
+        }
+    }
+    for file in [MUTANTS_FILE, OUTCOMES_FILE].iter().chain(&LIST_FILES) {
+        remove_exported(&output.join(file))?;
+    }
+    Ok(())
+}
+
+/// Write the results of the last run as a `mutants.out` folder of cargo-mutants
+///
+/// The folder holds `outcomes.json`, `mutants.json`, the names of mutants by outcome in
+/// `caught.txt`, `missed.txt`, `timeout.txt` and `unviable.txt`, and their logs and diffs.
+/// The files of a previous export in the folder are replaced.
+pub(crate) fn export(mutation_root: &Path, output: &Path) -> eyre::Result<()> {
+    let results = RunResults::load(mutation_root)?;
+    if output.join(OUTCOMES_FILE).is_file() {
+        remove_export(output)?;
+    } else if output
+        .read_dir()
+        .is_ok_and(|mut entries| entries.next().is_some())
+    {
+        return Err(eyre!(
+            "{} is not empty and holds no {OUTCOMES_FILE}, export elsewhere",
+            output.display()
+        ));
+    }
+    std::fs::create_dir_all(output.join("log"))?;
+    std::fs::create_dir_all(output.join("diff"))?;
+
+    let mut outcomes = Outcomes {
+        outcomes: vec![],
+        total_mutants: results.mutants.len(),
+        missed: 0,
+        caught: 0,
+        timeout: 0,
+        unviable: 0,
+        success: 0,
+        failure: 0,
+    };
+    let mut names: [Vec<String>; 4] = Default::default();
+    for result in &results.mutants {
+        let (summary, index) = match result.status {
+            MutationStatus::Success => (Summary::MissedMutant, 1),
+            MutationStatus::Fail => (Summary::CaughtMutant, 0),
+            MutationStatus::Timeout => (Summary::Timeout, 2),
+            MutationStatus::CompilationFailed => (Summary::Unviable, 3),
+        };
+        names[index].push(mutant_name(&result.mutation));
+
+        let stable_id = &result.mutation.stable_id;
+        let log_path = Path::new("log").join(format!("{stable_id}.log"));
+        let report = mutation_root.join(&result.log);
+        let log_path = match std::fs::copy(&report, output.join(&log_path)) {
+            Ok(_) => Some(log_path),
+            Err(error) => {
+                log::debug!("No log {} : {error}", report.display());
+                None
+            }
+        };
+        let diff_path = Path::new("diff").join(format!("{stable_id}.diff"));
+        std::fs::write(output.join(&diff_path), &result.diff)?;
+
+        outcomes.outcomes.push(Outcome {
+            scenario: Scenario::Mutant(mutant(result)),
+            log_path,
+            diff_path: Some(diff_path),
+            summary,
+            phase_results: phase_results(result),
+        });
+    }
+    outcomes.caught = names[0].len();
+    outcomes.missed = names[1].len();
+    outcomes.timeout = names[2].len();
+    outcomes.unviable = names[3].len();
+    for (file, names) in LIST_FILES.iter().zip(names) {
+        let content: String = names.iter().map(|name| format!("{name}\n")).collect();
+        std::fs::write(output.join(file), content)?;
+    }
+
+    let mutants = outcomes
+        .outcomes
+        .iter()
+        .filter_map(|outcome| match &outcome.scenario {
+            Scenario::Mutant(mutant) => Some(mutant.clone()),
+            Scenario::Baseline => None,
+        })
+        .collect::<Vec<_>>();
+    let file = File::create(output.join(MUTANTS_FILE))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &mutants)?;
+    let file = File::create(output.join(OUTCOMES_FILE))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &outcomes)?;
+
+    println!(
+        "{} mutants exported to {}",
+        results.mutants.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Byte offset of a 1-based line and column in a content
+fn offset(content: &str, position: LineColumn) -> Option<usize> {
+    let line_start = content
+        .split_inclusive('\n')
+        .take(position.line.checked_sub(1)?)
+        .map(str::len)
+        .sum::<usize>();
+    let offset = line_start + position.column.checked_sub(1)?;
+    (offset <= content.len()).then_some(offset)
+}
+
+/// Darwin result of a mutant of cargo-mutants, none for outcomes which aren't a verdict
+///
+/// The original code is taken from the current content of the mutated file, the mutant is left
+/// without it if the file changed too much.
+fn mutant_result(outcome: &Outcome, mutants_out: &Path, content: &str) -> Option<MutantResult> {
+    let Scenario::Mutant(mutant) = &outcome.scenario else {
+        return None;
+    };
+    let status = match outcome.summary {
+        Summary::MissedMutant => MutationStatus::Success,
+        Summary::CaughtMutant => MutationStatus::Fail,
+        Summary::Timeout => MutationStatus::Timeout,
+        Summary::Unviable => MutationStatus::CompilationFailed,
+        Summary::Success | Summary::Failure => return None,
+    };
+
+    let range = offset(content, mutant.span.start)
+        .zip(offset(content, mutant.span.end))
+        .filter(|(start, end)| start <= end && content.get(*start..*end).is_some())
+        .unwrap_or_default();
+    let original = content[range.0..range.1].to_string();
+    let reason = if original.is_empty() {
+        format!("replace with {}", mutant.replacement)
+    } else {
+        format!("replace {original} by {}", mutant.replacement)
+    };
+    let operator = if mutant.genre == BINARY_OPERATOR && ARITHMETIC.contains(&original.as_str()) {
+        ARITHMETIC_OPERATOR.to_string()
+    } else {
+        mutant.genre.clone()
+    };
+    let function = mutant
+        .function
+        .as_ref()
+        .map(|function| function.name().to_string())
+        .unwrap_or_default();
+    let duration = |phase: &str| {
+        outcome
+            .phase_results
+            .iter()
+            .find(|result| result.phase == phase)
+            .map(|result| result.duration)
+    };
+    let diff = outcome
+        .diff_path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(mutants_out.join(path)).ok())
+        .unwrap_or_default();
+
+    Some(MutantResult {
+        mutation: MutationRecord {
+            id: 0,
+            stable_id: stable_id(
+                Path::new(&mutant.file),
+                &function,
+                mutant.span.start.line,
+                mutant.span.start.column,
+                &mutant.replacement,
+            ),
+            file: mutant.file.clone(),
+            function,
+            operator,
+            reason,
+            line: mutant.span.start.line,
+            column: mutant.span.start.column,
+            end_line: mutant.span.end.line,
+            end_column: mutant.span.end.column,
+            start: range.0,
+            end: range.1,
+            original,
+            replacement: mutant.replacement.clone(),
+            context: String::new(),
+            loop_control: false,
+        },
+        status,
+        caught_by: vec![],
+        errors: vec![],
+        execution: None,
+        duration: duration("Test"),
+        build_duration: duration("Build"),
+        warnings: vec![],
+        diagnostics: vec![],
+        timeout: None,
+        log: mutants_out.join(outcome.log_path.clone().unwrap_or_default()),
+        diff,
+    })
+}
+
+/// Lines comparing the outcomes of cargo-mutants with the results of darwin, matched by stable ID
+fn compare(imported: &[MutantResult], darwin: &[MutantResult]) -> Vec<String> {
+    let darwin = darwin
+        .iter()
+        .map(|result| (result.mutation.stable_id.as_str(), result.status))
+        .collect::<BTreeMap<_, _>>();
+    let mut same = 0;
+    let mut different = vec![];
+    let mut only_imported = 0;
+    for result in imported {
+        match darwin.get(result.mutation.stable_id.as_str()) {
+            Some(status) if *status == result.status => same += 1,
+            Some(status) => different.push(format!(
+                "    {}: {} in cargo-mutants, {} in darwin",
+                result.mutation.stable_id,
+                result.status.name(),
+                status.name()
+            )),
+            None => only_imported += 1,
+        }
+    }
+    let only_darwin = darwin.len() - same - different.len();
+    let mut lines = vec![
+        format!("  {same} mutants with the same outcome"),
+        format!("  {} mutants with another outcome", different.len()),
+    ];
+    lines.extend(different);
+    lines.push(format!("  {only_imported} mutants only in cargo-mutants"));
+    lines.push(format!("  {only_darwin} mutants only in darwin"));
+    lines
+}
+
+/// Save the results of a `mutants.out` folder of cargo-mutants as the ones of a new run, which
+/// becomes the latest one, and compare them with the results of the previous run
+///
+/// Logs are left in the imported folder, outcomes other than a verdict on a mutant are ignored.
+pub(crate) fn import(
+    mutants_out: &Path,
+    project_path: &Path,
+    mutation_root: &Path,
+    keep_reports: usize,
+    sort: SortKey,
+) -> eyre::Result<()> {
+    let path = mutants_out.join(OUTCOMES_FILE);
+    let file = File::open(&path).wrap_err(eyre!("No outcomes found in {}", path.display()))?;
+    let outcomes: Outcomes = serde_json::from_reader(BufReader::new(file))
+        .wrap_err(eyre!("Unable to read outcomes {}", path.display()))?;
+    let mutants_out = std::fs::canonicalize(mutants_out)?;
+
+    let mut contents: BTreeMap<&str, String> = BTreeMap::new();
+    let mut mutants = vec![];
+    for outcome in &outcomes.outcomes {
+        let Scenario::Mutant(mutant) = &outcome.scenario else {
+            continue;
+        };
+        let content = contents.entry(&mutant.file).or_insert_with(|| {
+            std::fs::read_to_string(project_path.join(&mutant.file)).unwrap_or_default()
+        });
+        if let Some(mut result) = mutant_result(outcome, &mutants_out, content) {
+            result.mutation.id = mutants.len();
+            mutants.push(result);
+        }
+    }
+    if let Ok(previous) = RunResults::load(&mutation_root.join(LATEST)) {
+        println!("Compared with the last run:");
+        for line in compare(&mutants, &previous.mutants) {
+            println!("{line}");
+        }
+    }
+    let results = RunResults {
+        mutants,
+        ..Default::default()
+    };
+    let run_path = start_run(mutation_root)?;
+    save_results(&results, &run_path, sort)?;
+    finish_run(mutation_root, &run_path, keep_reports)?;
+    println!(
+        "{} mutants imported from {}, as the results of the last run",
+        results.mutants.len(),
+        mutants_out.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::mutants_out::{
+        compare, mutant_result, remove_export, Outcomes, OUTCOMES_FILE,
+    };
+    use crate::report::MutationStatus;
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_import_outcomes() {
+        let outcomes: Outcomes = serde_json::from_str(
+            r#"{
+              "outcomes": [
+                {"scenario": "Baseline", "log_path": "log/baseline.log", "summary": "Success", "phase_results": []},
+                {
+                  "scenario": {"Mutant": {
+                    "package": "pg", "file": "src/lib.rs",
+                    "function": {"function_name": "add", "return_type": "-> u8", "span": {}},
+                    "span": {"start": {"line": 2, "column": 7}, "end": {"line": 2, "column": 8}},
+                    "replacement": "-", "genre": "BinaryOperator"
+                  }},
+                  "log_path": "log/src__lib.rs_line_2_col_7.log",
+                  "summary": "MissedMutant",
+                  "phase_results": [
+                    {"phase": "Build", "duration": 1.5, "process_status": "Success", "argv": []},
+                    {"phase": "Test", "duration": 0.5, "process_status": "Success", "argv": []}
+                  ]
+                },
+                {
+                  "scenario": {"Mutant": {
+                    "file": "src/lib.rs", "function": "add",
+                    "span": {"start": {"line": 1, "column": 1}, "end": {"line": 3, "column": 2}},
+                    "replacement": "Default::default()", "genre": "FnValue"
+                  }},
+                  "log_path": "log/src__lib.rs_line_1_col_1.log",
+                  "summary": "CaughtMutant"
+                }
+              ],
+              "total_mutants": 2, "missed": 1, "caught": 1
+            }"#,
+        )
+        .unwrap();
+        let content = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let results = outcomes
+            .outcomes
+            .iter()
+            .filter_map(|outcome| mutant_result(outcome, Path::new("/out"), content))
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        let missed = &results[0];
+        assert_eq!(missed.status, MutationStatus::Success);
+        assert_eq!(missed.mutation.operator, "arithmetic");
+        assert_eq!(missed.mutation.original, "+");
+        assert_eq!(missed.mutation.reason, "replace + by -");
+        assert_eq!(missed.mutation.stable_id, "src-lib-add-2-7-sub");
+        assert_eq!((missed.mutation.start, missed.mutation.end), (35, 36));
+        assert_eq!(missed.build_duration, Some(1.5));
+        assert_eq!(
+            missed.log,
+            Path::new("/out/log/src__lib.rs_line_2_col_7.log")
+        );
+        let caught = &results[1];
+        assert_eq!(caught.status, MutationStatus::Fail);
+        assert_eq!(caught.mutation.operator, "FnValue");
+        assert_eq!(caught.mutation.function, "add");
+        assert!(caught.mutation.original.ends_with("x + y\n}"));
+
+        let mut darwin = missed.clone();
+        darwin.status = MutationStatus::Fail;
+        assert_eq!(
+            compare(&results, &[darwin]),
+            vec![
+                "  0 mutants with the same outcome",
+                "  1 mutants with another outcome",
+                "    src-lib-add-2-7-sub: missing in cargo-mutants, ok in darwin",
+                "  1 mutants only in cargo-mutants",
+                "  0 mutants only in darwin",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_export() {
+        let output = ScratchFolder::new("export");
+        std::fs::create_dir_all(output.join("log")).unwrap();
+        let outcomes = r#"{"outcomes": [{
+          "scenario": {"Mutant": {
+            "file": "src/lib.rs", "function": "add",
+            "span": {"start": {"line": 2, "column": 7}, "end": {"line": 2, "column": 8}},
+            "replacement": "-", "genre": "BinaryOperator"
+          }},
+          "log_path": "log/src-lib-add-2-7-sub.log", "diff_path": "../notes.md",
+          "summary": "MissedMutant"
+        }]}"#;
+        std::fs::write(output.join(OUTCOMES_FILE), outcomes).unwrap();
+        std::fs::write(output.join("missed.txt"), "").unwrap();
+        std::fs::write(output.join("log/src-lib-add-2-7-sub.log"), "").unwrap();
+        std::fs::write(output.join("notes.md"), "").unwrap();
+        remove_export(&output).unwrap();
+        let mut left = walkdir::WalkDir::new(&output)
+            .into_iter()
+            .map(|entry| {
+                entry
+                    .unwrap()
+                    .path()
+                    .strip_prefix(&output)
+                    .unwrap()
+                    .to_path_buf()
+            })
+            .collect::<Vec<_>>();
+        left.sort();
+        assert_eq!(
+            left,
+            vec![Path::new(""), Path::new("log"), Path::new("notes.md")]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::Path;
+use std::time::Duration;
+
+use eyre::{eyre, WrapErr};
+use serde::Serialize;
+
+use crate::actions::reporting::results::{RunResults, StatusCounts, SCHEMA_VERSION};
+use crate::mutation::MutationRecord;
+use crate::report::MutationStatus;
+
+/// Time allowed to the webhook to answer
+static WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Outcome of a run, posted as JSON to the webhook
+#[derive(Debug, Serialize)]
+pub(crate) struct Notification<'a> {
+    /// Version of the format of the results
+    schema_version: u32,
+    /// `finished`, `failed` or `interrupted`
+    status: &'static str,
+    /// One line description, displayed by chat webhooks
+    text: String,
+    project: String,
+    /// Error which aborted the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Ratio of mutants caught among the ones caught or missing, none if there are none
+    score: Option<f64>,
+    #[serde(flatten)]
+    counts: StatusCounts,
+    /// Mutants not caught by the tests
+    survivors: Vec<&'a MutationRecord>,
+}
+
+impl<'a> Notification<'a> {
+    /// Notification of a run which went to completion
+    pub(crate) fn finished(project_path: &Path, results: &'a RunResults) -> Self {
+        let counts = results.counts();
+        let score = counts.score();
+        let project = project_path.display().to_string();
+        let score_text = match score {
+            Some(score) => format!(", score {:.0}%", score * 100.0),
+            None => "".to_string(),
+        };
+        Notification {
+            schema_version: SCHEMA_VERSION,
+            status: "finished",
+            text: format!(
+                "Darwin finished on {project}: {} mutants, {} missing, {} ok, {} timeout, {} killed{score_text}",
+                counts.mutants, counts.missing, counts.ok, counts.timeout, counts.killed
+            ),
+            project,
+            error: None,
+            score,
+            counts,
+            survivors: results
+                .mutants
+                .iter()
+                .filter(|result| result.status == MutationStatus::Success)
+                .map(|result| &result.mutation)
+                .collect(),
+        }
+    }
+
+    /// Notification of a run which stopped before its end, by an error or an interruption
+    pub(crate) fn aborted(project_path: &Path, error: Option<String>) -> Self {
+        let project = project_path.display().to_string();
+        let (status, text) = match &error {
+            Some(error) => ("failed", format!("Darwin failed on {project}: {error}")),
+            None => (
+                "interrupted",
+                format!("Darwin was interrupted on {project}"),
+            ),
+        };
+        Notification {
+            schema_version: SCHEMA_VERSION,
+            status,
+            text,
+            project,
+            error,
+            score: None,
+            counts: StatusCounts::default(),
+            survivors: vec![],
+        }
+    }
+}
+
+/// Post the notification to the webhook
+pub(crate) fn notify(url: &str, notification: &Notification) -> eyre::Result<()> {
+    log::info!("Notify {url}");
+    ureq::post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(&serde_json::to_string(notification)?)
+        .map_err(|error| eyre!("{error}"))
+        .wrap_err("Unable to notify webhook")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::notify::Notification;
+    use crate::actions::reporting::results::{RunResults, SUMMARY_V0};
+    use crate::report::MutationStatus;
+
+    #[test]
+    fn test_notification() {
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mutant = results.mutants[0].clone();
+        results
+            .mutants
+            .extend([mutant.clone(), mutant.clone(), mutant]);
+        results.mutants[1].status = MutationStatus::Fail;
+        results.mutants[2].status = MutationStatus::Fail;
+        results.mutants[3].status = MutationStatus::CompilationFailed;
+
+        let notification =
+            serde_json::to_value(Notification::finished(Path::new("/work/project"), &results))
+                .unwrap();
+        assert_eq!(notification["status"], "finished");
+        assert_eq!(
+            notification["text"],
+            "Darwin finished on /work/project: 4 mutants, 1 missing, 2 ok, 0 timeout, 1 killed, score 67%"
+        );
+        assert_eq!(notification["killed"], 1);
+        assert_eq!(
+            notification["survivors"][0]["stable_id"],
+            "src-lib-add-5-7-sub"
+        );
+
+        let notification = serde_json::to_value(Notification::aborted(
+            Path::new("/work/project"),
+            Some("Unable to run cargo".to_string()),
+        ))
+        .unwrap();
+        assert_eq!(notification["status"], "failed");
+        assert_eq!(notification["score"], serde_json::Value::Null);
+    }
+}
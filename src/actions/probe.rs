@@ -0,0 +1,191 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+
+use eyre::WrapErr;
+
+use crate::actions::analyze::{parse_rust, FUNCTION_ITEM};
+use crate::actions::generate::{copy_workspace, project_entries};
+use crate::actions::verify::check_tests;
+use crate::config::Settings;
+use crate::mutation::Mutation;
+
+/// Prefix of the unused variables inserted in mutated functions, the compiler warns about the
+/// ones of compiled functions
+static PROBE_PREFIX: &str = "darwin_probe_";
+
+/// Offset of the body of the innermost function enclosing a byte, right after its opening brace
+fn function_body(tree: &tree_sitter::Tree, offset: usize) -> Option<usize> {
+    let mut node = tree.root_node().descendant_for_byte_range(offset, offset)?;
+    loop {
+        if node.kind() == FUNCTION_ITEM {
+            return node
+                .child_by_field_name("body")
+                .map(|body| body.start_byte() + 1);
+        }
+        node = node.parent()?;
+    }
+}
+
+/// Insert a probe at the start of each function body, returns the probed source
+fn insert_probes(source: &str, probes: &BTreeMap<usize, usize>) -> String {
+    let mut probed = String::with_capacity(source.len() + probes.len() * 64);
+    let mut start = 0;
+    for (offset, probe) in probes {
+        probed.push_str(&source[start..*offset]);
+        probed.push_str(&format!(
+            " #[warn(warnings, unused_variables)] let {PROBE_PREFIX}{probe} = ();"
+        ));
+        start = *offset;
+    }
+    probed.push_str(&source[start..]);
+    probed
+}
+
+/// Probes reported as unused variables in the JSON messages of `cargo check`
+fn reported_probes(messages: &str) -> HashSet<usize> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-message")
+        .filter_map(|message| {
+            message["message"]["message"]
+                .as_str()?
+                .strip_prefix(&format!("unused variable: `{PROBE_PREFIX}"))?
+                .strip_suffix('`')?
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+/// Leave out the mutants of functions compiled in no test target, returns the mutants kept
+///
+/// An unused variable is inserted in each mutated function of a copy of the project, whose tests
+/// are checked: the compiler only warns about the variables of functions it compiles, functions
+/// behind a disabled feature or another platform are silent. Mutants outside of functions are
+/// kept, as well as every mutant if the check fails.
+pub(crate) fn skip_not_compiled(
+    mutants: Vec<Mutation>,
+    settings: &Settings,
+) -> eyre::Result<Vec<Mutation>> {
+    // Probe of each mutant, and probes of each file by offset
+    let mut mutant_probes = vec![];
+    let mut files: BTreeMap<PathBuf, (&str, BTreeMap<usize, usize>)> = BTreeMap::new();
+    let mut trees = BTreeMap::new();
+    for mutation in &mutants {
+        let path = mutation.get_file_path()?;
+        let source = mutation.source()?;
+        let tree = match trees.entry(path.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(parse_rust(source)?),
+        };
+        let Some(offset) = tree
+            .as_ref()
+            .and_then(|tree| function_body(tree, mutation.get_range().start))
+        else {
+            mutant_probes.push(None);
+            continue;
+        };
+        let (_, probes) = files
+            .entry(path.clone())
+            .or_insert_with(|| (source, BTreeMap::new()));
+        let next = mutant_probes.len();
+        mutant_probes.push(Some(*probes.entry(offset).or_insert(next)));
+    }
+    if files.is_empty() {
+        return Ok(mutants);
+    }
+
+    log::info!("Check which mutated functions are compiled");
+    std::fs::create_dir_all(&settings.mutation_path)?;
+    let mutation_root = std::fs::canonicalize(&settings.mutation_path)
+        .wrap_err("Unable to get canonical mutation_root")?;
+    let probe_path = mutation_root.join("probe");
+    if probe_path.exists() {
+        std::fs::remove_dir_all(&probe_path)?;
+    }
+    copy_workspace(&project_entries(settings)?, settings, &probe_path)?;
+    for (path, (source, probes)) in &files {
+        let probed_path = probe_path.join(path.strip_prefix(&settings.workspace_root)?);
+        // Never write through a link to the original sources
+        if probed_path.is_symlink() {
+            std::fs::remove_file(&probed_path)?;
+        }
+        std::fs::write(&probed_path, insert_probes(source, probes))?;
+    }
+    let messages = check_tests(&probe_path.join(&settings.member_path), settings);
+    std::fs::remove_dir_all(&probe_path)?;
+
+    let reported = match messages {
+        Ok(messages) => reported_probes(&messages),
+        Err(error) => {
+            log::debug!("{error:#}");
+            HashSet::new()
+        }
+    };
+    // Nothing reported means the check failed or warnings can't be seen
+    if reported.is_empty() {
+        println!("Unable to tell which functions are compiled, no mutant is skipped");
+        return Ok(mutants);
+    }
+
+    let mut kept = vec![];
+    let mut functions = BTreeSet::new();
+    for (mutation, probe) in mutants.into_iter().zip(mutant_probes) {
+        match probe {
+            Some(probe) if !reported.contains(&probe) => {
+                functions.insert((mutation.relative_file()?, mutation.function_name.clone()));
+            }
+            _ => kept.push(mutation),
+        }
+    }
+    for (file, function) in functions {
+        println!(
+            "[Not compiled] : Function \"{function}\" of file {file}, compiled in no test target"
+        );
+    }
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::actions::analyze::parse_rust;
+    use crate::actions::probe::{function_body, insert_probes, reported_probes};
+
+    #[test]
+    fn test_insert_probes() {
+        let source =
+            "const A: u8 = 1 + 1;\nfn a() -> u8 {\n    fn b() -> u8 { 2 * 2 }\n    b() - 1\n}\n";
+        let tree = parse_rust(source).unwrap().unwrap();
+        assert_eq!(function_body(&tree, source.find("1 + 1").unwrap()), None);
+        let outer = function_body(&tree, source.find("- 1").unwrap()).unwrap();
+        let inner = function_body(&tree, source.find("* 2").unwrap()).unwrap();
+        assert_eq!(&source[outer - 1..outer], "{");
+        assert!(inner > outer);
+
+        let probed = insert_probes(source, &BTreeMap::from([(outer, 0), (inner, 3)]));
+        assert_eq!(
+            probed,
+            "const A: u8 = 1 + 1;\nfn a() -> u8 { #[warn(warnings, unused_variables)] let darwin_probe_0 = ();\n    \
+             fn b() -> u8 { #[warn(warnings, unused_variables)] let darwin_probe_3 = (); 2 * 2 }\n    b() - 1\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_reported_probes() {
+        let messages = [
+            r#"{"reason":"compiler-artifact","target":{"name":"a"}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"unused variable: `darwin_probe_3`"}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`"}}"#,
+            r#"{"reason":"build-finished","success":true}"#,
+        ]
+        .join("\n");
+        assert_eq!(
+            reported_probes(&messages).into_iter().collect::<Vec<_>>(),
+            vec![3]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt::Write;
+
+use crate::actions::reporting::results::RunResults;
+
+/// Metrics of a run in the Prometheus text format, ended by `# EOF` as OpenMetrics requires
+pub(crate) fn metrics(results: &RunResults) -> String {
+    let counts = results.counts();
+    let mut metrics = String::new();
+    let mut metric = |name: &str, help: &str, samples: &[(&str, f64)]| {
+        let _ = writeln!(metrics, "# HELP {name} {help}");
+        let _ = writeln!(metrics, "# TYPE {name} gauge");
+        for (labels, value) in samples {
+            let _ = writeln!(metrics, "{name}{labels} {value}");
+        }
+    };
+
+    metric(
+        "mutants",
+        "Number of mutants of the run",
+        &[("", counts.mutants as f64)],
+    );
+    metric(
+        "mutants_missing",
+        "Number of mutants not caught by the tests",
+        &[("", counts.missing as f64)],
+    );
+    metric(
+        "mutants_by_status",
+        "Number of mutants by status",
+        &[
+            ("{status=\"missing\"}", counts.missing as f64),
+            ("{status=\"ok\"}", counts.ok as f64),
+            ("{status=\"timeout\"}", counts.timeout as f64),
+            ("{status=\"killed\"}", counts.killed as f64),
+        ],
+    );
+    if let Some(score) = counts.score() {
+        metric(
+            "mutation_score",
+            "Ratio of caught mutants among the caught and missing ones",
+            &[("", score)],
+        );
+    }
+    if let Some(duration) = results.duration {
+        metric(
+            "run_duration_seconds",
+            "Duration of the run",
+            &[("", duration)],
+        );
+    }
+    metrics.push_str("# EOF\n");
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::metrics::metrics;
+    use crate::actions::reporting::results::{RunResults, SUMMARY_V0};
+    use crate::report::MutationStatus;
+
+    #[test]
+    fn test_metrics() {
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mutant = results.mutants[0].clone();
+        results.mutants.extend([mutant.clone(), mutant]);
+        results.mutants[1].status = MutationStatus::Fail;
+        results.mutants[2].status = MutationStatus::Fail;
+        results.duration = Some(12.5);
+
+        let metrics = metrics(&results);
+        assert!(metrics.contains("# TYPE mutants gauge\nmutants 3\n"));
+        assert!(metrics.contains("mutants_by_status{status=\"ok\"} 2\n"));
+        assert!(metrics.contains("mutation_score 0.6666666666666666\n"));
+        assert!(metrics.contains("run_duration_seconds 12.5\n"));
+        assert!(metrics.ends_with("# EOF\n"));
+    }
+}
@@ -1,14 +1,19 @@
 use std::fs;
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::ops::ControlFlow;
-use std::path::PathBuf;
+use std::path::Path;
 
+use crate::actions::coverage::Coverage;
+use crate::cli::SortKey;
 use crate::mutation::Mutation;
+use results::{MutantResult, RunResults};
 
+pub(crate) mod metrics;
+pub(crate) mod results;
 pub(crate) mod sink;
+pub(crate) mod table;
 
-fn generate_report(mutation: &Mutation, mutation_root: &PathBuf) -> eyre::Result<()> {
+fn generate_report(mutation: &Mutation, mutation_root: &Path) -> eyre::Result<()> {
     let content = mutation.display(false)?;
     let data = content.as_bytes();
     let mutation_log_path =
@@ -19,47 +24,189 @@ fn generate_report(mutation: &Mutation, mutation_root: &PathBuf) -> eyre::Result
     Ok(())
 }
 
-fn generate_summary(
-    mutations: &Vec<Mutation>,
-    mutation_root: &PathBuf,
-    project_path: &PathBuf,
-) -> eyre::Result<()> {
+fn generate_summary(results: &RunResults, mutation_root: &Path, sort: SortKey) -> eyre::Result<()> {
     let summary_path = mutation_root.join("summary");
     let mut summary_file = File::create(summary_path)?;
 
-    let data = mutations
-        .iter()
-        .try_fold(vec![], |mut acc: Vec<u8>, mutation| {
-            match mutation.simple(project_path) {
-                Ok(data) => {
-                    acc.extend_from_slice(format!("{data}\n").as_bytes());
-                    ControlFlow::Continue(acc)
-                }
-                Err(err) => ControlFlow::Break(err),
-            }
-        });
-
-    match data {
-        ControlFlow::Continue(data) => {
-            summary_file.write_all(&data)?;
+    for result in results.sorted(sort) {
+        writeln!(summary_file, "{}", result.simple())?;
+    }
+    if !results.weak_oracles.is_empty() {
+        writeln!(summary_file, "Tests execute but don't assert:")?;
+        for weak_oracle in &results.weak_oracles {
+            writeln!(summary_file, "{}", weak_oracle.simple())?;
+        }
+    }
+    let outliers = results.timing_outliers();
+    if !outliers.is_empty() {
+        writeln!(
+            summary_file,
+            "Tests much faster or slower than without mutation:"
+        )?;
+        for line in outliers {
+            writeln!(summary_file, "{line}")?;
         }
-        ControlFlow::Break(err) => Err(err)?,
+    }
+    if !results.batches.is_empty() {
+        writeln!(
+            summary_file,
+            "Estimated build time saved by verifying the mutants of a file one after the other:"
+        )?;
+        for batch in &results.batches {
+            writeln!(summary_file, "{}", batch.simple())?;
+        }
+    }
+    for skipped in &results.skipped {
+        writeln!(summary_file, "{}", skipped.simple())?;
+    }
+    for function in &results.dead_code {
+        writeln!(summary_file, "{}", function.simple())?;
+    }
+    // Mutants left out by the selection of the settings are only counted
+    for skipped in results
+        .not_attempted
+        .iter()
+        .filter(|skipped| skipped.reason.automatic())
+    {
+        writeln!(summary_file, "{}", skipped.simple())?;
+    }
+    for warning in table::unviable_operators(results) {
+        writeln!(summary_file, "{warning}")?;
+    }
+    if let Some(line) = results.excluded.simple() {
+        writeln!(summary_file, "{line}")?;
     }
     Ok(())
 }
 
-pub fn generate_reports(
-    mutations: &Vec<Mutation>,
-    mutation_root: &PathBuf,
-    project_path: &PathBuf,
-) -> eyre::Result<()> {
-    log::info!("Generate reports");
+/// Write the log of a verified mutant and add it to the summary, so that an aborted run keeps them
+///
+/// The summary is written again, in order, at the end of the run.
+pub(crate) fn stream_report(mutation: &Mutation, mutation_root: &Path) -> eyre::Result<()> {
     let report_path = mutation_root.join("reports");
     fs::create_dir_all(&report_path)?;
+    generate_report(mutation, &report_path)?;
 
-    for mutation in mutations {
-        generate_report(mutation, &report_path)?
+    let mut lines = String::new();
+    for result in RunResults::from_mutations(std::slice::from_ref(mutation))?.mutants {
+        lines.push_str(&format!("{}\n", result.simple()));
     }
-    generate_summary(mutations, mutation_root, project_path)?;
+    // A single write, workers append concurrently
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(mutation_root.join("summary"))?
+        .write_all(lines.as_bytes())?;
     Ok(())
 }
+
+/// Write the summary of the last run without the mutants about to be verified again, whose lines
+/// are streamed to it along their new verdicts
+///
+/// The summary is written again, from the merged results, once they're all verified.
+pub(crate) fn prepare_update(
+    ids: &[usize],
+    mutation_root: &Path,
+    sort: SortKey,
+) -> eyre::Result<()> {
+    let mut results = RunResults::load(mutation_root)?;
+    results
+        .mutants
+        .retain(|result| !ids.contains(&result.mutation.id));
+    generate_summary(&results, mutation_root, sort)
+}
+
+/// Write the summary, with mutants in the order of `sort`, `metrics.prom` and `summary.json` of
+/// the results
+pub fn save_results(results: &RunResults, mutation_root: &Path, sort: SortKey) -> eyre::Result<()> {
+    generate_summary(results, mutation_root, sort)?;
+    fs::write(
+        mutation_root.join("metrics.prom"),
+        metrics::metrics(results),
+    )?;
+    results.save(mutation_root)
+}
+
+/// Result of a previous run carried forward, with its detailed report
+pub(crate) struct CarriedResult {
+    pub(crate) result: MutantResult,
+    pub(crate) report: String,
+}
+
+/// Write the reports of a run, whose results are completed with the verified and carried mutants
+pub fn generate_reports(
+    mutations: &[Mutation],
+    carried: Vec<CarriedResult>,
+    mut results: RunResults,
+    coverage: Option<&Coverage>,
+    mutation_root: &Path,
+    sort: SortKey,
+) -> eyre::Result<RunResults> {
+    log::info!("Generate reports");
+    results.mutants = RunResults::from_mutations(mutations)?.mutants;
+    for CarriedResult { result, report } in carried {
+        // Reports of the run are only created along with the first verified mutant
+        let log = mutation_root.join(&result.log);
+        if let Some(parent) = log.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(log, report)?;
+        results.mutants.push(result);
+    }
+    results.mutants.sort_by_key(|result| result.mutation.id);
+    if let Some(coverage) = coverage {
+        coverage.annotate(&mut results);
+    }
+    save_results(&results, mutation_root, sort)?;
+    Ok(results)
+}
+
+/// Update the reports of the previous run with mutants verified again
+pub fn update_reports(
+    mutations: &[Mutation],
+    coverage: Option<&Coverage>,
+    mutation_root: &Path,
+    sort: SortKey,
+) -> eyre::Result<RunResults> {
+    log::info!("Update reports");
+    let mut results = RunResults::load(mutation_root)?;
+    results.merge(RunResults::from_mutations(mutations)?);
+    if let Some(coverage) = coverage {
+        coverage.annotate(&mut results);
+    }
+    save_results(&results, mutation_root, sort)?;
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::results::{MutantResult, RunResults};
+    use crate::actions::reporting::{prepare_update, save_results};
+    use crate::cli::SortKey;
+    use crate::report::MutationStatus;
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_prepare_update() {
+        let mutation_root = ScratchFolder::new("update");
+        let mut verified = MutantResult::add_mutant(MutationStatus::Success, "*", "mul");
+        verified.mutation.id = 1;
+        let results = RunResults {
+            mutants: vec![
+                MutantResult::add_mutant(MutationStatus::Fail, "-", "sub"),
+                verified,
+            ],
+            ..Default::default()
+        };
+        save_results(&results, &mutation_root, SortKey::Id).unwrap();
+
+        prepare_update(&[1], &mutation_root, SortKey::Id).unwrap();
+        let summary = std::fs::read_to_string(mutation_root.join("summary")).unwrap();
+        // The results are kept until the mutant has a new verdict
+        let kept = RunResults::load(&mutation_root).unwrap();
+
+        assert!(summary.contains("Mutation #0 replace + by -"), "{summary}");
+        assert!(!summary.contains("Mutation #1"), "{summary}");
+        assert_eq!(kept.mutants.len(), 2);
+    }
+}
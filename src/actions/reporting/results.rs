@@ -0,0 +1,1057 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::SortKey;
+use crate::mutation::{Mutation, MutationRecord};
+use crate::report::{Diagnostic, MutationStatus, SkipReason, TimeoutCause};
+
+pub(crate) static RESULTS_FILE: &str = "summary.json";
+/// Version of the format of the machine-readable outputs, `summary.json` and `list --format json`
+///
+/// Adding a field keeps the version, removing, renaming or changing the meaning of a field bumps it.
+pub(crate) static SCHEMA_VERSION: u32 = 1;
+/// Ratio to the duration of the tests without mutation beyond which tests of a mutant are reported
+/// as much faster or slower
+static TIMING_RATIO: f64 = 4.0;
+
+/// Persisted outcome of a mutant verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MutantResult {
+    #[serde(flatten)]
+    pub(crate) mutation: MutationRecord,
+    /// `missing`, `ok`, `timeout` or `killed`
+    pub(crate) status: MutationStatus,
+    /// Tests which failed because of the mutation
+    pub(crate) caught_by: Vec<String>,
+    /// Compiler error codes of killed mutants, such as `E0308`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) errors: Vec<String>,
+    /// Whether the tests executed missing mutants, when coverage data is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) execution: Option<Execution>,
+    /// Duration of the tests in seconds, none if they didn't run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) duration: Option<f64>,
+    /// Duration of the build in seconds, none if not measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) build_duration: Option<f64>,
+    /// Warnings of clippy on missing mutants which the project without mutation doesn't have
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) warnings: Vec<String>,
+    /// Compiler errors of killed mutants, with their code and location
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    /// Why the tests ran past the timeout, `infinite-loop` or `slow`, none if they finished within
+    /// it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) timeout: Option<TimeoutCause>,
+    /// Detailed report, relative to the mutation path
+    pub(crate) log: PathBuf,
+    pub(crate) diff: String,
+}
+
+impl MutantResult {
+    /// One line description, as in the summary file
+    pub(crate) fn simple(&self) -> String {
+        self.describe(self.status.simple())
+    }
+
+    /// File, line and column of the mutant
+    fn position(&self) -> (&str, usize, usize) {
+        let MutationRecord {
+            file, line, column, ..
+        } = &self.mutation;
+        (file, *line, *column)
+    }
+
+    fn describe(&self, status: String) -> String {
+        let MutationRecord {
+            id,
+            reason,
+            function,
+            file,
+            line,
+            column,
+            ..
+        } = &self.mutation;
+        let mut line = format!(
+            "{status} : Mutation #{id} {reason} in function \"{function}\" of file {file} at line {line}:{column}"
+        );
+        if !self.errors.is_empty() {
+            line.push_str(&format!(" ({})", self.errors.join(", ")));
+        }
+        if let Some(timeout) = &self.timeout {
+            line.push_str(&format!(", {}", timeout.hint(self.status)));
+        }
+        if let Some(execution) = &self.execution {
+            line.push_str(&format!(", {}", execution.hint()));
+        }
+        if !self.warnings.is_empty() {
+            line.push_str(&format!(", {} new warnings", self.warnings.len()));
+        }
+        line
+    }
+
+    /// One line diagnostic, `file:line:column: warning: message` as rustc's short messages
+    pub(crate) fn diagnostic(&self) -> String {
+        let MutationRecord {
+            stable_id,
+            reason,
+            function,
+            file,
+            line,
+            column,
+            ..
+        } = &self.mutation;
+        let mut diagnostic = format!(
+            "{file}:{line}:{column}: warning: mutant survived, {reason} in function \"{function}\" (mutant {stable_id})"
+        );
+        if let Some(execution) = &self.execution {
+            diagnostic.push_str(&format!(", {}", execution.hint()));
+        }
+        diagnostic
+    }
+
+    /// One line comparison of the duration of the tests to the one without mutation, if any
+    pub(crate) fn timing(&self, baseline: f64) -> Option<String> {
+        let duration = self
+            .duration
+            .filter(|_| self.status != MutationStatus::Timeout)?;
+        let MutationRecord {
+            id,
+            reason,
+            function,
+            file,
+            ..
+        } = &self.mutation;
+        let (tag, ratio, comparison) = if duration * TIMING_RATIO < baseline {
+            ("[Faster]", baseline / duration, "faster")
+        } else if duration > baseline * TIMING_RATIO {
+            ("[Slower]", duration / baseline, "slower")
+        } else {
+            return None;
+        };
+        Some(format!(
+            "{tag} : Mutation #{id} {reason} in function \"{function}\" of file {file}, tests ran in {duration:.2}s, {ratio:.1}x {comparison} than without mutation"
+        ))
+    }
+}
+
+/// Whether the tests executed a mutant, according to coverage data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Execution {
+    pub(crate) executed: bool,
+    /// Tests which executed the mutant, when coverage is recorded per test
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tests: Vec<String>,
+}
+
+impl Execution {
+    /// What to do about a missing mutant: assert on code already executed, or test it at all
+    pub(crate) fn hint(&self) -> String {
+        match (self.executed, self.tests.as_slice()) {
+            (false, _) => "never executed by tests".to_string(),
+            (true, []) => "executed by tests but not asserted".to_string(),
+            (true, tests) => format!("executed by tests {} but not asserted", tests.join(", ")),
+        }
+    }
+}
+
+/// Number of mutants by status
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct StatusCounts {
+    pub(crate) mutants: usize,
+    pub(crate) missing: usize,
+    pub(crate) ok: usize,
+    pub(crate) timeout: usize,
+    pub(crate) killed: usize,
+}
+
+impl StatusCounts {
+    pub(crate) fn count<'a>(results: impl IntoIterator<Item = &'a MutantResult>) -> Self {
+        let mut counts = StatusCounts::default();
+        for result in results {
+            counts.mutants += 1;
+            match result.status {
+                MutationStatus::Success => counts.missing += 1,
+                MutationStatus::Fail => counts.ok += 1,
+                MutationStatus::Timeout => counts.timeout += 1,
+                MutationStatus::CompilationFailed => counts.killed += 1,
+            }
+        }
+        counts
+    }
+
+    /// Ratio of caught mutants among the caught and missing ones, none if there are none
+    pub(crate) fn score(&self) -> Option<f64> {
+        let verified = self.ok + self.missing;
+        (verified > 0).then(|| self.ok as f64 / verified as f64)
+    }
+}
+
+/// Build time saved by verifying the mutants of a file one after the other, estimated from the
+/// build durations of its mutants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BatchTiming {
+    /// Path relative to the project
+    pub(crate) file: String,
+    /// Number of mutants of the file which were built
+    pub(crate) mutants: usize,
+    /// Duration in seconds of the slowest build, the one building the most
+    pub(crate) slowest_build: f64,
+    /// Average duration in seconds of the other builds
+    pub(crate) average_build: f64,
+    /// Estimate of the seconds saved, compared with every mutant built as slowly as the slowest one
+    pub(crate) saved: f64,
+}
+
+impl BatchTiming {
+    /// Timing of the files with several mutants built, in order of the files
+    pub(crate) fn from_results(results: &[MutantResult]) -> Vec<BatchTiming> {
+        let mut files: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+        for result in results {
+            if let Some(build_duration) = result.build_duration {
+                files
+                    .entry(&result.mutation.file)
+                    .or_default()
+                    .push(build_duration);
+            }
+        }
+        files
+            .into_iter()
+            .filter(|(_, builds)| builds.len() > 1)
+            .map(|(file, builds)| {
+                let slowest_build = builds.iter().copied().fold(0.0, f64::max);
+                let others = builds.iter().sum::<f64>() - slowest_build;
+                let count = builds.len() - 1;
+                BatchTiming {
+                    file: file.to_string(),
+                    mutants: builds.len(),
+                    slowest_build,
+                    average_build: others / count as f64,
+                    saved: slowest_build * count as f64 - others,
+                }
+            })
+            .collect()
+    }
+
+    /// One line description, as in the summary file
+    pub(crate) fn simple(&self) -> String {
+        format!(
+            "[Batch] : File {}, {} mutants, slowest build {:.2}s, other builds {:.2}s on average, about {:.2}s saved",
+            self.file, self.mutants, self.slowest_build, self.average_build, self.saved
+        )
+    }
+}
+
+/// Function whose mutants all survive although tests execute it: its tests don't assert enough
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WeakOracle {
+    /// Path relative to the project
+    pub(crate) file: String,
+    pub(crate) function: String,
+    /// Number of missing mutants of the function
+    pub(crate) mutants: usize,
+    /// Tests which execute the function, when coverage is recorded per test
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tests: Vec<String>,
+}
+
+impl WeakOracle {
+    /// One line description, as in the summary file
+    pub(crate) fn simple(&self) -> String {
+        let tests = if self.tests.is_empty() {
+            "tests".to_string()
+        } else {
+            format!("tests {}", self.tests.join(", "))
+        };
+        format!(
+            "[Weak oracle] : Function \"{}\" of file {}, {} mutants missing, executed by {tests} but not asserted",
+            self.function, self.file, self.mutants
+        )
+    }
+}
+
+/// Function never used, whose mutants are skipped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DeadFunction {
+    /// Path relative to the project
+    pub(crate) file: String,
+    pub(crate) function: String,
+    /// Number of mutants skipped
+    pub(crate) mutants: usize,
+}
+
+impl DeadFunction {
+    /// One line description, as in the summary file
+    pub(crate) fn simple(&self) -> String {
+        format!(
+            "[Dead code] : Function \"{}\" of file {}, never used, {} mutants skipped",
+            self.function, self.file, self.mutants
+        )
+    }
+}
+
+/// Source file which can't be mutated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SkippedFile {
+    /// Path relative to the project
+    pub(crate) file: String,
+    /// `unreadable` or `parse_failure`
+    #[serde(default = "unreadable")]
+    pub(crate) cause: SkipReason,
+    /// What was wrong with the file
+    pub(crate) reason: String,
+}
+
+/// Cause of the files skipped before their cause was recorded, parse failures stopped the run
+fn unreadable() -> SkipReason {
+    SkipReason::Unreadable
+}
+
+impl SkippedFile {
+    /// One line description, as in the summary file
+    pub(crate) fn simple(&self) -> String {
+        format!("[Skipped] : File {}, {}", self.file, self.reason)
+    }
+}
+
+/// Mutant left out of the run, never built nor tested
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SkippedMutant {
+    pub(crate) stable_id: String,
+    /// Path relative to the project
+    pub(crate) file: String,
+    pub(crate) function: String,
+    /// Mutation left out, such as `replace + by -`
+    pub(crate) mutation: String,
+    /// 1-based line of the mutated chunk start
+    pub(crate) line: usize,
+    /// 1-based column of the mutated chunk start, in bytes
+    pub(crate) column: usize,
+    pub(crate) reason: SkipReason,
+}
+
+impl SkippedMutant {
+    pub(crate) fn new(mutation: &Mutation, reason: SkipReason) -> eyre::Result<Self> {
+        Ok(SkippedMutant {
+            stable_id: mutation.stable_id()?,
+            file: mutation.relative_file()?,
+            function: mutation.function_name.clone(),
+            mutation: mutation.reason.clone(),
+            line: mutation.chunk.start_point.row + 1,
+            column: mutation.chunk.start_point.column + 1,
+            reason,
+        })
+    }
+
+    /// One line description, as in the summary file
+    pub(crate) fn simple(&self) -> String {
+        format!(
+            "[Skipped] : Mutation {} in function \"{}\" of file {} at line {}:{}, {}",
+            self.mutation,
+            self.function,
+            self.file,
+            self.line,
+            self.column,
+            self.reason.name()
+        )
+    }
+}
+
+/// Number of mutants left out of the run, by reason
+///
+/// Reasons missing from the results of older versions count no mutant.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Exclusions {
+    /// Files excluded by patterns or not selected, their mutants aren't counted
+    pub(crate) files: usize,
+    /// Mutants of functions or operators not selected
+    pub(crate) filter: usize,
+    /// Mutants of operators disabled on their file by the configuration
+    pub(crate) config: usize,
+    /// Mutants identical to another one or leaving the code unchanged
+    pub(crate) equivalent: usize,
+    /// Mutants of combinations which never built
+    pub(crate) unviable: usize,
+    /// Mutants of functions compiled in no test target
+    pub(crate) not_compiled: usize,
+    /// Mutants of functions never used
+    pub(crate) dead_code: usize,
+    /// Mutants only executed by `#[ignore]` tests
+    pub(crate) ignored_tests: usize,
+    /// Mutants left out by `--sample`
+    pub(crate) sample: usize,
+    /// Mutants left out by `--budget`
+    pub(crate) budget: usize,
+    /// Mutants whose `pre_mutant` hook failed
+    pub(crate) hook: usize,
+}
+
+impl Exclusions {
+    /// Count the mutants left out for each reason, along with the files excluded
+    pub(crate) fn new(files: usize, not_attempted: &[SkippedMutant]) -> Self {
+        let mut exclusions = Exclusions {
+            files,
+            ..Default::default()
+        };
+        for skipped in not_attempted {
+            if let Some(count) = exclusions.count_mut(skipped.reason) {
+                *count += 1;
+            }
+        }
+        exclusions
+    }
+
+    /// Count of the mutants left out for a reason, none for the reasons of whole files
+    fn count_mut(&mut self, reason: SkipReason) -> Option<&mut usize> {
+        match reason {
+            SkipReason::Filter => Some(&mut self.filter),
+            SkipReason::Config => Some(&mut self.config),
+            SkipReason::Equivalent => Some(&mut self.equivalent),
+            SkipReason::Unviable => Some(&mut self.unviable),
+            SkipReason::NotCompiled => Some(&mut self.not_compiled),
+            SkipReason::DeadCode => Some(&mut self.dead_code),
+            SkipReason::IgnoredTests => Some(&mut self.ignored_tests),
+            SkipReason::Sample => Some(&mut self.sample),
+            SkipReason::Budget => Some(&mut self.budget),
+            SkipReason::Hook => Some(&mut self.hook),
+            SkipReason::Unreadable | SkipReason::ParseFailure => None,
+        }
+    }
+
+    /// One line description, as in the summary file, `None` when nothing is excluded
+    pub(crate) fn simple(&self) -> Option<String> {
+        let reasons = [
+            (self.filter, SkipReason::Filter),
+            (self.config, SkipReason::Config),
+            (self.equivalent, SkipReason::Equivalent),
+            (self.unviable, SkipReason::Unviable),
+            (self.not_compiled, SkipReason::NotCompiled),
+            (self.dead_code, SkipReason::DeadCode),
+            (self.ignored_tests, SkipReason::IgnoredTests),
+            (self.sample, SkipReason::Sample),
+            (self.budget, SkipReason::Budget),
+            (self.hook, SkipReason::Hook),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, reason)| format!("{count} {}", reason.name()))
+        .collect::<Vec<_>>();
+        let mut parts = vec![];
+        if !reasons.is_empty() {
+            parts.push(format!(
+                "{} mutants ({})",
+                self.mutants(),
+                reasons.join(", ")
+            ));
+        }
+        if self.files > 0 {
+            parts.push(format!("{} files", self.files));
+        }
+        (!parts.is_empty()).then(|| format!("[Excluded] : {}", parts.join(", ")))
+    }
+
+    /// Number of mutants excluded, whatever the reason
+    pub(crate) fn mutants(&self) -> usize {
+        self.filter
+            + self.config
+            + self.equivalent
+            + self.unviable
+            + self.not_compiled
+            + self.dead_code
+            + self.ignored_tests
+            + self.sample
+            + self.budget
+            + self.hook
+    }
+}
+
+/// Results of a whole run
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct RunResults {
+    /// Version of the format, 0 for results written before it was versioned
+    #[serde(default)]
+    pub(crate) schema_version: u32,
+    pub(crate) mutants: Vec<MutantResult>,
+    /// Source files which can't be mutated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) skipped: Vec<SkippedFile>,
+    /// Functions never used, whose mutants are left out of the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) dead_code: Vec<DeadFunction>,
+    /// Mutants left out of the run
+    #[serde(default)]
+    pub(crate) excluded: Exclusions,
+    /// Each mutant left out of the run, with the reason why
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) not_attempted: Vec<SkippedMutant>,
+    /// Functions executed by tests which catch none of their mutants, found with coverage data
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) weak_oracles: Vec<WeakOracle>,
+    /// Duration in seconds of the tests without mutation, when measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) baseline: Option<f64>,
+    /// Duration of the run in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) duration: Option<f64>,
+    /// Build time saved on each file by verifying its mutants one after the other, estimated when the
+    /// builds are timed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) batches: Vec<BatchTiming>,
+    /// Hash of the mutated files content at the time of the run, by path relative to the project
+    #[serde(default)]
+    pub(crate) hashes: BTreeMap<String, u64>,
+}
+
+impl Default for RunResults {
+    fn default() -> Self {
+        RunResults {
+            schema_version: SCHEMA_VERSION,
+            mutants: vec![],
+            skipped: vec![],
+            dead_code: vec![],
+            excluded: Exclusions::default(),
+            not_attempted: vec![],
+            weak_oracles: vec![],
+            baseline: None,
+            duration: None,
+            batches: vec![],
+            hashes: BTreeMap::new(),
+        }
+    }
+}
+
+impl RunResults {
+    pub(crate) fn from_mutations(mutations: &[Mutation]) -> eyre::Result<Self> {
+        let mutants = mutations
+            .iter()
+            .filter_map(|mutation| {
+                let report = mutation.get_report()?;
+                Some((mutation, report))
+            })
+            .map(|(mutation, report)| {
+                Ok(MutantResult {
+                    mutation: mutation.record()?,
+                    status: report.status,
+                    caught_by: report.failed_tests(),
+                    errors: report.error_codes(),
+                    execution: None,
+                    duration: report.duration.map(|duration| duration.as_secs_f64()),
+                    build_duration: report.build_duration.map(|duration| duration.as_secs_f64()),
+                    warnings: report.warnings.clone(),
+                    diagnostics: report.diagnostics.clone(),
+                    timeout: report.timeout,
+                    log: Path::new("reports")
+                        .join(format!("mutation_{}.log", mutation.get_mutation_id())),
+                    diff: mutation.diff(false)?,
+                })
+            })
+            .collect::<eyre::Result<_>>()?;
+        Ok(RunResults {
+            mutants,
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn save(&self, mutation_root: &Path) -> eyre::Result<()> {
+        let file = File::create(mutation_root.join(RESULTS_FILE))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+
+    pub(crate) fn load(mutation_root: &Path) -> eyre::Result<Self> {
+        let path = mutation_root.join(RESULTS_FILE);
+        let file = File::open(&path).wrap_err(eyre!(
+            "No results found in {}, run darwin first",
+            path.display()
+        ))?;
+        let results: RunResults = serde_json::from_reader(BufReader::new(file))
+            .wrap_err(eyre!("Unable to read results {}", path.display()))?;
+        results
+            .upgrade()
+            .wrap_err(eyre!("Unable to read results {}", path.display()))
+    }
+
+    /// Results in the current format, provided their format is known
+    fn upgrade(self) -> eyre::Result<Self> {
+        if self.schema_version > SCHEMA_VERSION {
+            return Err(eyre!(
+                "Schema version {} is newer than the supported one {SCHEMA_VERSION}, update darwin",
+                self.schema_version
+            ));
+        }
+        Ok(RunResults {
+            schema_version: SCHEMA_VERSION,
+            ..self
+        })
+    }
+
+    /// Number of mutants and files left out of the run for one of these reasons
+    pub(crate) fn skipped_for(&self, reasons: &[SkipReason]) -> usize {
+        let mutants = self
+            .not_attempted
+            .iter()
+            .filter(|skipped| reasons.contains(&skipped.reason))
+            .count();
+        let files = self
+            .skipped
+            .iter()
+            .filter(|skipped| reasons.contains(&skipped.cause))
+            .count();
+        mutants + files
+    }
+
+    /// Mutants in the order of a sort key, ties broken by their ID
+    pub(crate) fn sorted(&self, sort: SortKey) -> Vec<&MutantResult> {
+        let mut mutants = self.mutants.iter().collect::<Vec<_>>();
+        match sort {
+            SortKey::Id => {}
+            SortKey::File => mutants.sort_by(|a, b| a.position().cmp(&b.position())),
+            SortKey::Status => mutants.sort_by(|a, b| {
+                (a.status.severity(), a.position()).cmp(&(b.status.severity(), b.position()))
+            }),
+            // Mutants whose tests didn't run last
+            SortKey::Duration => mutants.sort_by(|a, b| {
+                let duration = |result: &MutantResult| result.duration.unwrap_or(-1.0);
+                duration(b).total_cmp(&duration(a))
+            }),
+        }
+        mutants
+    }
+
+    /// Replace results of mutants verified again, keep the others
+    pub(crate) fn merge(&mut self, other: RunResults) {
+        for result in other.mutants {
+            match self
+                .mutants
+                .iter_mut()
+                .find(|previous| previous.mutation.id == result.mutation.id)
+            {
+                Some(previous) => *previous = result,
+                None => self.mutants.push(result),
+            }
+        }
+        self.mutants.sort_by_key(|result| result.mutation.id);
+    }
+
+    /// Functions whose mutants which built are all missing, although executed by tests
+    ///
+    /// Mutants must have been annotated with coverage data beforehand.
+    pub(crate) fn find_weak_oracles(&self) -> Vec<WeakOracle> {
+        let mut functions: Vec<(&str, &str, Vec<&MutantResult>)> = vec![];
+        for result in &self.mutants {
+            let MutationRecord { file, function, .. } = &result.mutation;
+            if function.is_empty() || result.status == MutationStatus::CompilationFailed {
+                continue;
+            }
+            match functions
+                .iter_mut()
+                .find(|(other_file, other_function, _)| {
+                    other_file == file && other_function == function
+                }) {
+                Some((_, _, results)) => results.push(result),
+                None => functions.push((file, function, vec![result])),
+            }
+        }
+        functions
+            .into_iter()
+            .filter(|(_, _, results)| {
+                results.iter().all(|result| {
+                    result.status == MutationStatus::Success
+                        && result
+                            .execution
+                            .as_ref()
+                            .is_some_and(|execution| execution.executed)
+                })
+            })
+            .map(|(file, function, results)| {
+                let mut tests: Vec<String> = vec![];
+                for test in results.iter().flat_map(|result| {
+                    result
+                        .execution
+                        .iter()
+                        .flat_map(|execution| &execution.tests)
+                }) {
+                    if !tests.contains(test) {
+                        tests.push(test.clone());
+                    }
+                }
+                WeakOracle {
+                    file: file.to_string(),
+                    function: function.to_string(),
+                    mutants: results.len(),
+                    tests,
+                }
+            })
+            .collect()
+    }
+
+    /// Number of mutants by status
+    pub(crate) fn counts(&self) -> StatusCounts {
+        StatusCounts::count(&self.mutants)
+    }
+
+    /// Mutants whose tests ran much faster or slower than without mutation, as summary lines
+    pub(crate) fn timing_outliers(&self) -> Vec<String> {
+        let Some(baseline) = self.baseline else {
+            return vec![];
+        };
+        self.mutants
+            .iter()
+            .filter_map(|result| result.timing(baseline))
+            .collect()
+    }
+
+    /// Find a mutant by numeric ID or stable ID
+    pub(crate) fn find(&self, id: &str) -> Option<&MutantResult> {
+        self.mutants
+            .iter()
+            .find(|result| result.mutation.stable_id == id || result.mutation.id.to_string() == id)
+    }
+}
+
+/// `summary.json` written before the schema was versioned, with a single missing mutant
+#[cfg(test)]
+pub(crate) static SUMMARY_V0: &str = r#"{
+  "mutants": [
+    {
+      "id": 0,
+      "stable_id": "src-lib-add-5-7-sub",
+      "file": "src/lib.rs",
+      "function": "add",
+      "operator": "arithmetic",
+      "reason": "replace + by -",
+      "line": 5,
+      "column": 7,
+      "start": 46,
+      "end": 47,
+      "original": "+",
+      "replacement": "-",
+      "status": "missing",
+      "caught_by": [],
+      "log": "reports/mutation_0.log",
+      "diff": ""
+    }
+  ]
+}"#;
+
+#[cfg(test)]
+impl MutantResult {
+    /// Result of a mutant replacing the `+` of `x + y` in function `add` of `src/lib.rs`, for the
+    /// tests of the reports
+    pub(crate) fn add_mutant(status: MutationStatus, replacement: &str, name: &str) -> Self {
+        MutantResult {
+            mutation: MutationRecord {
+                id: 0,
+                stable_id: format!("src-lib-add-2-7-{name}"),
+                file: "src/lib.rs".to_string(),
+                function: "add".to_string(),
+                operator: "arithmetic".to_string(),
+                reason: format!("replace + by {replacement}"),
+                line: 2,
+                column: 7,
+                end_line: 2,
+                end_column: 8,
+                start: 35,
+                end: 36,
+                original: "+".to_string(),
+                replacement: replacement.to_string(),
+                context: "".to_string(),
+                loop_control: false,
+            },
+            status,
+            caught_by: vec![],
+            errors: vec![],
+            execution: None,
+            duration: None,
+            build_duration: None,
+            warnings: vec![],
+            diagnostics: vec![],
+            timeout: None,
+            log: "reports/mutation_0.log".into(),
+            diff: "".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::results::{
+        BatchTiming, Exclusions, RunResults, SkippedFile, SkippedMutant, WeakOracle,
+        SCHEMA_VERSION, SUMMARY_V0,
+    };
+    use crate::cli::SortKey;
+    use crate::report::{MutationStatus, SkipReason};
+
+    /// `summary.json` as written by the current version, fields must not be renamed or removed
+    /// without bumping the schema version
+    static SUMMARY_V1: &str = r#"{
+  "schema_version": 1,
+  "mutants": [
+    {
+      "id": 1,
+      "stable_id": "src-lib-add-5-7-mul",
+      "file": "src/lib.rs",
+      "function": "add",
+      "operator": "arithmetic",
+      "reason": "replace + by *",
+      "line": 5,
+      "column": 7,
+      "end_line": 5,
+      "end_column": 8,
+      "start": 46,
+      "end": 47,
+      "original": "+",
+      "replacement": "*",
+      "context": "block",
+      "status": "killed",
+      "caught_by": [],
+      "errors": ["E0308"],
+      "log": "reports/mutation_1.log",
+      "diff": "-    x + y\n+    x * y\n"
+    }
+  ],
+  "skipped": [
+    {
+      "file": "src/legacy.rs",
+      "cause": "unreadable",
+      "reason": "binary file"
+    }
+  ],
+  "excluded": {
+    "files": 1,
+    "filter": 0,
+    "config": 0,
+    "equivalent": 2,
+    "unviable": 0,
+    "not_compiled": 0,
+    "dead_code": 0,
+    "ignored_tests": 0,
+    "sample": 3,
+    "budget": 0,
+    "hook": 0
+  },
+  "hashes": {
+    "src/lib.rs": 42
+  }
+}"#;
+
+    #[test]
+    fn test_schema_stability() {
+        let results: RunResults = serde_json::from_str(SUMMARY_V1).unwrap();
+        let results = results.upgrade().unwrap();
+        assert_eq!(results.mutants[0].status, MutationStatus::CompilationFailed);
+        assert_eq!(results.skipped[0].reason, "binary file");
+        assert_eq!(results.skipped[0].cause, SkipReason::Unreadable);
+        assert_eq!(
+            results.excluded.simple().unwrap(),
+            "[Excluded] : 5 mutants (2 equivalent, 3 sample), 1 files"
+        );
+        assert_eq!(
+            serde_json::to_value(&results).unwrap(),
+            serde_json::from_str::<serde_json::Value>(SUMMARY_V1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_not_attempted() {
+        let skipped = |stable_id: &str, reason| SkippedMutant {
+            stable_id: stable_id.to_string(),
+            file: "src/lib.rs".to_string(),
+            function: "double".to_string(),
+            mutation: "replace + by *".to_string(),
+            line: 7,
+            column: 7,
+            reason,
+        };
+        let not_attempted = vec![
+            skipped("src-lib-double-7-7-mul", SkipReason::DeadCode),
+            skipped("src-lib-double-7-7-sub", SkipReason::DeadCode),
+            skipped("src-lib-add-2-7-mul", SkipReason::Sample),
+        ];
+        let excluded = Exclusions::new(1, &not_attempted);
+        assert_eq!(
+            excluded.simple().unwrap(),
+            "[Excluded] : 3 mutants (2 dead code, 1 sample), 1 files"
+        );
+        assert_eq!(
+            not_attempted[0].simple(),
+            "[Skipped] : Mutation replace + by * in function \"double\" of file src/lib.rs at line 7:7, dead code"
+        );
+        assert_eq!(
+            serde_json::to_value(&not_attempted[0]).unwrap()["reason"],
+            "dead_code"
+        );
+
+        let results = RunResults {
+            not_attempted,
+            skipped: vec![SkippedFile {
+                file: "tests/fixtures/broken.rs".to_string(),
+                cause: SkipReason::ParseFailure,
+                reason: "parse failure at line 2".to_string(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(results.skipped_for(&[]), 0);
+        assert_eq!(results.skipped_for(&[SkipReason::DeadCode]), 2);
+        assert_eq!(
+            results.skipped_for(&[SkipReason::ParseFailure, SkipReason::Sample]),
+            2
+        );
+    }
+
+    #[test]
+    fn test_weak_oracles() {
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mutant = results.mutants[0].clone();
+        results.mutants = vec![mutant.clone(), mutant.clone(), mutant];
+        results.mutants[1].status = MutationStatus::CompilationFailed;
+        for result in &mut results.mutants {
+            result.execution =
+                serde_json::from_str(r#"{"executed": true, "tests": ["test_add"]}"#).unwrap();
+        }
+        assert_eq!(
+            results.find_weak_oracles(),
+            vec![WeakOracle {
+                file: "src/lib.rs".to_string(),
+                function: "add".to_string(),
+                mutants: 2,
+                tests: vec!["test_add".to_string()]
+            }]
+        );
+
+        results.mutants[2].execution = None;
+        assert!(results.find_weak_oracles().is_empty());
+    }
+
+    #[test]
+    fn test_sorted() {
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mutant = results.mutants[0].clone();
+        results.mutants = vec![mutant.clone(), mutant.clone(), mutant.clone(), mutant];
+        let statuses = [
+            MutationStatus::CompilationFailed,
+            MutationStatus::Fail,
+            MutationStatus::Success,
+            MutationStatus::Timeout,
+        ];
+        for (id, result) in results.mutants.iter_mut().enumerate() {
+            result.mutation.id = id;
+            result.status = statuses[id];
+            result.duration = [None, Some(0.5), Some(2.0), Some(60.0)][id];
+        }
+        results.mutants[3].mutation.file = "src/a.rs".to_string();
+        results.mutants[1].mutation.line = 2;
+        let ids = |sort: SortKey| {
+            results
+                .sorted(sort)
+                .iter()
+                .map(|result| result.mutation.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(SortKey::Id), vec![0, 1, 2, 3]);
+        assert_eq!(ids(SortKey::File), vec![3, 1, 0, 2]);
+        assert_eq!(ids(SortKey::Status), vec![2, 3, 1, 0]);
+        assert_eq!(ids(SortKey::Duration), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        assert_eq!(
+            results.mutants[0].diagnostic(),
+            "src/lib.rs:5:7: warning: mutant survived, replace + by - in function \"add\" (mutant src-lib-add-5-7-sub)"
+        );
+    }
+
+    #[test]
+    fn test_timing_outliers() {
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mutant = results.mutants[0].clone();
+        results.mutants = vec![mutant.clone(), mutant.clone(), mutant.clone(), mutant];
+        for (result, duration) in results.mutants.iter_mut().zip([0.1, 1.5, 9.0, 60.0]) {
+            result.duration = Some(duration);
+        }
+        results.mutants[3].status = MutationStatus::Timeout;
+        assert!(results.timing_outliers().is_empty());
+
+        results.baseline = Some(2.0);
+        assert_eq!(
+            results.timing_outliers(),
+            vec![
+                "[Faster] : Mutation #0 replace + by - in function \"add\" of file src/lib.rs, tests ran in 0.10s, 20.0x faster than without mutation",
+                "[Slower] : Mutation #0 replace + by - in function \"add\" of file src/lib.rs, tests ran in 9.00s, 4.5x slower than without mutation",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_timings() {
+        let results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mutant = results.mutants[0].clone();
+        let mut mutants = vec![mutant.clone(), mutant.clone(), mutant.clone(), mutant];
+        for (result, build_duration) in
+            mutants
+                .iter_mut()
+                .zip([Some(8.0), Some(2.0), Some(3.0), None])
+        {
+            result.build_duration = build_duration;
+        }
+        mutants[2].mutation.file = "src/a/toto.rs".to_string();
+        assert!(BatchTiming::from_results(&mutants[2..]).is_empty());
+
+        mutants[1].mutation.file = "src/a/toto.rs".to_string();
+        mutants[0].build_duration = Some(1.0);
+        mutants[3].build_duration = Some(4.0);
+        let batches = BatchTiming::from_results(&mutants);
+        assert_eq!(
+            batches.iter().map(BatchTiming::simple).collect::<Vec<_>>(),
+            vec![
+                "[Batch] : File src/a/toto.rs, 2 mutants, slowest build 3.00s, other builds 2.00s on average, about 1.00s saved",
+                "[Batch] : File src/lib.rs, 2 mutants, slowest build 4.00s, other builds 1.00s on average, about 3.00s saved",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_compatibility() {
+        let results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let results = results.upgrade().unwrap();
+        assert_eq!(results.schema_version, SCHEMA_VERSION);
+        assert_eq!(results.mutants[0].mutation.stable_id, "src-lib-add-5-7-sub");
+        assert!(results.hashes.is_empty());
+        assert_eq!(results.excluded.simple(), None);
+
+        // Written again, then read back unchanged
+        let written = serde_json::to_value(&results).unwrap();
+        let read: RunResults = serde_json::from_value(written.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(read.upgrade().unwrap()).unwrap(),
+            written
+        );
+
+        // Exclusions written before some of their reasons existed
+        let older = SUMMARY_V0.replacen(
+            "{",
+            r#"{"excluded": {"files": 1, "filter": 0, "config": 0, "equivalent": 2, "unviable": 0, "sample": 3, "budget": 0},"#,
+            1,
+        );
+        let results: RunResults = serde_json::from_str(&older).unwrap();
+        assert_eq!(results.excluded.dead_code, 0);
+        assert_eq!(results.excluded.mutants(), 5);
+
+        let newer = SUMMARY_V1.replace(
+            "\"schema_version\": 1",
+            &format!("\"schema_version\": {}", SCHEMA_VERSION + 1),
+        );
+        let results: RunResults = serde_json::from_str(&newer).unwrap();
+        assert!(results.upgrade().is_err());
+    }
+}
@@ -124,13 +124,7 @@ where
         fn format_diff_old(diffs: Vec<Difference>) -> String {
             diffs
                 .iter()
-                .filter(|diff| {
-                    if let Difference::Add(..) = diff {
-                        false
-                    } else {
-                        true
-                    }
-                })
+                .filter(|diff| !matches!(diff, Difference::Add(..)))
                 .map(|diff| match diff {
                     Difference::Same(x) => x.normal(),
                     Difference::Add(x) => x.green(),
@@ -147,13 +141,7 @@ where
         fn format_diff_new(diffs: Vec<Difference>) -> String {
             diffs
                 .iter()
-                .filter(|diff| {
-                    if let Difference::Add(..) = diff {
-                        false
-                    } else {
-                        true
-                    }
-                })
+                .filter(|diff| !matches!(diff, Difference::Add(..)))
                 .map(|diff| match diff {
                     Difference::Same(x) => x.normal(),
                     Difference::Add(x) => x.red(),
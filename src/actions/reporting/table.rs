@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use crate::actions::reporting::results::{MutantResult, RunResults, StatusCounts};
+
+/// Share of mutants killed at compile time beyond which an operators family is reported unviable
+static UNVIABLE_RATE: f64 = 0.9;
+/// Number of mutants of an operators family below which its share of killed mutants tells nothing
+static UNVIABLE_MIN_MUTANTS: usize = 10;
+
+/// Number of mutants by status of each group
+fn counts_by<'a>(
+    results: &'a RunResults,
+    group: impl Fn(&'a MutantResult) -> &'a str,
+) -> BTreeMap<&'a str, StatusCounts> {
+    let mut groups: BTreeMap<&str, Vec<&MutantResult>> = BTreeMap::new();
+    for result in &results.mutants {
+        groups.entry(group(result)).or_default().push(result);
+    }
+    groups
+        .into_iter()
+        .map(|(name, results)| (name, StatusCounts::count(results)))
+        .collect()
+}
+
+/// Number of mutants by status of each file
+fn file_counts(results: &RunResults) -> BTreeMap<&str, StatusCounts> {
+    counts_by(results, |result| &result.mutation.file)
+}
+
+/// Table of the mutants caught and missing by file, followed by the totals
+pub(crate) fn table(results: &RunResults) -> String {
+    let row = |name: &str, counts: StatusCounts| {
+        let score = match counts.score() {
+            Some(score) => format!("{:.0}%", score * 100.0),
+            None => "-".to_string(),
+        };
+        [
+            name.to_string(),
+            counts.mutants.to_string(),
+            counts.ok.to_string(),
+            counts.missing.to_string(),
+            score,
+        ]
+    };
+    let mut rows = vec![["File", "Mutants", "Caught", "Missing", "Score"].map(String::from)];
+    rows.extend(
+        file_counts(results)
+            .into_iter()
+            .map(|(file, counts)| row(file, counts)),
+    );
+    rows.push(row("Total", results.counts()));
+
+    let widths = (0..5)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    rows.iter()
+        .map(|row| {
+            // File names are aligned to the left, numbers to the right
+            let mut line = format!("{:<width$}", row[0], width = widths[0]);
+            for (cell, width) in row.iter().zip(&widths).skip(1) {
+                line.push_str(&format!("  {cell:>width$}"));
+            }
+            line + "\n"
+        })
+        .collect()
+}
+
+/// The `count` files with the lowest score among the ones with missing mutants, lowest first
+pub(crate) fn weakest_files(results: &RunResults, count: usize) -> Vec<String> {
+    let mut files = file_counts(results)
+        .into_iter()
+        .filter(|(_, counts)| counts.missing > 0)
+        .filter_map(|(file, counts)| Some((file, counts.score()?, counts.missing)))
+        .collect::<Vec<_>>();
+    // Equal scores put first the files with the most mutants to catch
+    files.sort_by(|(_, score, missing), (_, other_score, other_missing)| {
+        score
+            .total_cmp(other_score)
+            .then(other_missing.cmp(missing))
+    });
+    files
+        .into_iter()
+        .take(count)
+        .map(|(file, score, missing)| {
+            format!(
+                "{file}: score {:.0}%, {missing} missing mutants",
+                score * 100.0
+            )
+        })
+        .collect()
+}
+
+/// Share of the mutants of each operators family killed at compile time
+pub(crate) fn kill_rates(results: &RunResults) -> Vec<String> {
+    counts_by(results, |result| &result.mutation.operator)
+        .into_iter()
+        .map(|(operator, counts)| {
+            format!(
+                "{operator}: {} of {} mutants killed at compile time ({:.0}%)",
+                counts.killed,
+                counts.mutants,
+                counts.killed as f64 * 100.0 / counts.mutants as f64
+            )
+        })
+        .collect()
+}
+
+/// Warnings for the operators families whose mutants almost never build on the project
+pub(crate) fn unviable_operators(results: &RunResults) -> Vec<String> {
+    counts_by(results, |result| &result.mutation.operator)
+        .into_iter()
+        .filter(|(_, counts)| {
+            counts.mutants >= UNVIABLE_MIN_MUTANTS
+                && counts.killed as f64 > counts.mutants as f64 * UNVIABLE_RATE
+        })
+        .map(|(operator, counts)| {
+            format!(
+                "[Unviable operator] : Operator \"{operator}\", {} of {} mutants killed at compile time, consider disabling it with --exclude-operator {operator}",
+                counts.killed, counts.mutants
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::results::{RunResults, SUMMARY_V0};
+    use crate::actions::reporting::table::{kill_rates, table, unviable_operators, weakest_files};
+    use crate::report::MutationStatus;
+
+    #[test]
+    fn test_table() {
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mutant = results.mutants[0].clone();
+        results.mutants.extend([mutant.clone(), mutant]);
+        results.mutants[1].status = MutationStatus::Fail;
+        results.mutants[2].status = MutationStatus::CompilationFailed;
+        results.mutants[2].mutation.file = "src/a/toto.rs".to_string();
+
+        assert_eq!(
+            table(&results),
+            "File           Mutants  Caught  Missing  Score\n\
+             src/a/toto.rs        1       0        0      -\n\
+             src/lib.rs           2       1        1    50%\n\
+             Total                3       1        1    50%\n"
+        );
+        assert_eq!(
+            weakest_files(&results, 5),
+            vec!["src/lib.rs: score 50%, 1 missing mutants"]
+        );
+        assert!(weakest_files(&results, 0).is_empty());
+        assert_eq!(
+            kill_rates(&results),
+            vec!["arithmetic: 1 of 3 mutants killed at compile time (33%)"]
+        );
+        assert!(unviable_operators(&results).is_empty());
+
+        let mut killed = results.mutants[2].clone();
+        killed.mutation.operator = "logical".to_string();
+        results.mutants.extend(vec![killed.clone(); 9]);
+        assert!(unviable_operators(&results).is_empty());
+        results.mutants.push(killed);
+        assert_eq!(
+            unviable_operators(&results),
+            vec!["[Unviable operator] : Operator \"logical\", 10 of 10 mutants killed at compile time, consider disabling it with --exclude-operator logical"]
+        );
+    }
+}
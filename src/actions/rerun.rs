@@ -0,0 +1,126 @@
+use eyre::eyre;
+
+use crate::actions::coverage::Coverage;
+use crate::actions::reporting::results::{MutantResult, RunResults};
+use crate::actions::{generate, reporting};
+use crate::config::Settings;
+use crate::mutation::Mutation;
+use crate::report::MutationStatus;
+
+/// Mutants of the last run matching `ids` or, if `missing`, all the mutants which weren't caught
+fn select<'a>(
+    results: &'a RunResults,
+    ids: &[String],
+    missing: bool,
+) -> eyre::Result<Vec<&'a MutantResult>> {
+    if missing {
+        return Ok(results
+            .mutants
+            .iter()
+            .filter(|result| result.status == MutationStatus::Success)
+            .collect());
+    }
+    ids.iter()
+        .map(|id| {
+            results
+                .find(id)
+                .ok_or(eyre!("No mutant {id} in the last run"))
+        })
+        .collect()
+}
+
+/// Verify again mutants of the last run against the current code
+///
+/// Either the mutants matching `ids` or, if `missing`, all the mutants which weren't caught
+pub(crate) fn rerun(ids: &[String], missing: bool, settings: &Settings) -> eyre::Result<()> {
+    let results = RunResults::load(&settings.mutation_path)?;
+    let coverage = Coverage::from_settings(settings)?;
+    let selected = select(&results, ids, missing)?;
+
+    if selected.is_empty() {
+        println!("No mutant to verify");
+        return Ok(());
+    }
+
+    let mut mutants = selected
+        .iter()
+        .map(|result| Mutation::from_record(&result.mutation, &settings.root_path))
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    let ids = mutants
+        .iter()
+        .map(Mutation::get_mutation_id)
+        .collect::<Vec<_>>();
+    // Streamed verdicts would otherwise be added to the lines of the previous ones
+    reporting::prepare_update(&ids, &settings.mutation_path, settings.sort)?;
+    generate::generate_and_verify_mutants(&mut mutants, settings, &results)?;
+    let results = reporting::update_reports(
+        &mutants,
+        coverage.as_ref(),
+        &settings.mutation_path,
+        settings.sort,
+    )?;
+    generate::print_survivors(&results, settings);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::results::{MutantResult, RunResults, SUMMARY_V0};
+    use crate::actions::rerun::select;
+    use crate::report::MutationStatus;
+    use crate::scratch::ScratchFolder;
+
+    /// Last run with a killed, a missing, a caught and a timed out mutant, read back from its
+    /// summary
+    fn last_run(folder: &ScratchFolder) -> RunResults {
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        results.mutants = vec![
+            MutantResult::add_mutant(MutationStatus::CompilationFailed, "==", "eq"),
+            MutantResult::add_mutant(MutationStatus::Success, "-", "sub"),
+            MutantResult::add_mutant(MutationStatus::Fail, "*", "mul"),
+            MutantResult::add_mutant(MutationStatus::Timeout, "/", "div"),
+        ];
+        for (id, result) in results.mutants.iter_mut().enumerate() {
+            result.mutation.id = id;
+        }
+        results.save(folder).unwrap();
+        RunResults::load(folder).unwrap()
+    }
+
+    fn stable_ids(selected: &[&MutantResult]) -> Vec<String> {
+        selected
+            .iter()
+            .map(|result| result.mutation.stable_id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_select_missing() {
+        let folder = ScratchFolder::new("rerun");
+        let results = last_run(&folder);
+        let ids = ["src-lib-add-2-7-mul".to_string()];
+        let selected = select(&results, &ids, true).unwrap();
+        assert_eq!(stable_ids(&selected), vec!["src-lib-add-2-7-sub"]);
+    }
+
+    #[test]
+    fn test_select_ids() {
+        let folder = ScratchFolder::new("rerun");
+        let results = last_run(&folder);
+        let ids = ["src-lib-add-2-7-div".to_string(), "2".to_string()];
+        let selected = select(&results, &ids, false).unwrap();
+        assert_eq!(
+            stable_ids(&selected),
+            vec!["src-lib-add-2-7-div", "src-lib-add-2-7-mul"]
+        );
+
+        let ids = ["src-lib-add-2-7-and".to_string()];
+        let error = select(&results, &ids, false).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No mutant src-lib-add-2-7-and in the last run"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use eyre::{eyre, WrapErr};
+
+use crate::actions::reporting::results::RESULTS_FILE;
+
+/// Link of the mutation root to the folder of the last finished run
+pub(crate) static LATEST: &str = "latest";
+/// Scratch folder of the mutation root where the `verify` subcommand checks single mutants
+pub(crate) static VERIFY: &str = "verify";
+/// Runs which never finished are removed once their folder is untouched for this long, the ones
+/// still in progress are left alone
+static ABANDONED_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Civil date of days since the Unix epoch, as year, month and day
+fn civil_date(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// `YYYYMMDD-HHMMSS-<pid>`, in UTC, sorted as the runs started
+///
+/// The process ID tells apart two runs started in the same second.
+fn run_id(now: SystemTime, pid: u32) -> String {
+    let seconds = now
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let (year, month, day) = civil_date(seconds.div_euclid(86_400));
+    let time = seconds.rem_euclid(86_400);
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}-{pid}",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Create the folder of a new run in the mutation root
+///
+/// Mutant projects and reports of the run are written there, so that runs sharing the mutation
+/// root never remove each other's files.
+pub(crate) fn start_run(mutation_root: &Path) -> eyre::Result<PathBuf> {
+    std::fs::create_dir_all(mutation_root)?;
+    let run_path = mutation_root.join(run_id(SystemTime::now(), std::process::id()));
+    std::fs::create_dir(&run_path).wrap_err(eyre!(
+        "Unable to create the run folder {}",
+        run_path.display()
+    ))?;
+    log::debug!("Run in {}", run_path.display());
+    Ok(run_path)
+}
+
+/// Point the `latest` link of the mutation root to a finished run
+///
+/// The link is replaced in one step, commands reading the last run never see it missing.
+fn mark_latest(mutation_root: &Path, run_path: &Path) -> eyre::Result<()> {
+    let latest = mutation_root.join(LATEST);
+    let run_id = run_path
+        .file_name()
+        .ok_or(eyre!("No run ID in {}", run_path.display()))?;
+    // Folder left by a command run before any run
+    if latest.is_dir() && !latest.is_symlink() {
+        std::fs::remove_dir_all(&latest)?;
+    }
+    let link = mutation_root.join(format!("{LATEST}.{}", std::process::id()));
+    if link.is_symlink() {
+        std::fs::remove_file(&link)?;
+    }
+    #[cfg(unix)]
+    let linked = std::os::unix::fs::symlink(run_id, &link);
+    #[cfg(windows)]
+    let linked = std::os::windows::fs::symlink_dir(run_id, &link);
+    linked.wrap_err(eyre!("Unable to create symbolic link {}", link.display()))?;
+    // Links to folders aren't replaced by a rename on Windows
+    #[cfg(windows)]
+    if latest.is_symlink() {
+        std::fs::remove_dir(&latest)?;
+    }
+    std::fs::rename(&link, &latest).wrap_err(eyre!("Unable to update {}", latest.display()))
+}
+
+/// Whether a folder of the mutation root is the one of a run
+fn is_run_id(name: &str) -> bool {
+    let parts = name.split('-').collect::<Vec<_>>();
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    matches!(parts[..], [date, time, pid] if date.len() == 8 && time.len() == 6
+        && digits(date) && digits(time) && digits(pid))
+}
+
+/// Folder of a run in the mutation root
+#[derive(Debug)]
+struct Run {
+    id: String,
+    /// Last time its results were written, none if it never finished
+    finished: Option<SystemTime>,
+    /// Last time a file was added to its folder
+    modified: SystemTime,
+}
+
+/// IDs of the runs to remove so that only the `keep` last finished ones are left, the latest one
+/// always among them
+fn pruned(mut runs: Vec<Run>, latest: Option<&str>, keep: usize, now: SystemTime) -> Vec<String> {
+    runs.sort_by_key(|run| {
+        (
+            Some(run.id.as_str()) != latest,
+            std::cmp::Reverse(run.finished),
+        )
+    });
+    let mut kept = 0;
+    runs.into_iter()
+        .filter(|run| match run.finished {
+            Some(_) => {
+                kept += 1;
+                kept > keep
+            }
+            None => now
+                .duration_since(run.modified)
+                .is_ok_and(|idle| idle > ABANDONED_AFTER),
+        })
+        .map(|run| run.id)
+        .collect()
+}
+
+/// Remove the runs of the mutation root beyond the `keep` last finished ones, and the ones
+/// abandoned before finishing
+fn prune_runs(mutation_root: &Path, keep: usize) -> eyre::Result<()> {
+    let latest = std::fs::read_link(mutation_root.join(LATEST))
+        .ok()
+        .and_then(|target| Some(target.file_name()?.to_string_lossy().to_string()));
+    let mut runs = vec![];
+    for entry in std::fs::read_dir(mutation_root)? {
+        let entry = entry?;
+        let id = entry.file_name().to_string_lossy().to_string();
+        if !entry.file_type()?.is_dir() || !is_run_id(&id) {
+            continue;
+        }
+        runs.push(Run {
+            finished: std::fs::metadata(entry.path().join(RESULTS_FILE))
+                .and_then(|metadata| metadata.modified())
+                .ok(),
+            modified: entry.metadata()?.modified()?,
+            id,
+        });
+    }
+    for id in pruned(runs, latest.as_deref(), keep, SystemTime::now()) {
+        let path = mutation_root.join(id);
+        log::debug!("Remove run {}", path.display());
+        std::fs::remove_dir_all(&path)
+            .wrap_err(eyre!("Unable to remove run {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Make a run whose results are written the latest one, then remove the runs beyond the `keep`
+/// last finished ones
+pub(crate) fn finish_run(mutation_root: &Path, run_path: &Path, keep: usize) -> eyre::Result<()> {
+    mark_latest(mutation_root, run_path)?;
+    // The run itself went through, only disk space is lost
+    if let Err(error) = prune_runs(mutation_root, keep) {
+        log::warn!("Unable to remove previous runs: {error:#}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use crate::actions::runs::{is_run_id, pruned, run_id, Run};
+
+    #[test]
+    fn test_run_id() {
+        assert_eq!(run_id(UNIX_EPOCH, 42), "19700101-000000-42");
+        // 2024-02-29T23:59:59Z
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_251_199);
+        assert_eq!(run_id(leap_day, 7), "20240229-235959-7");
+        let later = UNIX_EPOCH + Duration::from_secs(1_709_251_200);
+        assert!(run_id(later, 1) > run_id(leap_day, 7));
+    }
+
+    #[test]
+    fn test_is_run_id() {
+        assert!(is_run_id("20261016-091502-4242"));
+        assert!(!is_run_id("cache"));
+        assert!(!is_run_id("latest.4242"));
+        assert!(!is_run_id("20261016-0915-4242"));
+    }
+
+    #[test]
+    fn test_pruned() {
+        let at = |hours: u64| UNIX_EPOCH + Duration::from_secs(hours * 60 * 60);
+        let run = |id: &str, finished: Option<u64>, modified: u64| Run {
+            id: id.to_string(),
+            finished: finished.map(at),
+            modified: at(modified),
+        };
+        let runs = || {
+            vec![
+                run("20261016-010000-1", Some(2), 2),
+                // Started later but finished first
+                run("20261016-020000-2", Some(1), 1),
+                run("20261016-030000-3", None, 3),
+                run("20261016-040000-4", None, 100),
+                run("20261016-050000-5", Some(102), 102),
+            ]
+        };
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(103 * 60 * 60);
+        let latest = Some("20261016-050000-5");
+        assert_eq!(
+            pruned(runs(), latest, 1, now),
+            vec![
+                "20261016-010000-1",
+                "20261016-020000-2",
+                "20261016-030000-3"
+            ]
+        );
+        assert_eq!(
+            pruned(runs(), latest, 2, now),
+            vec!["20261016-020000-2", "20261016-030000-3"]
+        );
+        // The latest run is kept even if another one finished after it
+        assert_eq!(
+            pruned(runs(), Some("20261016-020000-2"), 1, now),
+            vec![
+                "20261016-050000-5",
+                "20261016-010000-1",
+                "20261016-030000-3"
+            ]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use crate::actions::cache::content_hash;
+use crate::actions::reporting::results::RunResults;
+use crate::cli::Order;
+use crate::mutation::{Complexity, Mutation};
+use crate::report::MutationStatus;
+
+/// How likely a mutant is to survive, fields are compared in order
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Priority {
+    /// Complexity of the mutated function, in the complexity order
+    complexity: Complexity,
+    /// The mutant survived the previous run
+    survived: bool,
+    /// The file has changed since the previous run
+    file_changed: bool,
+    /// Mutants of the same function which survived the previous run
+    function_survivors: usize,
+    /// Per mille of the mutants of the file which survived the previous run
+    file_survival: usize,
+    /// Last modification of the file
+    modified: Option<SystemTime>,
+}
+
+/// State of a mutated file
+struct FileState {
+    changed: bool,
+    modified: Option<SystemTime>,
+}
+
+fn file_state(mutation: &Mutation, previous: &RunResults, file: &str) -> FileState {
+    let Ok(path) = mutation.get_file_path() else {
+        return FileState {
+            changed: false,
+            modified: None,
+        };
+    };
+    // Without hashes, nothing is known about the previous run
+    let changed = !previous.hashes.is_empty()
+        && std::fs::read_to_string(path)
+            .is_ok_and(|content| previous.hashes.get(file) != Some(&content_hash(&content)));
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    FileState { changed, modified }
+}
+
+/// Order mutants so that the ones most likely to survive are verified first
+///
+/// In the complexity order, mutants of the most complex functions come first. Then mutants which
+/// survived the previous run, then the ones of files changed since, then the ones of functions and
+/// files where mutants survived, then the ones of recently modified files. Ties keep the order of
+/// the mutants.
+pub(crate) fn schedule<'a>(
+    mutants: &'a mut [Mutation],
+    previous: &RunResults,
+    order: Order,
+) -> eyre::Result<Vec<&'a mut Mutation>> {
+    let mut function_survivors: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut file_mutants: HashMap<&str, (usize, usize)> = HashMap::new();
+    for result in &previous.mutants {
+        let survived = result.status == MutationStatus::Success;
+        let (total, survivors) = file_mutants.entry(&result.mutation.file).or_default();
+        *total += 1;
+        if survived {
+            *survivors += 1;
+            *function_survivors
+                .entry((&result.mutation.file, &result.mutation.function))
+                .or_default() += 1;
+        }
+    }
+
+    let mut files: HashMap<String, FileState> = HashMap::new();
+    let mut priorities = Vec::with_capacity(mutants.len());
+    for mutation in mutants.iter() {
+        let record = mutation.record()?;
+        let state = files
+            .entry(record.file.clone())
+            .or_insert_with(|| file_state(mutation, previous, &record.file));
+        priorities.push(Priority {
+            complexity: match order {
+                Order::Spread => Complexity::default(),
+                Order::Complexity => mutation.complexity,
+            },
+            survived: previous
+                .find(&record.stable_id)
+                .is_some_and(|result| result.status == MutationStatus::Success),
+            file_changed: state.changed,
+            function_survivors: function_survivors
+                .get(&(record.file.as_str(), record.function.as_str()))
+                .copied()
+                .unwrap_or_default(),
+            file_survival: file_mutants
+                .get(record.file.as_str())
+                .map(|(total, survivors)| survivors * 1000 / total)
+                .unwrap_or_default(),
+            modified: state.modified,
+        });
+    }
+
+    let mut queue = priorities
+        .into_iter()
+        .zip(mutants.iter_mut())
+        .collect::<Vec<_>>();
+    queue.sort_by(|(left, _), (right, _)| Reverse(left).cmp(&Reverse(right)));
+    for (priority, mutation) in &queue {
+        log::trace!(
+            "Mutation {} priority {priority:?}",
+            mutation.get_mutation_id()
+        );
+    }
+    Ok(queue.into_iter().map(|(_, mutation)| mutation).collect())
+}
+
+/// Group scheduled mutants by file, so that a worker verifies the mutants of a file one after the
+/// other and only the crate of the file is built again between them
+///
+/// Files come in the order of their first mutant, mutants of a file keep their order.
+pub(crate) fn batch_by_file(queue: Vec<&mut Mutation>) -> eyre::Result<Vec<Vec<&mut Mutation>>> {
+    let mut batches: Vec<Vec<&mut Mutation>> = vec![];
+    let mut files: HashMap<String, usize> = HashMap::new();
+    for mutation in queue {
+        let file = mutation.relative_file()?;
+        match files.get(&file) {
+            Some(&batch) => batches[batch].push(mutation),
+            None => {
+                files.insert(file, batches.len());
+                batches.push(vec![mutation]);
+            }
+        }
+    }
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::cache::content_hash;
+    use crate::actions::reporting::results::{MutantResult, RunResults, SUMMARY_V0};
+    use crate::actions::schedule::schedule;
+    use crate::cli::Order;
+    use crate::mutation::{Mutation, MutationChunk};
+    use crate::report::MutationStatus;
+    use crate::scratch::ScratchFolder;
+
+    static SOURCE: &str = "pub fn add(x: u32, y: u32) -> u32 {\n    x + y\n}\n";
+
+    fn mutant(project: &Path, file: &str, function: &str, replacement: &str) -> Mutation {
+        let start = SOURCE.find('+').unwrap();
+        let mut mutation = Mutation::new(
+            replacement,
+            MutationChunk::from_range(SOURCE, start..start + 1),
+        )
+        .with_function_name(function);
+        mutation.set_file_path(&project.join(file), Path::new(file));
+        mutation
+    }
+
+    #[test]
+    fn test_schedule_likely_survivors_first() {
+        let project = ScratchFolder::new("schedule");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src").join("changed.rs"), SOURCE).unwrap();
+        let mut mutants = [
+            mutant(&project, "src/lib.rs", "sub", "+"),
+            mutant(&project, "src/lib.rs", "add", "*"),
+            mutant(&project, "src/lib.rs", "add", "-"),
+            mutant(&project, "src/changed.rs", "add", "-"),
+            mutant(&project, "src/new.rs", "add", "-"),
+            mutant(&project, "src/new.rs", "add", "*"),
+        ];
+        for (id, mutation) in mutants.iter_mut().enumerate() {
+            mutation.set_mutation_id(id);
+        }
+
+        // Of the mutants of src/lib.rs, the `-` one of `add` survived the previous run
+        let mut previous: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        previous.mutants = [
+            (&mutants[1], MutationStatus::Fail),
+            (&mutants[2], MutationStatus::Success),
+            (&mutants[0], MutationStatus::CompilationFailed),
+        ]
+        .into_iter()
+        .map(|(mutation, status)| MutantResult {
+            mutation: mutation.record().unwrap(),
+            ..MutantResult::add_mutant(status, "", "")
+        })
+        .collect();
+        previous
+            .hashes
+            .insert("src/changed.rs".to_string(), content_hash("before"));
+
+        let ids = schedule(&mut mutants, &previous, Order::Spread)
+            .unwrap()
+            .into_iter()
+            .map(|mutation| mutation.get_mutation_id())
+            .collect::<Vec<_>>();
+        // The survivor, the mutant of the changed file, the one of the function with a survivor,
+        // the one of the file with a survivor, then the new ones in their order
+        assert_eq!(ids, vec![2, 3, 1, 0, 4, 5]);
+    }
+}
@@ -0,0 +1,365 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread::Scope;
+use std::time::Duration;
+
+use eyre::{eyre, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::actions::reporting::results::{RunResults, StatusCounts, SCHEMA_VERSION};
+use crate::actions::{generate, rerun};
+use crate::config::Settings;
+use crate::plugin::Plugin;
+
+/// Maximum size of a request body
+static MAX_BODY: usize = 64 * 1024;
+
+/// Time given to a client to send its request or read the response
+static IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Work done in the background
+enum Job {
+    Run,
+    Rerun { mutants: Vec<String>, missing: bool },
+}
+
+/// Body of a rerun request
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RerunRequest {
+    mutants: Vec<String>,
+    missing: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ServerState {
+    /// A run or rerun is in progress
+    running: bool,
+    /// Number of runs and reruns finished
+    finished: usize,
+    /// Error of the last run or rerun
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Status<'a> {
+    /// Version of the format of the results
+    schema_version: u32,
+    #[serde(flatten)]
+    state: &'a ServerState,
+    #[serde(flatten)]
+    counts: StatusCounts,
+}
+
+struct Response {
+    code: u16,
+    body: String,
+}
+
+impl Response {
+    fn json<T: Serialize>(value: &T) -> Self {
+        match serde_json::to_string_pretty(value) {
+            Ok(body) => Response { code: 200, body },
+            Err(error) => Response::error(500, &error.to_string()),
+        }
+    }
+
+    fn error(code: u16, message: &str) -> Self {
+        Response {
+            code,
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.code {
+            200 => "OK",
+            202 => "Accepted",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+/// Start a job in the background, unless another one is in progress
+fn start<'scope, 'env>(
+    scope: &'scope Scope<'scope, 'env>,
+    state: &'env Mutex<ServerState>,
+    settings: &'env Settings,
+    plugins: &'env [Plugin],
+    job: Job,
+) -> Response {
+    let Ok(mut current) = state.lock() else {
+        return Response::error(500, "Poisoned state");
+    };
+    if current.running {
+        return Response::error(409, "A run is already in progress");
+    }
+    current.running = true;
+
+    scope.spawn(move || {
+        let result = match job {
+            Job::Run => generate::verify_project(settings, plugins).map(|_| ()),
+            Job::Rerun { mutants, missing } => rerun::rerun(&mutants, missing, settings),
+        };
+        if let Ok(mut current) = state.lock() {
+            current.running = false;
+            current.finished += 1;
+            current.error = result.err().map(|error| format!("{error:#}"));
+        }
+    });
+
+    Response {
+        code: 202,
+        body: serde_json::json!({ "started": true }).to_string(),
+    }
+}
+
+fn status(state: &Mutex<ServerState>, settings: &Settings) -> Response {
+    let Ok(state) = state.lock() else {
+        return Response::error(500, "Poisoned state");
+    };
+    let results = RunResults::load(&settings.mutation_path).unwrap_or_default();
+    Response::json(&Status {
+        schema_version: SCHEMA_VERSION,
+        state: &state,
+        counts: results.counts(),
+    })
+}
+
+/// Read the method, the path and the body of a request
+fn read_request(stream: &TcpStream) -> eyre::Result<(String, String, String)> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(eyre!("Malformed request line {request_line:?}"));
+    };
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            }
+        }
+    }
+    if content_length > MAX_BODY {
+        return Err(eyre!("Request body too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok((
+        method.to_string(),
+        path.to_string(),
+        String::from_utf8(body)?,
+    ))
+}
+
+fn route<'scope, 'env>(
+    method: &str,
+    path: &str,
+    body: &str,
+    scope: &'scope Scope<'scope, 'env>,
+    state: &'env Mutex<ServerState>,
+    settings: &'env Settings,
+    plugins: &'env [Plugin],
+) -> Response {
+    let segments = path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    match (method, segments.as_slice()) {
+        ("GET", ["status"]) => status(state, settings),
+        ("GET", ["mutants"]) => match RunResults::load(&settings.mutation_path) {
+            Ok(results) => Response::json(&results.mutants),
+            Err(error) => Response::error(404, &format!("{error:#}")),
+        },
+        ("GET", ["mutants", id]) => match RunResults::load(&settings.mutation_path) {
+            Ok(results) => match results.find(id) {
+                Some(result) => Response::json(result),
+                None => Response::error(404, &format!("No mutant {id} in the last run")),
+            },
+            Err(error) => Response::error(404, &format!("{error:#}")),
+        },
+        ("POST", ["run"]) => start(scope, state, settings, plugins, Job::Run),
+        ("POST", ["rerun"]) => match serde_json::from_str::<RerunRequest>(body) {
+            Ok(request) if request.missing || !request.mutants.is_empty() => start(
+                scope,
+                state,
+                settings,
+                plugins,
+                Job::Rerun {
+                    mutants: request.mutants,
+                    missing: request.missing,
+                },
+            ),
+            Ok(_) => Response::error(400, "Either mutants or missing must be defined"),
+            Err(error) => Response::error(400, &error.to_string()),
+        },
+        (_, ["status"] | ["mutants", ..] | ["run"] | ["rerun"]) => {
+            Response::error(405, &format!("Method {method} not allowed on {path}"))
+        }
+        _ => Response::error(404, &format!("Unknown endpoint {path}")),
+    }
+}
+
+/// Answer a request, on its own thread so that a slow client doesn't hold the others
+fn answer<'scope, 'env>(
+    mut stream: TcpStream,
+    scope: &'scope Scope<'scope, 'env>,
+    state: &'env Mutex<ServerState>,
+    settings: &'env Settings,
+    plugins: &'env [Plugin],
+) {
+    let timeouts = stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)));
+    if let Err(error) = timeouts {
+        log::warn!("Unable to set the timeouts of a connection: {error}");
+        return;
+    }
+    let response = match read_request(&stream) {
+        Ok((method, path, body)) => {
+            log::debug!("{method} {path}");
+            route(&method, &path, &body, scope, state, settings, plugins)
+        }
+        Err(error) => Response::error(400, &format!("{error:#}")),
+    };
+    let written = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.code,
+        response.reason(),
+        response.body.len(),
+        response.body
+    );
+    if let Err(error) = written {
+        log::warn!("Unable to answer: {error}");
+    }
+}
+
+/// Verify the project in the background and expose its results over HTTP, until interrupted
+pub(crate) fn serve(settings: &Settings, plugins: &[Plugin], address: &str) -> eyre::Result<()> {
+    let listener = TcpListener::bind(address).wrap_err(eyre!("Unable to listen on {address}"))?;
+    println!("Listening on http://{}", listener.local_addr()?);
+
+    let state = Mutex::new(ServerState::default());
+    std::thread::scope(|scope| {
+        start(scope, &state, settings, plugins, Job::Run);
+
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    log::warn!("Connection failed: {error}");
+                    continue;
+                }
+            };
+            let state = &state;
+            scope.spawn(move || answer(stream, scope, state, settings, plugins));
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::Mutex;
+
+    use clap::Parser;
+
+    use crate::actions::reporting::results::{
+        MutantResult, RunResults, SCHEMA_VERSION, SUMMARY_V0,
+    };
+    use crate::actions::serve::{answer, ServerState};
+    use crate::cli::{Cli, VerifyArgs};
+    use crate::config::Settings;
+    use crate::report::MutationStatus;
+    use crate::scratch::ScratchFolder;
+
+    /// Send a request to a server answering a single connection, its status line and JSON body
+    fn request(settings: &Settings, request: &str) -> (String, serde_json::Value) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let state = Mutex::new(ServerState::default());
+        let response = std::thread::scope(|scope| {
+            let client = scope.spawn(move || {
+                let mut stream = TcpStream::connect(address).unwrap();
+                stream.write_all(request.as_bytes()).unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                response
+            });
+            let (stream, _) = listener.accept().unwrap();
+            answer(stream, scope, &state, settings, &[]);
+            client.join().unwrap()
+        });
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status_line = head.lines().next().unwrap().to_string();
+        (status_line, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn test_status_route() {
+        let project = ScratchFolder::new("serve");
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"served\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src").join("lib.rs"), "").unwrap();
+        let args = ["cargo", "darwin", "--mutation-path"]
+            .map(OsString::from)
+            .into_iter()
+            .chain([project.join("tmp").into(), project.as_os_str().into()]);
+        let Cli::Darwin(darwin) = Cli::try_parse_from(args).unwrap();
+        let settings = Settings::new(darwin.run.project, VerifyArgs::default()).unwrap();
+        std::fs::create_dir_all(&settings.mutation_path).unwrap();
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        results.mutants = vec![
+            MutantResult::add_mutant(MutationStatus::Success, "-", "sub"),
+            MutantResult::add_mutant(MutationStatus::Fail, "*", "mul"),
+        ];
+        results.save(&settings.mutation_path).unwrap();
+
+        let (status_line, body) =
+            request(&settings, "GET /status HTTP/1.1\r\nHost: darwin\r\n\r\n");
+        assert_eq!(status_line, "HTTP/1.1 200 OK");
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "schema_version": SCHEMA_VERSION,
+                "running": false,
+                "finished": 0,
+                "error": null,
+                "mutants": 2,
+                "missing": 1,
+                "ok": 1,
+                "timeout": 0,
+                "killed": 0
+            })
+        );
+
+        let (status_line, body) = request(&settings, "POST /status HTTP/1.1\r\n\r\n");
+        assert_eq!(status_line, "HTTP/1.1 405 Method Not Allowed");
+        assert_eq!(body["error"], "Method POST not allowed on /status");
+    }
+}
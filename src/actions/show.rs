@@ -0,0 +1,146 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use eyre::eyre;
+
+use crate::actions::reporting::results::{MutantResult, RunResults};
+
+/// Details of a persisted mutant
+fn details(mutation_root: &Path, result: &MutantResult) -> eyre::Result<String> {
+    let mut details = String::new();
+    let MutantResult {
+        mutation,
+        status,
+        caught_by,
+        errors,
+        execution,
+        duration,
+        build_duration,
+        warnings,
+        diagnostics,
+        timeout,
+        log,
+        diff,
+    } = result;
+
+    writeln!(details, "Mutation #{} {}", mutation.id, mutation.stable_id)?;
+    writeln!(
+        details,
+        "{} in function \"{}\" of file {} at line {}:{}",
+        mutation.reason, mutation.function, mutation.file, mutation.line, mutation.column
+    )?;
+    writeln!(
+        details,
+        "Span: {}:{} to {}:{}, bytes {}..{}",
+        mutation.line,
+        mutation.column,
+        mutation.end_line,
+        mutation.end_column,
+        mutation.start,
+        mutation.end
+    )?;
+    writeln!(details, "Original: {}", mutation.original)?;
+    writeln!(details, "Replacement: {}", mutation.replacement)?;
+    writeln!(details, "Status: {}", status.name())?;
+    if caught_by.is_empty() {
+        writeln!(details, "Caught by: -")?;
+    } else {
+        writeln!(details, "Caught by:")?;
+        for test in caught_by {
+            writeln!(details, "  - {test}")?;
+        }
+    }
+    if !errors.is_empty() {
+        writeln!(details, "Errors: {}", errors.join(", "))?;
+    }
+    if !diagnostics.is_empty() {
+        writeln!(details, "Diagnostics:")?;
+        for diagnostic in diagnostics {
+            writeln!(details, "  - {}", diagnostic.simple())?;
+        }
+    }
+    if let Some(build_duration) = build_duration {
+        writeln!(details, "Build duration: {build_duration:.2}s")?;
+    }
+    if let Some(duration) = duration {
+        writeln!(details, "Tests duration: {duration:.2}s")?;
+    }
+    if let Some(timeout) = timeout {
+        writeln!(details, "Timeout: {}", timeout.hint(*status))?;
+    }
+    if let Some(execution) = execution {
+        writeln!(details, "Coverage: {}", execution.hint())?;
+    }
+    if !warnings.is_empty() {
+        writeln!(details, "New warnings:")?;
+        for warning in warnings {
+            writeln!(details, "  - {warning}")?;
+        }
+    }
+    writeln!(details, "Log: {}", mutation_root.join(log).display())?;
+    writeln!(details, "Mutation diff:\n{diff}")?;
+
+    Ok(details)
+}
+
+/// Print the details of a persisted mutant
+pub(crate) fn show(mutation_root: &Path, id: &str) -> eyre::Result<()> {
+    let results = RunResults::load(mutation_root)?;
+    let result = results
+        .find(id)
+        .ok_or(eyre!("No mutant {id} in the last run"))?;
+    print!("{}", details(mutation_root, result)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::results::{MutantResult, RunResults, SUMMARY_V0};
+    use crate::actions::show::{details, show};
+    use crate::report::MutationStatus;
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_show_persisted_mutant() {
+        let folder = ScratchFolder::new("show");
+        let mut results: RunResults = serde_json::from_str(SUMMARY_V0).unwrap();
+        let mut caught = MutantResult::add_mutant(MutationStatus::Fail, "*", "mul");
+        caught.mutation.id = 1;
+        caught.caught_by = vec!["tests::test_add".to_string()];
+        caught.duration = Some(0.5);
+        caught.diff = "-    x + y\n+    x * y\n".to_string();
+        results.mutants.push(caught);
+        results.save(&folder).unwrap();
+
+        let results = RunResults::load(&folder).unwrap();
+        let result = results.find("src-lib-add-2-7-mul").unwrap();
+        assert_eq!(
+            details(&folder, result).unwrap(),
+            format!(
+                "\
+Mutation #1 src-lib-add-2-7-mul
+replace + by * in function \"add\" of file src/lib.rs at line 2:7
+Span: 2:7 to 2:8, bytes 35..36
+Original: +
+Replacement: *
+Status: ok
+Caught by:
+  - tests::test_add
+Tests duration: 0.50s
+Log: {}
+Mutation diff:
+-    x + y
++    x * y
+
+",
+                folder.join("reports/mutation_0.log").display()
+            )
+        );
+
+        let error = show(&folder, "src-lib-add-2-7-div").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No mutant src-lib-add-2-7-div in the last run"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use eyre::eyre;
+
+use crate::actions::reporting::results::{MutantResult, RunResults};
+use crate::actions::runs::VERIFY;
+use crate::actions::{analyze, generate};
+use crate::cli::SourcePosition;
+use crate::config::Settings;
+use crate::mutation::Mutation;
+use crate::plugin::Plugin;
+
+/// Mutants whose span holds a 1-based line and column, the ones of the innermost span
+fn mutants_at(mutants: Vec<Mutation>, line: usize, column: usize) -> Vec<Mutation> {
+    let position = (line - 1, column - 1);
+    let mut mutants = mutants
+        .into_iter()
+        .filter(|mutation| {
+            let start = (
+                mutation.chunk.start_point.row,
+                mutation.chunk.start_point.column,
+            );
+            let end = (
+                mutation.chunk.end_point.row,
+                mutation.chunk.end_point.column,
+            );
+            start <= position && (position < end || position == start)
+        })
+        .collect::<Vec<_>>();
+    if let Some(innermost) = mutants
+        .iter()
+        .map(|mutation| mutation.get_range().len())
+        .min()
+    {
+        mutants.retain(|mutation| mutation.get_range().len() == innermost);
+    }
+    mutants
+}
+
+/// Verify the single mutant at a position of a file, returns its result
+///
+/// Mutants of the position are narrowed down by the operators of the settings and the replacement,
+/// exactly one must be left. The mutant is verified in the `verify` scratch folder of the mutation
+/// root, whose target folder is kept between calls. The last run and its `latest` link are left
+/// untouched.
+pub(crate) fn verify_single(
+    position: &SourcePosition,
+    replacement: Option<&str>,
+    settings: &Settings,
+    plugins: &[Plugin],
+) -> eyre::Result<MutantResult> {
+    let path = settings.root_path.join(&position.file);
+    if !path.is_file() {
+        return Err(eyre!("No file {} in the project", position.file.display()));
+    }
+    let mutants = analyze::get_mutations_for_file(&path, settings, plugins)?;
+    let mut mutants = mutants_at(mutants, position.line, position.column);
+    if let Some(replacement) = replacement {
+        mutants.retain(|mutation| mutation.get_replacement() == replacement);
+    }
+    match mutants.len() {
+        0 => return Err(eyre!("No mutant at {position}")),
+        1 => {}
+        _ => {
+            let replacements = mutants
+                .iter()
+                .map(|mutation| format!("`{}` ({})", mutation.get_replacement(), mutation.operator))
+                .collect::<Vec<_>>();
+            return Err(eyre!(
+                "{} mutants at {position}, choose one with --replacement: {}",
+                mutants.len(),
+                replacements.join(", ")
+            ));
+        }
+    }
+
+    let mut settings = settings.clone();
+    settings.mutation_path = settings.mutation_root.join(VERIFY);
+    settings.keep_targets = true;
+    // Verdict of the previous call
+    let summary = settings.mutation_path.join("summary");
+    if summary.exists() {
+        std::fs::remove_file(&summary)?;
+    }
+    generate::generate_and_verify_mutants(&mut mutants, &settings, &RunResults::default())?;
+    RunResults::from_mutations(&mutants)?
+        .mutants
+        .pop()
+        .ok_or(eyre!("No verdict for the mutant at {position}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::single::mutants_at;
+    use crate::mutation::{Mutation, MutationChunk};
+
+    #[test]
+    fn test_mutants_at() {
+        let content = "fn a(x: u8) -> u8 {\n    x + 2 * x\n}\n";
+        let mutant = |code: &str, replacement: &str| {
+            let start = content.find(code).unwrap();
+            Mutation::new(
+                replacement,
+                MutationChunk::from_range(content, start..start + code.len()),
+            )
+        };
+        let mutants = || {
+            vec![
+                mutant("x + 2 * x", "0"),
+                mutant("+", "-"),
+                mutant("*", "+"),
+                mutant("*", "/"),
+            ]
+        };
+        let replacements = |line, column| {
+            mutants_at(mutants(), line, column)
+                .iter()
+                .map(|mutation| mutation.get_replacement().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(replacements(2, 7), vec!["-"]);
+        assert_eq!(replacements(2, 11), vec!["+", "/"]);
+        assert_eq!(replacements(2, 5), vec!["0"]);
+        assert!(replacements(1, 1).is_empty());
+    }
+}
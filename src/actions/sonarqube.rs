@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::actions::reporting::results::{MutantResult, RunResults};
+use crate::report::MutationStatus;
+
+static ENGINE_ID: &str = "cargo-darwin";
+static RULE_ID: &str = "surviving-mutant";
+
+/// Report of the generic issue import format of SonarQube
+#[derive(Debug, Serialize)]
+struct Report {
+    rules: Vec<Rule>,
+    issues: Vec<Issue>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    engine_id: &'static str,
+    clean_code_attribute: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    severity: &'static str,
+    impacts: Vec<Impact>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Impact {
+    software_quality: &'static str,
+    severity: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Issue {
+    rule_id: &'static str,
+    primary_location: Location,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    message: String,
+    /// Relative to the project
+    file_path: String,
+    text_range: TextRange,
+}
+
+/// 1-based lines, 0-based columns with an exclusive end
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TextRange {
+    start_line: usize,
+    end_line: usize,
+    start_column: usize,
+    end_column: usize,
+}
+
+fn rule() -> Rule {
+    Rule {
+        id: RULE_ID,
+        name: "Surviving mutant",
+        description: "The code was mutated and the tests still pass: no test checks the behavior \
+                      of this code",
+        engine_id: ENGINE_ID,
+        clean_code_attribute: "TESTED",
+        kind: "CODE_SMELL",
+        severity: "MAJOR",
+        impacts: vec![Impact {
+            software_quality: "MAINTAINABILITY",
+            severity: "MEDIUM",
+        }],
+    }
+}
+
+fn issue(result: &MutantResult) -> Issue {
+    let mutation = &result.mutation;
+    let mut message = format!(
+        "Mutant survived, {} in function \"{}\" (mutant {})",
+        mutation.reason, mutation.function, mutation.stable_id
+    );
+    if let Some(execution) = &result.execution {
+        message.push_str(&format!(", {}", execution.hint()));
+    }
+    Issue {
+        rule_id: RULE_ID,
+        primary_location: Location {
+            message,
+            file_path: mutation.file.clone(),
+            text_range: TextRange {
+                start_line: mutation.line,
+                end_line: mutation.end_line.max(mutation.line),
+                start_column: mutation.column - 1,
+                end_column: mutation.end_column.saturating_sub(1),
+            },
+        },
+    }
+}
+
+/// Issues of the surviving mutants of the results
+fn report(results: &RunResults) -> Report {
+    Report {
+        rules: vec![rule()],
+        issues: results
+            .mutants
+            .iter()
+            .filter(|result| result.status == MutationStatus::Success)
+            .map(issue)
+            .collect(),
+    }
+}
+
+/// Write the surviving mutants of the last run as a generic issue report of SonarQube,
+/// `issues.json`, to import with `sonar.externalIssuesReportPaths`
+pub(crate) fn export(mutation_root: &Path, output: &Path) -> eyre::Result<()> {
+    let results = RunResults::load(mutation_root)?;
+    let report = report(&results);
+    std::fs::create_dir_all(output)?;
+    let path = output.join("issues.json");
+    std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+    println!(
+        "{} surviving mutants exported to {}",
+        report.issues.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::results::{MutantResult, RunResults};
+    use crate::actions::sonarqube::report;
+    use crate::report::MutationStatus;
+
+    #[test]
+    fn test_sonarqube_report() {
+        let results = RunResults {
+            mutants: vec![
+                MutantResult::add_mutant(MutationStatus::Fail, "-", "sub"),
+                MutantResult::add_mutant(MutationStatus::Success, "*", "mul"),
+            ],
+            ..Default::default()
+        };
+        let report = serde_json::to_value(report(&results)).unwrap();
+        assert_eq!(report["rules"][0]["id"], "surviving-mutant");
+        assert_eq!(report["rules"][0]["engineId"], "cargo-darwin");
+        assert_eq!(
+            report["issues"],
+            serde_json::json!([{
+                "ruleId": "surviving-mutant",
+                "primaryLocation": {
+                    "message": "Mutant survived, replace + by * in function \"add\" (mutant src-lib-add-2-7-mul)",
+                    "filePath": "src/lib.rs",
+                    "textRange": {"startLine": 2, "endLine": 2, "startColumn": 6, "endColumn": 7}
+                }
+            }])
+        );
+    }
+}
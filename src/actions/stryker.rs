@@ -0,0 +1,343 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use eyre::{eyre, WrapErr};
+use serde::Serialize;
+
+use crate::actions::cache::content_hash;
+use crate::actions::reporting::results::{MutantResult, RunResults};
+use crate::report::MutationStatus;
+
+/// Version of the mutation-testing-report-schema written
+static SCHEMA_VERSION: &str = "2";
+/// Script of the Mutation Testing Elements web component rendering the report
+static ELEMENTS_SCRIPT: &str = "https://www.unpkg.com/mutation-testing-elements";
+
+/// Report following the mutation-testing-report-schema of Stryker
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    schema_version: &'static str,
+    /// Scores from which the report shows green and red, Stryker's defaults
+    thresholds: Thresholds,
+    project_root: String,
+    /// Files by path relative to the project
+    files: BTreeMap<String, FileResult>,
+    /// Tests killing or covering mutants, under an empty path as their files aren't known
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    test_files: BTreeMap<String, TestFile>,
+    framework: Framework,
+}
+
+#[derive(Debug, Serialize)]
+struct Thresholds {
+    high: u8,
+    low: u8,
+}
+
+#[derive(Debug, Serialize)]
+struct Framework {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct FileResult {
+    language: &'static str,
+    source: String,
+    mutants: Vec<MutantReport>,
+}
+
+#[derive(Debug, Serialize)]
+struct TestFile {
+    tests: Vec<TestDefinition>,
+}
+
+/// Test referred to by `killedBy` and `coveredBy` of the mutants, named after its ID
+#[derive(Debug, Serialize)]
+struct TestDefinition {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MutantReport {
+    id: String,
+    mutator_name: String,
+    replacement: String,
+    description: String,
+    location: Location,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status_reason: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    killed_by: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    covered_by: Vec<String>,
+    /// Duration of the tests in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+    start: Position,
+    end: Position,
+}
+
+/// 1-based line and column
+#[derive(Debug, Serialize)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+/// Status of the schema: survivors never executed by tests have no coverage, killed mutants are
+/// compile errors
+fn status(result: &MutantResult) -> &'static str {
+    match result.status {
+        MutationStatus::Success
+            if result
+                .execution
+                .as_ref()
+                .is_some_and(|execution| !execution.executed) =>
+        {
+            "NoCoverage"
+        }
+        MutationStatus::Success => "Survived",
+        MutationStatus::Fail => "Killed",
+        MutationStatus::Timeout => "Timeout",
+        MutationStatus::CompilationFailed => "CompileError",
+    }
+}
+
+fn mutant_report(result: &MutantResult) -> MutantReport {
+    let mutation = &result.mutation;
+    let status_reason = match result.status {
+        MutationStatus::CompilationFailed if !result.errors.is_empty() => {
+            Some(result.errors.join(", "))
+        }
+        _ => result.execution.as_ref().map(|execution| execution.hint()),
+    };
+    MutantReport {
+        id: mutation.stable_id.clone(),
+        mutator_name: mutation.operator.clone(),
+        replacement: mutation.replacement.clone(),
+        description: mutation.reason.clone(),
+        location: Location {
+            start: Position {
+                line: mutation.line,
+                column: mutation.column,
+            },
+            end: Position {
+                line: mutation.end_line,
+                column: mutation.end_column,
+            },
+        },
+        status: status(result),
+        status_reason,
+        killed_by: result.caught_by.clone(),
+        covered_by: result
+            .execution
+            .iter()
+            .flat_map(|execution| execution.tests.clone())
+            .collect(),
+        duration: result
+            .duration
+            .map(|duration| (duration * 1000.0).round() as u64),
+    }
+}
+
+/// Report of the results, with the sources of the mutated files
+fn report(results: &RunResults, sources: BTreeMap<String, String>, project_root: &Path) -> Report {
+    let mut files: BTreeMap<String, FileResult> = sources
+        .into_iter()
+        .map(|(file, source)| {
+            let result = FileResult {
+                language: "rust",
+                source,
+                mutants: vec![],
+            };
+            (file, result)
+        })
+        .collect();
+    let mut tests = BTreeSet::new();
+    for result in &results.mutants {
+        if let Some(file) = files.get_mut(&result.mutation.file) {
+            let mutant = mutant_report(result);
+            tests.extend(mutant.killed_by.iter().chain(&mutant.covered_by).cloned());
+            file.mutants.push(mutant);
+        }
+    }
+    let mut test_files = BTreeMap::new();
+    if !tests.is_empty() {
+        let tests = tests
+            .into_iter()
+            .map(|test| TestDefinition {
+                id: test.clone(),
+                name: test,
+            })
+            .collect();
+        test_files.insert("".to_string(), TestFile { tests });
+    }
+    Report {
+        schema_version: SCHEMA_VERSION,
+        thresholds: Thresholds { high: 80, low: 60 },
+        project_root: project_root.display().to_string(),
+        files,
+        test_files,
+        framework: Framework {
+            name: "cargo-darwin",
+            version: env!("CARGO_PKG_VERSION"),
+        },
+    }
+}
+
+/// Page rendering a report with the Mutation Testing Elements web component
+fn html_page(json: &str) -> String {
+    // The report must not close the script embedding it
+    let json = json.replace("</", "<\\/");
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>Mutation report</title>
+  <script defer src="{ELEMENTS_SCRIPT}"></script>
+</head>
+<body>
+  <mutation-test-report-app title-postfix="cargo-darwin"></mutation-test-report-app>
+  <script>
+    document.querySelector("mutation-test-report-app").report = {json};
+  </script>
+</body>
+</html>
+"#
+    )
+}
+
+/// Write the results of the last run as a Stryker report, `mutation.json` and `mutation.html`
+///
+/// The report embeds the sources of the mutated files, files changed since the run are reported
+/// with their current content, the locations of their mutants may be off.
+pub(crate) fn export(project_path: &Path, mutation_root: &Path, output: &Path) -> eyre::Result<()> {
+    let results = RunResults::load(mutation_root)?;
+    let mut sources = BTreeMap::new();
+    for result in &results.mutants {
+        let file = &result.mutation.file;
+        if sources.contains_key(file) {
+            continue;
+        }
+        let path = project_path.join(file);
+        let source =
+            std::fs::read_to_string(&path).wrap_err(eyre!("Unable to read {}", path.display()))?;
+        if results
+            .hashes
+            .get(file)
+            .is_some_and(|hash| *hash != content_hash(&source))
+        {
+            println!("{file} changed since the last run, the locations of its mutants may be off");
+        }
+        sources.insert(file.clone(), source);
+    }
+
+    let report = report(&results, sources, &std::fs::canonicalize(project_path)?);
+    let json = serde_json::to_string(&report)?;
+    std::fs::create_dir_all(output)?;
+    std::fs::write(output.join("mutation.json"), &json)?;
+    std::fs::write(output.join("mutation.html"), html_page(&json))?;
+    println!(
+        "{} mutants exported to {}",
+        results.mutants.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use crate::actions::reporting::results::{Execution, MutantResult, RunResults};
+    use crate::actions::stryker::{html_page, report};
+    use crate::report::MutationStatus;
+
+    #[test]
+    fn test_stryker_report() {
+        let result = |status, replacement: &str, name: &str, execution| MutantResult {
+            caught_by: match status {
+                MutationStatus::Fail => vec!["tests::test_add".to_string()],
+                _ => vec![],
+            },
+            execution,
+            duration: Some(0.25),
+            ..MutantResult::add_mutant(status, replacement, name)
+        };
+        let results = RunResults {
+            mutants: vec![
+                result(MutationStatus::Fail, "-", "sub", None),
+                result(
+                    MutationStatus::Success,
+                    "*",
+                    "mul",
+                    Some(Execution {
+                        executed: false,
+                        tests: vec![],
+                    }),
+                ),
+                result(
+                    MutationStatus::Success,
+                    "/",
+                    "div",
+                    Some(Execution {
+                        executed: true,
+                        tests: vec!["tests::test_add".to_string(), "tests::test_sum".to_string()],
+                    }),
+                ),
+            ],
+            ..Default::default()
+        };
+        let source = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let sources = BTreeMap::from([("src/lib.rs".to_string(), source.to_string())]);
+        let report =
+            serde_json::to_value(report(&results, sources, Path::new("/project"))).unwrap();
+
+        assert_eq!(report["schemaVersion"], "2");
+        let file = &report["files"]["src/lib.rs"];
+        assert_eq!(file["source"], source);
+        assert_eq!(
+            file["mutants"][0],
+            serde_json::json!({
+                "id": "src-lib-add-2-7-sub",
+                "mutatorName": "arithmetic",
+                "replacement": "-",
+                "description": "replace + by -",
+                "location": {"start": {"line": 2, "column": 7}, "end": {"line": 2, "column": 8}},
+                "status": "Killed",
+                "killedBy": ["tests::test_add"],
+                "duration": 250
+            })
+        );
+        assert_eq!(file["mutants"][1]["status"], "NoCoverage");
+        assert_eq!(
+            file["mutants"][1]["statusReason"],
+            "never executed by tests"
+        );
+
+        assert_eq!(
+            file["mutants"][2]["coveredBy"],
+            serde_json::json!(["tests::test_add", "tests::test_sum"])
+        );
+        assert_eq!(
+            report["testFiles"],
+            serde_json::json!({"": {"tests": [
+                {"id": "tests::test_add", "name": "tests::test_add"},
+                {"id": "tests::test_sum", "name": "tests::test_sum"}
+            ]}})
+        );
+
+        assert!(html_page(r#"{"source":"</script>"}"#).contains(r#"{"source":"<\/script>"}"#));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+/// Primitive numeric types
+static NUMERIC_TYPES: [&str; 14] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32",
+    "f64",
+];
+/// Methods returning a number whatever their receiver
+static NUMERIC_METHODS: [&str; 8] = [
+    "len",
+    "count",
+    "abs",
+    "pow",
+    "signum",
+    "count_ones",
+    "leading_zeros",
+    "trailing_zeros",
+];
+/// Methods returning a `String` whatever their receiver
+static TEXT_METHODS: [&str; 3] = ["to_string", "to_uppercase", "to_lowercase"];
+
+/// Type of an operand, as far as syntax tells
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OperandType {
+    Numeric,
+    /// `String` or `&str`
+    Text,
+    Unknown,
+}
+
+fn type_of_name(name: &str) -> OperandType {
+    if NUMERIC_TYPES.contains(&name) {
+        OperandType::Numeric
+    } else if name == "String" || name == "str" {
+        OperandType::Text
+    } else {
+        OperandType::Unknown
+    }
+}
+
+fn type_of_syn(ty: &syn::Type) -> OperandType {
+    match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .map(|segment| type_of_name(&segment.ident.to_string()))
+            .unwrap_or(OperandType::Unknown),
+        syn::Type::Reference(reference) => type_of_syn(&reference.elem),
+        syn::Type::Paren(paren) => type_of_syn(&paren.elem),
+        _ => OperandType::Unknown,
+    }
+}
+
+/// Heuristics guessing the type of expressions of a function without type checking
+///
+/// Only literals, casts, a few well known methods and the parameters of the function are known,
+/// anything else is `Unknown`.
+#[derive(Debug, Default)]
+pub(crate) struct TypeHints {
+    parameters: HashMap<String, OperandType>,
+}
+
+impl TypeHints {
+    pub(crate) fn from_signature(signature: &syn::Signature) -> Self {
+        let parameters = signature
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                syn::FnArg::Typed(typed) => match &*typed.pat {
+                    syn::Pat::Ident(ident) => {
+                        Some((ident.ident.to_string(), type_of_syn(&typed.ty)))
+                    }
+                    _ => None,
+                },
+                syn::FnArg::Receiver(_) => None,
+            })
+            .collect();
+        TypeHints { parameters }
+    }
+
+    /// Type of the expression of the node
+    pub(crate) fn operand_type(&self, node: tree_sitter::Node, content: &str) -> OperandType {
+        let text = |node: tree_sitter::Node| &content[node.start_byte()..node.end_byte()];
+        match node.kind() {
+            "integer_literal" | "float_literal" => OperandType::Numeric,
+            "string_literal" | "raw_string_literal" => OperandType::Text,
+            "identifier" => self
+                .parameters
+                .get(text(node))
+                .copied()
+                .unwrap_or(OperandType::Unknown),
+            "type_cast_expression" => node
+                .child_by_field_name("type")
+                .map(|ty| type_of_name(text(ty)))
+                .unwrap_or(OperandType::Unknown),
+            "call_expression" => {
+                let method = node
+                    .child_by_field_name("function")
+                    .filter(|function| function.kind() == "field_expression")
+                    .and_then(|function| function.child_by_field_name("field"))
+                    .map(text)
+                    .unwrap_or_default();
+                if NUMERIC_METHODS.contains(&method) {
+                    OperandType::Numeric
+                } else if TEXT_METHODS.contains(&method) {
+                    OperandType::Text
+                } else {
+                    OperandType::Unknown
+                }
+            }
+            "macro_invocation" => match node.child_by_field_name("macro").map(text) {
+                Some("format") => OperandType::Text,
+                _ => OperandType::Unknown,
+            },
+            "parenthesized_expression" | "unary_expression" => node
+                .named_child(0)
+                .map(|inner| self.operand_type(inner, content))
+                .unwrap_or(OperandType::Unknown),
+            "binary_expression" => {
+                let operator = node.child_by_field_name("operator").map(|op| op.kind());
+                if !matches!(operator, Some("+" | "-" | "*" | "/" | "%")) {
+                    return OperandType::Unknown;
+                }
+                self.binary_operands_type(node, content)
+            }
+            _ => OperandType::Unknown,
+        }
+    }
+
+    /// Type of the operands of a binary expression, known if either operand is known
+    pub(crate) fn binary_operands_type(
+        &self,
+        node: tree_sitter::Node,
+        content: &str,
+    ) -> OperandType {
+        let operand = |field| {
+            node.child_by_field_name(field)
+                .map(|operand| self.operand_type(operand, content))
+                .unwrap_or(OperandType::Unknown)
+        };
+        match (operand("left"), operand("right")) {
+            (OperandType::Unknown, other) => other,
+            (known, _) => known,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::type_hints::{OperandType, TypeHints};
+
+    fn binary_type(source: &str) -> OperandType {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(tree_sitter_rust::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let item_fn: syn::ItemFn = syn::parse_str(source).unwrap();
+        let hints = TypeHints::from_signature(&item_fn.sig);
+
+        let mut nodes = vec![tree.root_node()];
+        while let Some(node) = nodes.pop() {
+            if node.kind() == "binary_expression" {
+                return hints.binary_operands_type(node, source);
+            }
+            let mut cursor = node.walk();
+            nodes.extend(node.children(&mut cursor));
+        }
+        panic!("No binary expression in {source}");
+    }
+
+    #[test]
+    fn test_operand_type() {
+        assert_eq!(
+            binary_type("fn f(x: u8, y: u8) -> u8 { x + y }"),
+            OperandType::Numeric
+        );
+        assert_eq!(
+            binary_type("fn f(x: &[u8]) -> usize { x.len() - 1 }"),
+            OperandType::Numeric
+        );
+        assert_eq!(
+            binary_type("fn f(x: T) -> f64 { (x as f64) - 1.0 }"),
+            OperandType::Numeric
+        );
+        assert_eq!(
+            binary_type("fn f(name: String) -> String { name + \"!\" }"),
+            OperandType::Text
+        );
+        assert_eq!(
+            binary_type("fn f(x: T, y: T) -> T { x + y }"),
+            OperandType::Unknown
+        );
+    }
+}
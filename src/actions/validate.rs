@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use eyre::{eyre, WrapErr};
+use serde::Deserialize;
+
+/// Dependency of a package of the output of `cargo metadata`
+#[derive(Debug, Deserialize)]
+pub(crate) struct Dependency {
+    pub(crate) name: String,
+}
+
+/// Target of a package of the output of `cargo metadata`
+#[derive(Debug, Deserialize)]
+pub(crate) struct Target {
+    /// `lib`, `bin`, `test`, `bench`...
+    pub(crate) kind: Vec<String>,
+    /// Root source file of the target
+    pub(crate) src_path: PathBuf,
+}
+
+/// Package of the output of `cargo metadata`
+#[derive(Debug, Deserialize)]
+pub(crate) struct Package {
+    pub(crate) name: String,
+    pub(crate) manifest_path: PathBuf,
+    pub(crate) dependencies: Vec<Dependency>,
+    #[serde(default)]
+    pub(crate) targets: Vec<Target>,
+}
+
+/// Output of `cargo metadata`
+#[derive(Debug, Deserialize)]
+pub(crate) struct Metadata {
+    pub(crate) workspace_root: PathBuf,
+    pub(crate) packages: Vec<Package>,
+}
+
+impl Metadata {
+    /// Member of the workspace named `name`
+    pub(crate) fn package(&self, name: &str) -> eyre::Result<&Package> {
+        self.packages
+            .iter()
+            .find(|package| package.name == name)
+            .ok_or(eyre!(
+                "No package {name} in the workspace {}, available packages: {}",
+                self.workspace_root.display(),
+                self.packages
+                    .iter()
+                    .map(|package| package.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+    }
+}
+
+/// Check that the project is a Cargo project that Cargo is able to load
+///
+/// Returns the metadata of the workspace of the project
+pub(crate) fn validate_project(root_path: &Path) -> eyre::Result<Metadata> {
+    let manifest_path = root_path.join("Cargo.toml");
+    if !manifest_path.is_file() {
+        return Err(eyre!(
+            "{} is not a Cargo project, no Cargo.toml found at {}",
+            root_path.display(),
+            manifest_path.display()
+        ));
+    }
+
+    let output = std::process::Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .wrap_err("Unable to run cargo metadata")?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "Cargo is unable to load the project {}:\n{}",
+            manifest_path.display(),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+
+    let mut metadata: Metadata =
+        serde_json::from_slice(&output.stdout).wrap_err("Invalid cargo metadata output")?;
+    metadata.workspace_root = std::fs::canonicalize(&metadata.workspace_root)?;
+    for package in &mut metadata.packages {
+        package.manifest_path = std::fs::canonicalize(&package.manifest_path)?;
+    }
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::validate::validate_project;
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_validate_without_manifest() {
+        let folder = ScratchFolder::new("validate");
+        let error = validate_project(&folder).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "{} is not a Cargo project, no Cargo.toml found at {}",
+                folder.display(),
+                folder.join("Cargo.toml").display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_manifest() {
+        let folder = ScratchFolder::new("validate");
+        std::fs::write(folder.join("Cargo.toml"), "[package\nname = \"broken\"\n").unwrap();
+        let error = validate_project(&folder).unwrap_err().to_string();
+        let header = format!(
+            "Cargo is unable to load the project {}:\n",
+            folder.join("Cargo.toml").display()
+        );
+        assert!(error.starts_with(&header), "{error}");
+        // Followed by the error of Cargo, whose wording depends on its version
+        assert!(error[header.len()..].starts_with("error:"), "{error}");
+    }
+
+    #[test]
+    fn test_validate_project() {
+        let folder = ScratchFolder::new("validate");
+        std::fs::write(
+            folder.join("Cargo.toml"),
+            "[package]\nname = \"valid\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(folder.join("src")).unwrap();
+        std::fs::write(folder.join("src").join("lib.rs"), "").unwrap();
+        let metadata = validate_project(&folder).unwrap();
+        assert_eq!(
+            metadata.workspace_root,
+            std::fs::canonicalize(&*folder).unwrap()
+        );
+        assert_eq!(metadata.packages[0].name, "valid");
+    }
+}
@@ -1,13 +1,248 @@
-use std::io::Read;
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::Path;
 use std::process::Stdio;
-use std::time::Duration;
+use std::time::Instant;
 
-use eyre::eyre;
-use wait_timeout::ChildExt;
+use clap::ValueEnum;
+use eyre::{eyre, WrapErr};
 
+use crate::actions::capture::{run_captured, run_whole};
+use crate::actions::hooks::shell;
+use crate::actions::validate::Package;
+use crate::cli::{Runner, Warnings};
+use crate::config::Settings;
 use crate::mutation::Mutation;
-use crate::report::{MutationReport, MutationStatus};
+use crate::report::{parse_diagnostics, MutationReport, MutationStatus, TimeoutCause};
+
+/// Property testing frameworks and the variable setting their number of cases
+static PROPERTY_FRAMEWORKS: [(&str, &str); 2] = [
+    ("proptest", "PROPTEST_CASES"),
+    ("quickcheck", "QUICKCHECK_TESTS"),
+];
+/// Number of cases of property tests if not configured, frameworks default to 100 or more
+pub(crate) static DEFAULT_PROPERTY_CASES: u32 = 32;
+/// Snapshot testing frameworks and the variables keeping them from accepting changed snapshots
+static SNAPSHOT_FRAMEWORKS: [(&str, &[(&str, &str)]); 1] = [(
+    "insta",
+    &[("INSTA_UPDATE", "no"), ("INSTA_FORCE_PASS", "0")],
+)];
+
+/// Whether one of the packages depends on a framework
+fn uses(packages: &[&Package], framework: &str) -> bool {
+    packages.iter().any(|package| {
+        package
+            .dependencies
+            .iter()
+            .any(|dependency| dependency.name == framework)
+    })
+}
+
+/// Environment lowering the number of cases of the property testing frameworks used by packages
+///
+/// Variables already defined in the environment of darwin, read with `var_os`, are kept.
+pub(crate) fn property_tests_env(
+    packages: &[&Package],
+    cases: u32,
+    var_os: impl Fn(&str) -> Option<OsString>,
+) -> Vec<(String, String)> {
+    PROPERTY_FRAMEWORKS
+        .iter()
+        .filter(|(framework, _)| uses(packages, framework))
+        .filter(|(_, variable)| var_os(variable).is_none())
+        .map(|(framework, variable)| {
+            log::debug!("Run {framework} tests with {cases} cases");
+            (variable.to_string(), cases.to_string())
+        })
+        .collect()
+}
+
+/// Environment of the snapshot testing frameworks used by packages, failing the tests whose snapshot
+/// changes
+///
+/// Variables defined in the environment of darwin are overridden: a mutant whose changed snapshot
+/// is accepted, or written for review, would pass its tests.
+pub(crate) fn snapshot_tests_env(packages: &[&Package]) -> Vec<(String, String)> {
+    SNAPSHOT_FRAMEWORKS
+        .iter()
+        .filter(|(framework, _)| uses(packages, framework))
+        .flat_map(|(framework, variables)| {
+            log::debug!("Run {framework} tests without updating snapshots");
+            variables
+                .iter()
+                .map(|(variable, value)| (variable.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Check that the runner is installed, before verifying any mutant
+pub(crate) fn check_runner(settings: &Settings) -> eyre::Result<()> {
+    if settings.runner != Runner::Miri {
+        return Ok(());
+    }
+    let output = cargo(settings)
+        .args(["miri", "--version"])
+        .current_dir(&settings.root_path)
+        .env("RUST_BACKTRACE", "0")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .wrap_err("Unable to run cargo miri")?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "Miri is unavailable, install it with `rustup +nightly component add miri`:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    Ok(())
+}
+
+/// Cargo running with the toolchain of the settings, or else the one of the project
+fn cargo(settings: &Settings) -> std::process::Command {
+    toolchain_command("cargo", settings.toolchain.as_deref(), |variable| {
+        std::env::var_os(variable)
+    })
+}
+
+/// Version of the compiler building mutants, `rustc -vV` of the toolchain of the settings
+pub(crate) fn compiler_version(settings: &Settings) -> eyre::Result<String> {
+    let output = toolchain_command("rustc", settings.toolchain.as_deref(), |variable| {
+        std::env::var_os(variable)
+    })
+    .arg("-vV")
+    .current_dir(&settings.root_path)
+    .output()
+    .wrap_err("Unable to run rustc")?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "Unable to get the version of rustc:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Rustup proxy running with the given toolchain, or else the one of the project
+fn toolchain_command(
+    program: &str,
+    toolchain: Option<&str>,
+    var_os: impl Fn(&str) -> Option<OsString>,
+) -> std::process::Command {
+    let mut command = std::process::Command::new(program);
+    match toolchain {
+        Some(toolchain) => {
+            command.arg(format!("+{toolchain}"));
+        }
+        // Rustup defines the toolchain darwin runs with, which would take precedence over the
+        // toolchain file of mutant projects, unless it was explicitly chosen
+        None if var_os("RUSTUP_TOOLCHAIN_SOURCE")
+            .is_some_and(|source| source == "default" || source == "toolchain-file") =>
+        {
+            command.env_remove("RUSTUP_TOOLCHAIN");
+        }
+        None => {}
+    }
+    command
+}
+
+/// Cargo command run in a mutant project, with the environment and compiler flags of the settings
+///
+/// Compiler flags are appended to `RUSTFLAGS` if defined, or else to the `build.rustflags` of the
+/// cargo configuration of the project, which `RUSTFLAGS` would override.
+fn cargo_command(
+    args: &[&str],
+    path: &Path,
+    env: &[(String, String)],
+    settings: &Settings,
+) -> std::process::Command {
+    cargo_command_with_flags(args, path, env, settings, &settings.rustflags)
+}
+
+/// Cargo command run in a mutant project, with the environment of the settings and compiler flags
+fn cargo_command_with_flags(
+    args: &[&str],
+    path: &Path,
+    env: &[(String, String)],
+    settings: &Settings,
+    rustflags: &[String],
+) -> std::process::Command {
+    let mut command = cargo(settings);
+    command
+        .current_dir(path)
+        .envs(env.iter().map(|(key, value)| (key, value)));
+    let env_rustflags = env
+        .iter()
+        .rev()
+        .find(|(key, _)| key == "RUSTFLAGS")
+        .map(|(_, value)| value.clone())
+        .or_else(|| std::env::var("RUSTFLAGS").ok());
+    let mut options = vec![];
+    if settings.frozen {
+        options.push("--frozen".to_string());
+    }
+    match env_rustflags {
+        _ if rustflags.is_empty() => {}
+        Some(env_rustflags) => {
+            let mut flags = vec![env_rustflags.as_str()];
+            flags.extend(rustflags.iter().map(String::as_str));
+            command.env("RUSTFLAGS", flags.join(" ").trim());
+        }
+        None => {
+            let flags = toml::Value::from(rustflags.to_vec());
+            options.extend(["--config".to_string(), format!("build.rustflags={flags}")]);
+        }
+    }
+    // Right after the subcommand: external ones, like clippy and miri, ignore options before, and
+    // arguments may end with arguments of the test harness
+    let subcommand = args
+        .iter()
+        .position(|arg| arg.starts_with('-'))
+        .unwrap_or(args.len());
+    command
+        .args(&args[..subcommand])
+        .args(options)
+        .args(&args[subcommand..]);
+    command
+}
+
+/// Homes of cargo and rustup made absolute, as cargo commands of mutants run in other folders
+///
+/// Registries, git checkouts and credentials are then found whatever the folder, relative homes
+/// would resolve in each mutant project.
+pub(crate) fn absolute_homes() -> eyre::Result<Vec<(String, String)>> {
+    let mut env = vec![];
+    for variable in ["CARGO_HOME", "RUSTUP_HOME"] {
+        let Some(home) = std::env::var_os(variable).filter(|home| Path::new(home).is_relative())
+        else {
+            continue;
+        };
+        let home = std::path::absolute(&home).wrap_err(eyre!(
+            "Unable to resolve {variable} {}",
+            home.to_string_lossy()
+        ))?;
+        env.push((variable.to_string(), home.display().to_string()));
+    }
+    Ok(env)
+}
+
+/// Fetch the dependencies of a copy of the project, before mutants are built in parallel
+///
+/// Registries and git repositories are queried once, a dependency which can't be resolved is
+/// reported here instead of killing every mutant.
+pub(crate) fn fetch_dependencies(path: &Path, settings: &Settings) -> eyre::Result<()> {
+    let output = cargo_command(&["fetch"], path, &settings.env, settings)
+        .output()
+        .wrap_err("Unable to run cargo fetch")?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "Unable to fetch the dependencies of the project, check the registries and their credentials, \
+             or fetch them beforehand and run with `--frozen`:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    Ok(())
+}
 
 /// Run cargo build on mutated project
 ///
@@ -16,86 +251,385 @@ use crate::report::{MutationReport, MutationStatus};
 /// Capture output
 ///
 /// Generate the report
+///
+/// Builds go to `target_dir` if any, or else to the target folder of the mutant project
 pub(crate) fn run_test_for_mutation(
     mutation: &mut Mutation,
-    project_path: &PathBuf,
+    path: &Path,
+    settings: &Settings,
+    target_dir: Option<&Path>,
 ) -> eyre::Result<()> {
-    let path = mutation.get_mutation_project_path()?;
-
     log::trace!(
         "Build mutation {} in function {} of file {} at line {}:{}",
         mutation.reason,
         mutation.function_name,
-        dunce::simplified(mutation.get_file_path()?.strip_prefix(project_path)?).display(),
+        mutation.relative_file()?,
         mutation.chunk.start_point.row + 1,
-        mutation.chunk.start_point.column
+        mutation.chunk.start_point.column + 1
     );
 
     log::trace!(
         "Test mutation {} in function {} of file {} at line {}:{}",
         mutation.reason,
         mutation.function_name,
-        dunce::simplified(mutation.get_file_path()?.strip_prefix(project_path)?).display(),
+        mutation.relative_file()?,
         mutation.chunk.start_point.row + 1,
-        mutation.chunk.start_point.column
+        mutation.chunk.start_point.column + 1
     );
 
-    let command = std::process::Command::new("cargo")
-        .arg("build")
-        .current_dir(path)
-        .env("RUSTFLAGS", "-Awarnings")
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()?
-        .wait_with_output()?;
+    let mut env = vec![(
+        "DARWIN_MUTANT_ID".to_string(),
+        mutation.get_mutation_id().to_string(),
+    )];
+    env.extend(scratch_env(&mutation.get_scratch_path()?));
+    env.extend(target_dir.map(target_env));
+    env.extend(settings.env.iter().cloned());
+
+    let mut report = build_and_test(path, &env, settings)?;
+    if report.status == MutationStatus::Timeout && mutation.loop_control {
+        report.timeout = Some(TimeoutCause::InfiniteLoop);
+    }
+    mutation.set_report(report);
+    mutation.pretty()?;
+    Ok(())
+}
+
+/// Run the tests of a copy of the project without mutation
+pub(crate) fn run_baseline_tests(
+    path: &Path,
+    scratch_path: &Path,
+    settings: &Settings,
+) -> eyre::Result<MutationReport> {
+    let mut env = scratch_env(scratch_path);
+    env.extend(settings.env.iter().cloned());
+    build_and_test(path, &env, settings)
+}
+
+/// Build a copy of the project without mutation in a target folder, along with its tests
+///
+/// Mutants later built in the same folder only rebuild the crates of the workspace.
+pub(crate) fn warm_target(path: &Path, target_dir: &Path, settings: &Settings) -> eyre::Result<()> {
+    let mut env = vec![target_env(target_dir)];
+    env.extend(settings.env.iter().cloned());
+    let mut args = settings.runner.test_args().to_vec();
+    args.push("--no-run");
+    if !settings.doctests {
+        args.push("--tests");
+    }
+    for args in [&["build"][..], &args] {
+        let build = run_captured(&mut cargo_command(args, path, &env, settings), None)?;
+        if !build.status.is_some_and(|status| status.success()) {
+            return Err(eyre!("{}", build.stderr.trim_end()));
+        }
+    }
+    Ok(())
+}
+
+/// Check a project with a lint subcommand, `check` or `clippy`, and extra lint flags, returns the
+/// JSON messages of cargo
+///
+/// Lints are capped to warnings instead of following the warnings policy of the settings, so that
+/// every crate is checked and reports its warnings.
+fn lint_messages(
+    args: &[&str],
+    lints: &[&str],
+    path: &Path,
+    env: &[(String, String)],
+    settings: &Settings,
+) -> eyre::Result<String> {
+    let subcommand = args[0];
+    let mut rustflags = settings
+        .rustflags
+        .iter()
+        .filter(|flag| {
+            Warnings::value_variants()
+                .iter()
+                .all(|warnings| warnings.rustflags() != Some(flag.as_str()))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    rustflags.extend(lints.iter().map(|lint| lint.to_string()));
+    rustflags.extend(["--cap-lints".to_string(), "warn".to_string()]);
+    let mut args = args.to_vec();
+    args.push("--message-format=json");
+    let mut command = cargo_command_with_flags(&args, path, env, settings, &rustflags);
+    // Messages are read whole, any of them may matter, and like builds checks have no timeout
+    let output =
+        run_whole(&mut command, None).wrap_err(eyre!("Unable to run cargo {subcommand}"))?;
+    let messages = output.stdout;
+    if !output.status.is_some_and(|status| status.success()) {
+        // Diagnostics are among the messages, the output only tells the build failed
+        let errors = messages
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|message| message["message"]["level"] == "error")
+            .filter_map(|message| Some(message["message"]["rendered"].as_str()?.to_string()))
+            .collect::<String>();
+        return Err(eyre!(
+            "cargo {subcommand} failed:\n{errors}{}",
+            output.stderr.trim_end()
+        ));
+    }
+    Ok(messages)
+}
+
+/// Check the tests of a project, returns the JSON messages of cargo
+pub(crate) fn check_tests(path: &Path, settings: &Settings) -> eyre::Result<String> {
+    lint_messages(&["check", "--tests"], &[], path, &settings.env, settings)
+}
+
+/// Check a project with the `dead_code` lint, returns the JSON messages of cargo for its libraries
+/// and binaries, then for them as tests
+///
+/// Functions only used by unit tests are reported in the first messages, functions only used by
+/// `main` in the others.
+pub(crate) fn dead_code_messages(path: &Path, settings: &Settings) -> eyre::Result<[String; 2]> {
+    let lints = ["-W", "dead_code"];
+    Ok([
+        lint_messages(&["check"], &lints, path, &settings.env, settings)?,
+        lint_messages(
+            &["check", "--profile", "test"],
+            &lints,
+            path,
+            &settings.env,
+            settings,
+        )?,
+    ])
+}
+
+/// Warnings of clippy on the tests of a project, as `code: message at file:line`
+///
+/// Builds go to `target_dir` if any, or else to the target folder of the project.
+pub(crate) fn clippy_warnings(
+    path: &Path,
+    target_dir: Option<&Path>,
+    settings: &Settings,
+) -> eyre::Result<Vec<String>> {
+    let mut env = target_dir.map(target_env).into_iter().collect::<Vec<_>>();
+    env.extend(settings.env.iter().cloned());
+    let messages = lint_messages(&["clippy", "--tests"], &[], path, &env, settings)
+        .wrap_err("Unable to run clippy, install it with `rustup component add clippy`")?;
+    Ok(parse_warnings(&messages))
+}
+
+/// Warnings with a lint code in the JSON messages of cargo, each one once
+fn parse_warnings(messages: &str) -> Vec<String> {
+    let mut warnings = vec![];
+    for diagnostic in parse_diagnostics(messages) {
+        if diagnostic.level != "warning" || diagnostic.code.is_none() {
+            continue;
+        }
+        let warning = diagnostic.simple();
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    warnings
+}
 
-    let report = if command.status.code() == Some(101) {
-        let stdout = String::from_utf8_lossy(&command.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&command.stderr).to_string();
+/// Variable pointing cargo to a target folder out of the project
+fn target_env(target_dir: &Path) -> (String, String) {
+    (
+        "CARGO_TARGET_DIR".to_string(),
+        target_dir.display().to_string(),
+    )
+}
+
+/// Variables pointing tests to a scratch folder, so that files written by parallel mutants stay apart
+fn scratch_env(scratch_path: &Path) -> Vec<(String, String)> {
+    let scratch_path = scratch_path.display().to_string();
+    ["DARWIN_SCRATCH", "TMPDIR", "TMP", "TEMP"]
+        .into_iter()
+        .map(|key| (key.to_string(), scratch_path.clone()))
+        .collect()
+}
 
-        MutationReport::new(stdout, stderr, MutationStatus::CompilationFailed)
+/// Build a project then run its tests, or the oracle of the settings, within the timeout of the
+/// settings
+///
+/// The build reports its diagnostics as JSON, a failed build keeps its errors along with their
+/// human-readable text.
+fn build_and_test(
+    path: &Path,
+    env: &[(String, String)],
+    settings: &Settings,
+) -> eyre::Result<MutationReport> {
+    let build_start = Instant::now();
+    let build = run_captured(
+        &mut cargo_command(&["build", "--message-format=json"], path, env, settings),
+        None,
+    )?;
+    let build_duration = build_start.elapsed();
+
+    let report = if build.status.and_then(|status| status.code()) == Some(101) {
+        let diagnostics = parse_diagnostics(&build.stdout);
+        let rendered = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.rendered.as_str())
+            .collect::<String>();
+        MutationReport::new(
+            "".to_string(),
+            format!("{rendered}{}", build.stderr),
+            MutationStatus::CompilationFailed,
+        )
+        .with_diagnostics(
+            diagnostics
+                .into_iter()
+                .filter(|diagnostic| diagnostic.level == "error")
+                .collect(),
+        )
     } else {
-        let mut command = std::process::Command::new("cargo")
-            .arg("test")
-            .current_dir(path)
-            .env("RUSTFLAGS", "-Awarnings")
-            .env("RUST_BACKTRACE", "0")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
-
-        let cargo_test_result = command.wait_timeout(Duration::from_secs(60))?;
-        match cargo_test_result {
-            Some(status) => {
-                let mut stdout = String::new();
+        let mut command = match &settings.oracle {
+            Some(oracle) => {
+                let mut command = shell(oracle);
                 command
-                    .stdout
-                    .ok_or(eyre!("No stdout"))?
-                    .read_to_string(&mut stdout)?;
-                let mut stderr = String::new();
+                    .current_dir(path)
+                    .envs(env.iter().map(|(key, value)| (key, value)));
                 command
-                    .stderr
-                    .ok_or(eyre!("No stderr"))?
-                    .read_to_string(&mut stderr)?;
-
+            }
+            None => {
+                let mut args = settings.runner.test_args().to_vec();
+                if !settings.doctests {
+                    // Every target but the documentation
+                    args.push("--tests");
+                }
+                if settings.include_ignored {
+                    args.extend(["--", "--include-ignored"]);
+                }
+                cargo_command(&args, path, env, settings)
+            }
+        };
+        command.env("RUST_BACKTRACE", "0");
+        let mut start = Instant::now();
+        let mut test = run_captured(&mut command, Some(settings.timeout))?;
+        let timed_out = test.status.is_none();
+        if timed_out {
+            // Slow tests finish given more time, an infinite loop doesn't
+            log::debug!("Timeout, run the tests again with twice the timeout");
+            start = Instant::now();
+            test = run_captured(&mut command, Some(settings.timeout * 2))?;
+        }
+        let report = match test.status {
+            Some(status) => {
+                // Miri aborts on undefined behavior with its own exit codes
                 let status = match status.code() {
-                    Some(101) => MutationStatus::Fail,
                     Some(0) => MutationStatus::Success,
-                    _ => unreachable!(),
+                    _ => MutationStatus::Fail,
                 };
-                MutationReport::new(stdout, stderr, status)
-            }
-            None => {
-                command.kill()?;
-                MutationReport::new(
-                    "".to_string(),
-                    "Timeout!".to_string(),
-                    MutationStatus::Timeout,
-                )
+                MutationReport::new(test.stdout, test.stderr, status).with_duration(start.elapsed())
             }
+            None => MutationReport::new(
+                "".to_string(),
+                "Timeout!".to_string(),
+                MutationStatus::Timeout,
+            )
+            .with_duration(start.elapsed()),
+        };
+        if timed_out {
+            report.with_timeout(TimeoutCause::Slow)
+        } else {
+            report
         }
     };
-    mutation.set_report(report);
-    mutation.pretty(project_path)?;
-    Ok(())
+    Ok(report.with_build_duration(build_duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    use crate::actions::validate::{Dependency, Package};
+    use crate::actions::verify::{
+        parse_warnings, property_tests_env, snapshot_tests_env, toolchain_command,
+    };
+
+    #[test]
+    fn test_toolchain_command() {
+        let command = |toolchain: Option<&str>, source: Option<&str>| {
+            let command = toolchain_command("cargo", toolchain, |_| source.map(OsString::from));
+            let args = command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            let envs = command
+                .get_envs()
+                .map(|(key, value)| (key.to_string_lossy().to_string(), value.is_some()))
+                .collect::<Vec<_>>();
+            (args, envs)
+        };
+
+        assert_eq!(
+            command(Some("nightly"), None),
+            (vec!["+nightly".to_string()], vec![])
+        );
+        assert_eq!(
+            command(Some("nightly"), Some("default")),
+            (vec!["+nightly".to_string()], vec![])
+        );
+        // The toolchain darwin runs with gives way to the toolchain file of the mutant project
+        for source in ["default", "toolchain-file"] {
+            assert_eq!(
+                command(None, Some(source)),
+                (vec![], vec![("RUSTUP_TOOLCHAIN".to_string(), false)])
+            );
+        }
+        // Explicitly chosen, as with `cargo +stable darwin`
+        assert_eq!(command(None, Some("cli")), (vec![], vec![]));
+        assert_eq!(command(None, None), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_property_tests_env() {
+        let package = |dependencies: &[&str]| Package {
+            name: "a".to_string(),
+            manifest_path: PathBuf::from("Cargo.toml"),
+            dependencies: dependencies
+                .iter()
+                .map(|name| Dependency {
+                    name: name.to_string(),
+                })
+                .collect(),
+            targets: vec![],
+        };
+        let plain = package(&["serde"]);
+        let property = package(&["quickcheck"]);
+        let undefined = |_: &str| None;
+        assert!(property_tests_env(&[&plain], 10, undefined).is_empty());
+        assert_eq!(
+            property_tests_env(&[&plain, &property], 10, undefined),
+            vec![("QUICKCHECK_TESTS".to_string(), "10".to_string())]
+        );
+        // Defined by the user
+        let defined = |variable: &str| (variable == "QUICKCHECK_TESTS").then(|| "500".into());
+        assert!(property_tests_env(&[&property], 10, defined).is_empty());
+
+        let snapshot = package(&["insta"]);
+        assert!(snapshot_tests_env(&[&plain, &property]).is_empty());
+        assert_eq!(
+            snapshot_tests_env(&[&snapshot]),
+            vec![
+                ("INSTA_UPDATE".to_string(), "no".to_string()),
+                ("INSTA_FORCE_PASS".to_string(), "0".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_warnings() {
+        let eq_op = r#"{"reason":"compiler-message","message":{"level":"warning","message":"equal expressions as operands to `==`","code":{"code":"clippy::eq_op"},"spans":[{"file_name":"src/lib.rs","line_start":2,"is_primary":true}]}}"#;
+        let messages = [
+            r#"{"reason":"compiler-artifact","target":{"name":"a"}}"#,
+            eq_op,
+            // The same warning in the library and its tests
+            eq_op,
+            r#"{"reason":"compiler-message","message":{"level":"warning","message":"2 warnings emitted","code":null,"spans":[]}}"#,
+            r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types","code":{"code":"E0308"},"spans":[]}}"#,
+        ]
+        .join("\n");
+        assert_eq!(
+            parse_warnings(&messages),
+            vec!["clippy::eq_op: equal expressions as operands to `==` at src/lib.rs:2"]
+        );
+    }
 }
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::actions::cache::{write_aside, CACHE_DIRECTORY};
+use crate::mutation::Mutation;
+use crate::report::MutationStatus;
+
+static VIABILITY_FILE: &str = "viability.json";
+/// Killed mutants needed before a combination which never built is considered unviable
+static UNVIABLE_THRESHOLD: usize = 3;
+
+/// Outcomes of the mutants of a combination of operator, replacement and syntactic context
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Viability {
+    /// Mutants which built
+    built: usize,
+    /// Mutants which didn't build
+    killed: usize,
+}
+
+/// How often mutants build, by combination of operator, replacement and syntactic context
+///
+/// Kept between runs to learn which combinations never build.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ViabilityStats {
+    combinations: BTreeMap<String, Viability>,
+}
+
+fn stats_path(mutation_root: &Path) -> PathBuf {
+    mutation_root.join(CACHE_DIRECTORY).join(VIABILITY_FILE)
+}
+
+impl ViabilityStats {
+    /// Statistics of the previous runs, empty if none
+    pub(crate) fn load(mutation_root: &Path) -> Self {
+        let path = stats_path(mutation_root);
+        let Ok(file) = File::open(&path) else {
+            return ViabilityStats::default();
+        };
+        serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|error| {
+            log::warn!("Invalid viability statistics {}: {error}", path.display());
+            ViabilityStats::default()
+        })
+    }
+
+    pub(crate) fn save(&self, mutation_root: &Path) -> eyre::Result<()> {
+        write_aside(
+            &stats_path(mutation_root),
+            &serde_json::to_vec_pretty(self)?,
+        )
+    }
+
+    /// Count the outcomes of verified mutants
+    pub(crate) fn record(&mut self, mutants: &[Mutation]) {
+        for mutation in mutants {
+            let Some(report) = mutation.get_report() else {
+                continue;
+            };
+            let viability = self
+                .combinations
+                .entry(mutation.viability_key())
+                .or_default();
+            match report.status {
+                MutationStatus::CompilationFailed => viability.killed += 1,
+                _ => viability.built += 1,
+            }
+        }
+    }
+
+    /// Whether mutants of the combination have been killed several times and never built
+    fn unviable(&self, key: &str) -> bool {
+        self.combinations
+            .get(key)
+            .is_some_and(|viability| viability.built == 0 && viability.killed >= UNVIABLE_THRESHOLD)
+    }
+
+    /// Drop mutants of combinations which never built
+    pub(crate) fn filter(&self, mutants: Vec<Mutation>) -> Vec<Mutation> {
+        let total = mutants.len();
+        let mutants = mutants
+            .into_iter()
+            .filter(|mutation| !self.unviable(&mutation.viability_key()))
+            .collect::<Vec<_>>();
+        if mutants.len() < total {
+            log::info!(
+                "Skip {} mutants of combinations which never built",
+                total - mutants.len()
+            );
+        }
+        mutants
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::viability::ViabilityStats;
+    use crate::mutation::{Mutation, MutationChunk};
+    use crate::report::{MutationReport, MutationStatus};
+
+    #[test]
+    fn test_viability() {
+        let content = "fn sub(x: u8, y: u8) -> u8 {\n    x - y\n}\n";
+        let mutant = |replacement: &str, status: Option<MutationStatus>| {
+            let mut mutation =
+                Mutation::new(replacement, MutationChunk::from_range(content, 35..36))
+                    .with_operator("arithmetic")
+                    .with_context("block");
+            mutation.mutate_file(&content.into());
+            if let Some(status) = status {
+                mutation.set_report(MutationReport::new("".into(), "".into(), status));
+            }
+            mutation
+        };
+
+        let mut stats = ViabilityStats::default();
+        let verified = (0..3)
+            .flat_map(|_| {
+                [
+                    mutant("&&", Some(MutationStatus::CompilationFailed)),
+                    mutant("+", Some(MutationStatus::CompilationFailed)),
+                ]
+            })
+            .chain([mutant("+", Some(MutationStatus::Fail))])
+            .collect::<Vec<_>>();
+        stats.record(&verified);
+
+        let mutants = stats.filter(vec![
+            mutant("&&", None),
+            mutant("+", None),
+            mutant("*", None),
+        ]);
+        assert_eq!(
+            mutants
+                .iter()
+                .map(Mutation::get_replacement)
+                .collect::<Vec<_>>(),
+            vec!["+", "*"]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::actions::reporting::results::{MutantResult, RunResults};
+use crate::report::MutationStatus;
+
+/// Report of the native JSON format of the warnings-ng plugin of Jenkins
+#[derive(Debug, Serialize)]
+struct Report {
+    issues: Vec<Issue>,
+    size: usize,
+}
+
+/// 1-based lines and columns
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Issue {
+    /// Relative to the project
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    /// `ERROR`, `HIGH`, `NORMAL` or `LOW`
+    severity: &'static str,
+    /// Operators family of the mutant
+    category: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    message: String,
+    description: String,
+    /// Stable ID of the mutant, which tells new and fixed issues between builds
+    fingerprint: String,
+}
+
+fn issue(result: &MutantResult) -> Issue {
+    let mutation = &result.mutation;
+    let mut description = format!(
+        "Mutant {} of function \"{}\"",
+        mutation.stable_id, mutation.function
+    );
+    if let Some(execution) = &result.execution {
+        description.push_str(&format!(", {}", execution.hint()));
+    }
+    Issue {
+        file_name: mutation.file.clone(),
+        line_start: mutation.line,
+        line_end: mutation.end_line.max(mutation.line),
+        column_start: mutation.column,
+        column_end: mutation.end_column,
+        severity: "NORMAL",
+        category: mutation.operator.clone(),
+        kind: "Surviving mutant",
+        message: format!("Mutant survived, {}", mutation.reason),
+        description,
+        fingerprint: mutation.stable_id.clone(),
+    }
+}
+
+/// Issues of the surviving mutants of the results
+fn report(results: &RunResults) -> Report {
+    let issues = results
+        .mutants
+        .iter()
+        .filter(|result| result.status == MutationStatus::Success)
+        .map(issue)
+        .collect::<Vec<_>>();
+    Report {
+        size: issues.len(),
+        issues,
+    }
+}
+
+/// Write the surviving mutants of the last run in the native JSON format of warnings-ng,
+/// `issues.json`, to record with its `issues` tool
+pub(crate) fn export(mutation_root: &Path, output: &Path) -> eyre::Result<()> {
+    let results = RunResults::load(mutation_root)?;
+    let report = report(&results);
+    std::fs::create_dir_all(output)?;
+    let path = output.join("issues.json");
+    std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+    println!(
+        "{} surviving mutants exported to {}",
+        report.size,
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::reporting::results::{Execution, MutantResult, RunResults};
+    use crate::actions::warnings_ng::report;
+    use crate::report::MutationStatus;
+
+    #[test]
+    fn test_warnings_ng_report() {
+        let result = |status, replacement: &str, name: &str| MutantResult {
+            execution: Some(Execution {
+                executed: true,
+                tests: vec![],
+            }),
+            ..MutantResult::add_mutant(status, replacement, name)
+        };
+        let results = RunResults {
+            mutants: vec![
+                result(MutationStatus::Success, "*", "mul"),
+                result(MutationStatus::CompilationFailed, "&&", "and"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(report(&results)).unwrap(),
+            serde_json::json!({
+                "issues": [{
+                    "fileName": "src/lib.rs",
+                    "lineStart": 2,
+                    "lineEnd": 2,
+                    "columnStart": 7,
+                    "columnEnd": 8,
+                    "severity": "NORMAL",
+                    "category": "arithmetic",
+                    "type": "Surviving mutant",
+                    "message": "Mutant survived, replace + by *",
+                    "description": "Mutant src-lib-add-2-7-mul of function \"add\", executed by tests but not asserted",
+                    "fingerprint": "src-lib-add-2-7-mul"
+                }],
+                "size": 1
+            })
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use imara_diff::intern::InternedInput;
+
+use crate::actions::coverage::Coverage;
+use crate::actions::reporting::results::{MutantResult, RunResults};
+use crate::actions::{analyze, generate, get_project_entries, reporting};
+use crate::config::Settings;
+use crate::mutation::Mutation;
+use crate::plugin::Plugin;
+use crate::report::MutationStatus;
+
+/// Content of the Rust files of the project, by path relative to the project
+type Snapshot = BTreeMap<PathBuf, String>;
+
+fn snapshot(settings: &Settings) -> eyre::Result<Snapshot> {
+    let mut snapshot = Snapshot::new();
+    for entry in get_project_entries(&settings.root_path, settings)? {
+        let path = entry.path();
+        if !entry.file_type().is_file()
+            || path.extension().is_none_or(|extension| extension != "rs")
+        {
+            continue;
+        }
+        // A file being saved may be unreadable for a moment, it is caught on next poll
+        if let Ok(content) = std::fs::read_to_string(path) {
+            snapshot.insert(
+                path.strip_prefix(&settings.root_path)?.to_path_buf(),
+                content,
+            );
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Changed lines between two versions of a file
+struct LineChanges {
+    /// Changed lines ranges, before and after, 0-based
+    hunks: Vec<(Range<u32>, Range<u32>)>,
+}
+
+impl LineChanges {
+    fn new(before: &str, after: &str) -> Self {
+        let input = InternedInput::new(before, after);
+        let mut hunks = vec![];
+        imara_diff::diff(
+            imara_diff::Algorithm::Histogram,
+            &input,
+            |before: Range<u32>, after: Range<u32>| hunks.push((before, after)),
+        );
+        LineChanges { hunks }
+    }
+
+    /// Line after the change of a line before the change, none if the line has changed
+    fn map(&self, line: usize) -> Option<usize> {
+        let line = line as u32;
+        let mut shift = 0_i64;
+        for (before, after) in &self.hunks {
+            if before.contains(&line) {
+                return None;
+            }
+            if before.start > line {
+                break;
+            }
+            shift += after.len() as i64 - before.len() as i64;
+        }
+        Some((line as i64 + shift) as usize)
+    }
+}
+
+/// Results of the last run, if they still match the code of the project
+fn previous_results(settings: &Settings) -> Option<RunResults> {
+    let results = RunResults::load(&settings.mutation_path).ok()?;
+    results
+        .mutants
+        .iter()
+        .all(|result| Mutation::from_record(&result.mutation, &settings.root_path).is_ok())
+        .then_some(results)
+}
+
+/// Verify the mutants affected by the changes between two snapshots
+///
+/// Mutants on changed lines are new ones, mutants on unchanged lines keep their verdict, except
+/// the survivors of changed files which are verified again. If only files without mutants have
+/// changed, like tests, all the survivors are verified again.
+fn update(
+    settings: &Settings,
+    plugins: &[Plugin],
+    before: &Snapshot,
+    after: &Snapshot,
+    mut results: RunResults,
+) -> eyre::Result<RunResults> {
+    let changed = after
+        .iter()
+        .filter(|(path, content)| before.get(*path) != Some(content))
+        .map(|(path, _)| path)
+        .chain(before.keys().filter(|path| !after.contains_key(*path)))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut next_id = results
+        .mutants
+        .iter()
+        .map(|result| result.mutation.id + 1)
+        .max()
+        .unwrap_or_default();
+    let mut pending = vec![];
+    let mut code_changed = false;
+
+    for path in &changed {
+        let file = path.to_string_lossy().replace('\\', "/");
+        log::info!("{file} has changed");
+        let (previous, others): (Vec<MutantResult>, Vec<MutantResult>) = results
+            .mutants
+            .into_iter()
+            .partition(|result| result.mutation.file == file);
+        results.mutants = others;
+
+        let mutants = match after.get(path) {
+            Some(_) if !settings.is_excluded(path) => {
+                analyze::get_mutations_for_file(&settings.root_path.join(path), settings, plugins)?
+            }
+            _ => vec![],
+        };
+        code_changed |= !previous.is_empty() || !mutants.is_empty();
+
+        let changes = LineChanges::new(
+            before.get(path).map_or("", String::as_str),
+            after.get(path).map_or("", String::as_str),
+        );
+        for mut mutation in mutants {
+            let record = mutation.record()?;
+            let counterpart = previous.iter().find(|result| {
+                changes.map(result.mutation.line - 1) == Some(record.line - 1)
+                    && result.mutation.column == record.column
+                    && result.mutation.original == record.original
+                    && result.mutation.replacement == record.replacement
+            });
+            match counterpart {
+                Some(result) => {
+                    mutation.set_mutation_id(result.mutation.id);
+                    if result.status == MutationStatus::Success {
+                        pending.push(mutation);
+                    } else {
+                        results.mutants.push(MutantResult {
+                            mutation: mutation.record()?,
+                            diff: mutation.diff(false)?,
+                            ..result.clone()
+                        });
+                    }
+                }
+                None => {
+                    mutation.set_mutation_id(next_id);
+                    next_id += 1;
+                    pending.push(mutation);
+                }
+            }
+        }
+    }
+
+    if !code_changed {
+        let (survivors, others): (Vec<MutantResult>, Vec<MutantResult>) = results
+            .mutants
+            .into_iter()
+            .partition(|result| result.status == MutationStatus::Success);
+        results.mutants = others;
+        for result in survivors {
+            pending.push(Mutation::from_record(
+                &result.mutation,
+                &settings.root_path,
+            )?);
+        }
+    }
+
+    results.mutants.sort_by_key(|result| result.mutation.id);
+    let previous = RunResults::load(&settings.mutation_path).unwrap_or_default();
+    reporting::save_results(&results, &settings.mutation_path, settings.sort)?;
+    if pending.is_empty() {
+        println!("No mutant to verify");
+        return Ok(results);
+    }
+
+    generate::generate_and_verify_mutants(&mut pending, settings, &previous)?;
+    let coverage = Coverage::from_settings(settings)?;
+    reporting::update_reports(
+        &pending,
+        coverage.as_ref(),
+        &settings.mutation_path,
+        settings.sort,
+    )
+}
+
+/// Verify again the mutants affected by each change of the project, until interrupted
+pub(crate) fn watch(
+    settings: &Settings,
+    plugins: &[Plugin],
+    interval: Duration,
+) -> eyre::Result<()> {
+    let mut results = match previous_results(settings) {
+        Some(results) => results,
+        None => generate::verify_project(settings, plugins)?,
+    };
+    let mut sources = snapshot(settings)?;
+
+    loop {
+        println!(
+            "Watching {} for changes, Ctrl-C to stop",
+            dunce::simplified(&settings.root_path).display()
+        );
+        let current = loop {
+            std::thread::sleep(interval);
+            let current = snapshot(settings)?;
+            if current != sources {
+                break current;
+            }
+        };
+        results = update(settings, plugins, &sources, &current, results)?;
+        generate::print_survivors(&results, settings);
+        sources = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::watch::LineChanges;
+
+    #[test]
+    fn test_line_changes() {
+        let before = "fn a() {}\nfn b() {}\nfn c() {}\n";
+        let after = "// header\nfn a() {}\nfn b() { 1 }\nfn c() {}\n";
+        let changes = LineChanges::new(before, after);
+        assert_eq!(changes.map(0), Some(1));
+        assert_eq!(changes.map(1), None);
+        assert_eq!(changes.map(2), Some(3));
+    }
+}
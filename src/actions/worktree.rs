@@ -0,0 +1,145 @@
+use std::path::Path;
+use std::process::Stdio;
+
+use eyre::{eyre, WrapErr};
+
+/// Dirty files listed in the error, the others are counted
+static LISTED_FILES: usize = 10;
+
+/// Paths of the files listed by `git status --porcelain -z`
+///
+/// Entries are separated by NUL bytes, paths are left unquoted. Renamed and copied files are
+/// followed by an entry holding their original path.
+fn dirty_files(porcelain: &str) -> Vec<&str> {
+    let mut entries = porcelain.split('\0').filter(|entry| !entry.is_empty());
+    let mut files = vec![];
+    while let Some(entry) = entries.next() {
+        let Some(path) = entry.get(3..) else {
+            continue;
+        };
+        if entry.starts_with(['R', 'C']) {
+            entries.next();
+        }
+        files.push(path);
+    }
+    files
+}
+
+/// Run git in the project, returns its output or an error telling how to skip the check
+fn git(project_path: &Path, args: &[&str]) -> eyre::Result<std::process::Output> {
+    std::process::Command::new("git")
+        .args(args)
+        .current_dir(project_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .wrap_err("Unable to run git, pass --allow-dirty to write in the working tree anyway")
+}
+
+/// Refuse to write a file in the working tree of the project unless git tracks it and tells its
+/// tracked files have no uncommitted changes, so that a mutant written in place can always be
+/// undone with git
+///
+/// `file` is relative to the project. `allow_dirty` skips the check, it's needed as well for
+/// projects out of a git repository.
+pub(crate) fn ensure_clean(
+    project_path: &Path,
+    file: &Path,
+    allow_dirty: bool,
+) -> eyre::Result<()> {
+    if allow_dirty {
+        return Ok(());
+    }
+    let file = file.to_string_lossy();
+    let tracked = git(project_path, &["ls-files", "--error-unmatch", "--", &file])?;
+    if !tracked.status.success() {
+        return Err(eyre!(
+            "{file} isn't tracked by git, the mutant couldn't be undone with git, pass --allow-dirty to write it anyway"
+        ));
+    }
+    let output = git(
+        project_path,
+        &["status", "--porcelain", "-z", "--untracked-files=no"],
+    )?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "Unable to check that the working tree of {} is clean, pass --allow-dirty to write in it anyway:\n{}",
+            project_path.display(),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    let porcelain = String::from_utf8_lossy(&output.stdout);
+    let dirty = dirty_files(&porcelain);
+    if dirty.is_empty() {
+        return Ok(());
+    }
+    let mut listed = dirty
+        .iter()
+        .take(LISTED_FILES)
+        .map(|path| format!("  {path}"))
+        .collect::<Vec<_>>();
+    if dirty.len() > LISTED_FILES {
+        listed.push(format!("  and {} more", dirty.len() - LISTED_FILES));
+    }
+    Err(eyre!(
+        "The working tree of {} has uncommitted changes, commit or stash them, or pass --allow-dirty:\n{}",
+        project_path.display(),
+        listed.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::actions::worktree::{dirty_files, ensure_clean, git};
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_dirty_files() {
+        let porcelain =
+            " M src/lib.rs\0M  Cargo.toml\0R  src/new.rs\0src/old.rs\0 M src/a -> b.rs\0";
+        assert_eq!(
+            dirty_files(porcelain),
+            vec!["src/lib.rs", "Cargo.toml", "src/new.rs", "src/a -> b.rs"]
+        );
+        assert!(dirty_files("").is_empty());
+    }
+
+    #[test]
+    fn test_ensure_clean() {
+        let project = ScratchFolder::new("worktree");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(project.join("src/other.rs"), "fn b() {}\n").unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["add", "src/lib.rs"],
+            &[
+                "-c",
+                "user.name=darwin",
+                "-c",
+                "user.email=darwin@localhost",
+                "commit",
+                "-q",
+                "-m",
+                "init",
+            ],
+        ] {
+            assert!(git(&project, args).unwrap().status.success());
+        }
+        let lib = Path::new("src/lib.rs");
+
+        let clean = ensure_clean(&project, lib, false);
+        // Not tracked
+        let untracked = ensure_clean(&project, Path::new("src/other.rs"), false);
+        std::fs::write(project.join("src/lib.rs"), "fn a() { }\n").unwrap();
+        let dirty = ensure_clean(&project, lib, false);
+        let allowed = ensure_clean(&project, lib, true);
+
+        assert!(clean.is_ok());
+        assert!(untracked.unwrap_err().to_string().contains("isn't tracked"));
+        assert!(dirty.unwrap_err().to_string().contains("src/lib.rs"));
+        assert!(allowed.is_ok());
+    }
+}
@@ -1,14 +1,16 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use colored::Colorize;
+use serde::Deserialize;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::report::{MutationStatus, SkipReason};
 
 fn get_default_project_path() -> PathBuf {
-    let path = env::current_dir().unwrap();
-    path
+    env::current_dir().unwrap()
 }
 
-fn get_default_mutation_path() -> PathBuf {
+pub(crate) fn get_default_mutation_path() -> PathBuf {
     let mut path = env::current_dir().unwrap();
     path.push("tmp");
     path
@@ -21,6 +23,11 @@ pub enum Cli {
     Darwin(Darwin),
 }
 
+/// Write shell completions of the whole command line on stdout
+pub(crate) fn completions(shell: clap_complete::Shell) {
+    clap_complete::generate(shell, &mut Cli::command(), "cargo", &mut std::io::stdout());
+}
+
 pub(crate) fn help() -> String {
     format!(
         r#"
@@ -37,19 +44,743 @@ pub(crate) fn help() -> String {
 }
 
 #[derive(clap::Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 /// Darwin mutates your code, if your code still passes check tests, then your code isn't
 /// enough tested
 pub struct Darwin {
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+    #[command(flatten)]
+    pub(crate) run: RunArgs,
+}
+
+// Parsed once, size doesn't matter
+#[allow(clippy::large_enum_variant)]
+#[derive(clap::Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Write a commented starter darwin.toml in the project
+    Init(InitArgs),
+    /// Print shell completions
+    Completions(CompletionsArgs),
+    /// List mutants without verifying them
+    List(ListArgs),
+    /// Print the details of a mutant of the last run
+    Show(ShowArgs),
+    /// Apply a mutant of the last run on the working tree
+    Apply(ApplyArgs),
+    /// Write copies of the files where mutants of the last run survived, with a comment at their lines
+    Annotate(AnnotateArgs),
+    /// Write the results of the last run for another tool: cargo-mutants, Stryker, SonarQube or Jenkins
+    Export(ExportArgs),
+    /// Read a `mutants.out` folder of cargo-mutants as the results of the last run
+    Import(ImportArgs),
+    /// Verify again mutants of the last run against the current code
+    Rerun(RerunArgs),
+    /// Verify the single mutant at a position of a file, without a full run
+    Verify(VerifySingleArgs),
+    /// Verify again the mutants affected by each change of the project
+    Watch(WatchArgs),
+    /// Verify the project in the background and expose its results over HTTP
+    Serve(ServeArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct CompletionsArgs {
+    /// Shell to generate completions for
+    pub(crate) shell: clap_complete::Shell,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct InitArgs {
+    /// Path of the project to configure
+    #[arg(name = "PROJECT PATH", default_value = get_default_project_path().into_os_string())]
+    pub(crate) root_path: PathBuf,
+    /// Overwrite an existing darwin.toml
+    #[arg(long, action, default_value = "false")]
+    pub(crate) force: bool,
+}
+
+/// Options selecting the project and the mutants
+#[derive(clap::Args, Debug)]
+pub(crate) struct ProjectArgs {
     /// Path of the project to mutate
     #[arg(name = "PROJECT PATH", default_value = get_default_project_path().into_os_string())]
     pub(crate) root_path: PathBuf,
-    /// Root path to mutated projects
-    #[arg(long, default_value = get_default_mutation_path().into_os_string())]
-    pub(crate) mutation_path: PathBuf,
+    /// Only mutate this member of the workspace, built alone instead of copying the whole workspace
+    #[arg(long, short, value_name = "NAME")]
+    pub(crate) package: Option<String>,
+    /// Root path to mutated projects [default: ./tmp]
+    #[arg(long)]
+    pub(crate) mutation_path: Option<PathBuf>,
+    /// Configuration file [default: darwin.toml or .darwin.toml in the project]
+    #[arg(long, value_name = "PATH")]
+    pub(crate) config: Option<PathBuf>,
+    /// Named profile of the configuration, `quick`, `thorough` and `ci` are built-in
+    #[arg(long)]
+    pub(crate) profile: Option<String>,
+    /// Fraction of mutants verified, between 0 and 1 [default: 1]
+    #[arg(long, value_name = "RATE")]
+    pub(crate) sample: Option<f64>,
+    /// Maximum number of mutants verified, spread across files, functions and operators
+    #[arg(long, value_name = "N")]
+    pub(crate) budget: Option<usize>,
+    /// Order in which mutants are taken by `--budget` and verified [default: spread]
+    #[arg(long, value_enum)]
+    pub(crate) order: Option<Order>,
+    /// Glob of files, relative to the project, never mutated, can be repeated
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) exclude: Vec<String>,
+    /// Also mutate build scripts and generated files, like `build.rs` or `*.pb.rs`
+    #[arg(long, action, default_value = "false")]
+    pub(crate) include_generated: bool,
+    /// Only mutate files matching the glob, relative to the project, can be repeated
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) file: Vec<String>,
+    /// Only mutate functions with this name, can be repeated
+    #[arg(long, value_name = "NAME")]
+    pub(crate) function: Vec<String>,
+    /// Only mutate functions with this visibility [default: all]
+    #[arg(long, value_enum)]
+    pub(crate) visibility: Option<Visibility>,
+    /// Only use this operators family, can be repeated
+    #[arg(long, value_name = "NAME")]
+    pub(crate) operator: Vec<String>,
+    /// Never use this operators family, can be repeated
+    #[arg(long, value_name = "NAME")]
+    pub(crate) exclude_operator: Vec<String>,
+    /// Operators approximating the ones of another mutation testing tool
+    #[arg(long, value_enum)]
+    pub(crate) preset: Option<Preset>,
+    /// Only mutate lines of a file, relative to the project, between two lines included, can be repeated
+    #[arg(long, value_name = "FILE:START-END", value_parser = parse_line_range)]
+    pub(crate) line_range: Vec<LineRange>,
+    /// Walk through symbolic links of the project and copy their target, instead of copying links
+    #[arg(long, action, default_value = "false")]
+    pub(crate) follow_symlinks: bool,
+    /// Also mutate arguments of macros taking expressions, like `println!` or `vec!`
+    #[arg(long, action, default_value = "false")]
+    pub(crate) mutate_macro_args: bool,
+    /// Also mutate integer and boolean literals bound to local variables, as `let retries = 3;`
+    #[arg(long, action, default_value = "false")]
+    pub(crate) mutate_literals: bool,
+    /// Dynamic library providing additional mutation operators, can be repeated
+    #[arg(long = "plugin", value_name = "PATH")]
+    pub(crate) plugins: Vec<PathBuf>,
+}
+
+/// Options of the mutants verification
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct VerifyArgs {
     /// Don't run the mutation only list them
     #[arg(long, action, default_value = "false")]
     pub(crate) dry_run: bool,
-    /// keep project folders after test
+    /// Keep project folders after test, all of them or only the missing or killed ones
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MUTANTS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "all"
+    )]
+    pub(crate) keep: Option<Keep>,
+    /// Number of finished runs kept in the mutation path, with their reports, older ones are
+    /// removed [default: 1]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub(crate) keep_reports: Option<u64>,
+    /// Only verify mutants of files changed since the previous run, or which weren't caught
     #[arg(long, action, default_value = "false")]
-    pub(crate) keep: bool,
+    pub(crate) incremental: bool,
+    /// Reuse the cached verdicts of caught and killed mutants while the project, the compiler and
+    /// the options of the verification don't change, `--cache=false` to verify every mutant
+    /// [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) cache: Option<bool>,
+    /// Store shared by runners where verdicts are cached too, a `file://` directory or an
+    /// `http(s)://` server accepting `GET` and `PUT`
+    #[arg(long, value_name = "URL")]
+    pub(crate) cache_url: Option<String>,
+    /// Skip mutants whose operator, replacement and context never built in previous runs,
+    /// `--skip-unviable=false` to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) skip_unviable: Option<bool>,
+    /// Skip mutants of functions compiled in no test target, according to `cargo check`,
+    /// `--skip-not-compiled=false` to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) skip_not_compiled: Option<bool>,
+    /// Skip mutants of functions never used, according to the `dead_code` lint,
+    /// `--skip-dead-code=false` to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) skip_dead_code: Option<bool>,
+    /// Run clippy on missing mutants and report the warnings the project without mutation doesn't
+    /// have, `--clippy-survivors=false` to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) clippy_survivors: Option<bool>,
+    /// Tests timeout in seconds [default: 60]
+    #[arg(long, value_name = "SECONDS")]
+    pub(crate) timeout: Option<u64>,
+    /// Number of mutants verified in parallel [default: 1]
+    #[arg(long, short)]
+    pub(crate) jobs: Option<usize>,
+    /// Build each mutant in a target folder of its own, instead of the pre-built one of its worker,
+    /// `--isolated-targets=false` to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) isolated_targets: Option<bool>,
+    /// Build mutants with `--frozen`, from the lock file and the dependencies already fetched,
+    /// `--frozen=false` to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) frozen: Option<bool>,
+    /// Run doctests along other tests, `--doctests=false` to skip them [default: true]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) doctests: Option<bool>,
+    /// Also run `#[ignore]` tests, which don't catch mutants otherwise, `--include-ignored=false`
+    /// to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) include_ignored: Option<bool>,
+    /// Run the tests once without mutation, then report mutants whose tests ran much faster or
+    /// slower, `--timing=false` to override the configuration [default: false]
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub(crate) timing: Option<bool>,
+    /// LCOV coverage of the tests, a file or a folder of one file per test, telling missing
+    /// mutants never executed from those executed but not asserted
+    #[arg(long, value_name = "PATH")]
+    pub(crate) coverage: Option<PathBuf>,
+    /// Command running the tests of mutants [default: cargo]
+    #[arg(long, value_enum)]
+    pub(crate) runner: Option<Runner>,
+    /// Shell command judging mutants after their build instead of their tests, such as the smoke
+    /// tests of a binary, a mutant is caught if it exits with an error
+    #[arg(long, value_name = "COMMAND")]
+    pub(crate) oracle: Option<String>,
+    /// Rustup toolchain of mutant builds [default: the toolchain of the project]
+    #[arg(long, value_name = "NAME")]
+    pub(crate) toolchain: Option<String>,
+    /// Compiler cache wrapping rustc in mutant builds, a command or a path, `sccache` if omitted
+    #[arg(
+        long,
+        value_name = "WRAPPER",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "sccache"
+    )]
+    pub(crate) compiler_cache: Option<String>,
+    /// Compiler warnings of mutant builds [default: allow]
+    #[arg(long, value_enum)]
+    pub(crate) warnings: Option<Warnings>,
+    /// Additional compiler flags of mutant builds, separated by spaces
+    #[arg(long, value_name = "FLAGS", allow_hyphen_values = true)]
+    pub(crate) rustflags: Option<String>,
+    /// Environment variable of the cargo commands of mutants, can be repeated
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub(crate) env: Vec<(String, String)>,
+    /// File of `KEY=VALUE` lines defining environment variables of the cargo commands of mutants
+    #[arg(long, value_name = "PATH")]
+    pub(crate) env_file: Option<PathBuf>,
+    /// Shell command run in each mutant project before its verification
+    #[arg(long, value_name = "COMMAND")]
+    pub(crate) pre_mutant: Option<String>,
+    /// Shell command run in each mutant project once its verdict is known
+    #[arg(long, value_name = "COMMAND")]
+    pub(crate) post_mutant: Option<String>,
+    /// Shell command run in the project once all reports are generated
+    #[arg(long, value_name = "COMMAND")]
+    pub(crate) post_run: Option<String>,
+    /// Number of files with the lowest score listed at the end of the run [default: 5]
+    #[arg(long, value_name = "N")]
+    pub(crate) weakest: Option<usize>,
+    /// Format of the messages printed at the end of the run [default: human]
+    #[arg(long, value_enum)]
+    pub(crate) message_format: Option<MessageFormat>,
+    /// Order of the mutants in the summary and the survivors printed at the end of the run
+    /// [default: status]
+    #[arg(long, value_enum, value_name = "KEY")]
+    pub(crate) sort: Option<SortKey>,
+    /// URL to which the summary of the run is posted as JSON once it finishes or is aborted
+    #[arg(long, value_name = "URL")]
+    pub(crate) notify_webhook: Option<String>,
+    /// Exit with code 3 when mutants or files are left out of the run for one of these reasons,
+    /// separated by commas
+    #[arg(long, value_enum, value_name = "REASONS", value_delimiter = ',')]
+    pub(crate) fail_on_skip: Vec<SkipReason>,
+}
+
+/// Mutant projects kept after their verification
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Keep {
+    All,
+    /// Mutants not caught by tests
+    Missing,
+    /// Mutants which don't build
+    Killed,
+}
+
+impl Keep {
+    /// Whether the project of a mutant with this status is kept
+    pub(crate) fn retains(&self, status: MutationStatus) -> bool {
+        match self {
+            Keep::All => true,
+            Keep::Missing => status == MutationStatus::Success,
+            Keep::Killed => status == MutationStatus::CompilationFailed,
+        }
+    }
+}
+
+/// Policy of compiler warnings in mutant builds
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Warnings {
+    /// Silence warnings
+    #[default]
+    Allow,
+    /// Keep the lints of the project
+    Warn,
+    /// Mutants causing warnings don't build
+    Deny,
+}
+
+impl Warnings {
+    /// Compiler flags applying the policy
+    pub(crate) fn rustflags(&self) -> Option<&'static str> {
+        match self {
+            Warnings::Allow => Some("-Awarnings"),
+            Warnings::Warn => None,
+            Warnings::Deny => Some("-Dwarnings"),
+        }
+    }
+}
+
+/// Parse a `KEY=VALUE` environment variable
+pub(crate) fn parse_env_var(definition: &str) -> Result<(String, String), String> {
+    match definition.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got {definition}")),
+    }
+}
+
+/// Lines of a file, relative to the project, bounds included
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LineRange {
+    pub(crate) file: PathBuf,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl LineRange {
+    /// Whether the range holds a line of a file relative to the project
+    pub(crate) fn contains(&self, relative_path: &Path, line: usize) -> bool {
+        self.file == relative_path && (self.start..=self.end).contains(&line)
+    }
+}
+
+/// Parse a `FILE:START-END` range of lines
+pub(crate) fn parse_line_range(definition: &str) -> Result<LineRange, String> {
+    let error = || format!("expected FILE:START-END, got {definition}");
+    let (file, lines) = definition.rsplit_once(':').ok_or_else(error)?;
+    let (start, end) = lines.split_once('-').ok_or_else(error)?;
+    let start = start.trim().parse::<usize>().map_err(|_| error())?;
+    let end = end.trim().parse::<usize>().map_err(|_| error())?;
+    if file.is_empty() || start == 0 || start > end {
+        return Err(error());
+    }
+    Ok(LineRange {
+        file: PathBuf::from(file),
+        start,
+        end,
+    })
+}
+
+/// Position in a file relative to the project, 1-based line and column in bytes
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SourcePosition {
+    pub(crate) file: PathBuf,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl std::fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+/// Parse a `FILE:LINE:COLUMN` position
+pub(crate) fn parse_source_position(definition: &str) -> Result<SourcePosition, String> {
+    let error = || format!("expected FILE:LINE:COLUMN, got {definition}");
+    let (rest, column) = definition.rsplit_once(':').ok_or_else(error)?;
+    let (file, line) = rest.rsplit_once(':').ok_or_else(error)?;
+    let line = line.trim().parse::<usize>().map_err(|_| error())?;
+    let column = column.trim().parse::<usize>().map_err(|_| error())?;
+    if file.is_empty() || line == 0 || column == 0 {
+        return Err(error());
+    }
+    Ok(SourcePosition {
+        file: PathBuf::from(file),
+        line,
+        column,
+    })
+}
+
+/// Command running the tests of mutants
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Runner {
+    /// `cargo test`
+    #[default]
+    Cargo,
+    /// `cargo miri test`, mutants causing undefined behavior are caught
+    Miri,
+}
+
+impl Runner {
+    /// Arguments of cargo running the tests
+    pub(crate) fn test_args(&self) -> &'static [&'static str] {
+        match self {
+            Runner::Cargo => &["test"],
+            Runner::Miri => &["miri", "test"],
+        }
+    }
+
+    /// Tests timeout in seconds if not configured, miri interprets tests and is far slower
+    pub(crate) fn default_timeout(&self) -> u64 {
+        match self {
+            Runner::Cargo => 60,
+            Runner::Miri => 600,
+        }
+    }
+}
+
+/// Visibility of the functions mutated
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Visibility {
+    /// Functions declared `pub`, the public API of a library
+    Pub,
+    /// Functions not declared `pub`, `pub(crate)` and `pub(super)` included
+    Private,
+    #[default]
+    All,
+}
+
+impl Visibility {
+    /// Whether a function, public or not, has this visibility
+    pub(crate) fn matches(&self, public: bool) -> bool {
+        match self {
+            Visibility::Pub => public,
+            Visibility::Private => !public,
+            Visibility::All => true,
+        }
+    }
+}
+
+/// Format of the messages printed at the end of the run
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum MessageFormat {
+    /// Summary table and weakest files
+    #[default]
+    Human,
+    /// Surviving mutants as well, one `file:line:column: warning: message` line each, as rustc's
+    /// short messages
+    Short,
+}
+
+/// Order of the mutants in the `summary` and the results printed at the end of the run
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SortKey {
+    /// Order of the mutants found by the analysis
+    Id,
+    /// By file, then line and column
+    File,
+    /// Missing mutants first, then timeouts, caught and killed ones, each by file and line
+    #[default]
+    Status,
+    /// Slowest tests first
+    Duration,
+}
+
+/// Order in which mutants are taken by `--budget` and verified
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Order {
+    /// Files, functions and operators in turn, so that a budget covers the whole project
+    #[default]
+    Spread,
+    /// Functions with the most branches and syntax nodes first
+    Complexity,
+}
+
+/// Operators approximating the ones of another mutation testing tool
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Preset {
+    /// cargo-mutants
+    Mutants,
+    /// PIT, with its default mutators
+    Pit,
+    /// Stryker, with its default mutators
+    StrykerLike,
+}
+
+impl Preset {
+    /// Operators families enabled by the preset
+    pub(crate) fn operators(&self) -> Vec<String> {
+        let operators: &[&str] = match self {
+            Preset::Mutants => &["arithmetic"],
+            Preset::Pit => &["arithmetic", "literal"],
+            Preset::StrykerLike => &["arithmetic", "literal", "length"],
+        };
+        operators
+            .iter()
+            .map(|operator| operator.to_string())
+            .collect()
+    }
+
+    /// Whether the preset keeps a replacement, replacements of families it ignores are kept
+    pub(crate) fn replaces(&self, operator: &str, original: &str, replacement: &str) -> bool {
+        match (self, operator) {
+            // cargo-mutants never replaces `-` by `*` and has no logical replacement of arithmetic
+            (Preset::Mutants, "arithmetic") => {
+                matches!((original, replacement), ("+", "-" | "*") | ("-", "+"))
+            }
+            // Math mutators only swap an operator with its inverse
+            (Preset::Pit | Preset::StrykerLike, "arithmetic") => {
+                matches!((original, replacement), ("+", "-") | ("-", "+"))
+            }
+            // Primitive and boolean returns mutators, values become 0 and booleans are flipped
+            (Preset::Pit, "literal") => {
+                matches!(original, "true" | "false") || replacement.starts_with('0')
+            }
+            // Boolean literal mutator, numbers are left untouched
+            (Preset::StrykerLike, "literal") => matches!(original, "true" | "false"),
+            _ => true,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct RunArgs {
+    /// Only verify the mutant with this numeric or stable ID, can be repeated
+    #[arg(long = "mutant", value_name = "ID", value_delimiter = ',')]
+    pub(crate) mutants: Vec<String>,
+    /// Only verify the mutants whose numeric or stable IDs are listed in the file, one per line
+    #[arg(long, value_name = "PATH")]
+    pub(crate) mutants_file: Option<PathBuf>,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+    #[command(flatten)]
+    pub(crate) verify: VerifyArgs,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) enum Format {
+    /// Human readable
+    #[default]
+    Text,
+    /// JSON document
+    Json,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct ListArgs {
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) format: Format,
+    /// Print the diff of each mutant
+    #[arg(long, action, default_value = "false")]
+    pub(crate) diff: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct ShowArgs {
+    /// Numeric or stable ID of the mutant
+    pub(crate) mutant: String,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct ApplyArgs {
+    /// Numeric or stable ID of the mutant
+    pub(crate) mutant: String,
+    /// Restore the original code of an applied mutant
+    #[arg(long, action, default_value = "false")]
+    pub(crate) revert: bool,
+    /// Don't ask for confirmation
+    #[arg(long, short, action, default_value = "false")]
+    pub(crate) yes: bool,
+    /// Apply the mutant even if tracked files have uncommitted changes, or out of a git repository
+    #[arg(long, action, default_value = "false")]
+    pub(crate) allow_dirty: bool,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct AnnotateArgs {
+    /// Folder of the annotated copies [default: annotated in the mutation path]
+    #[arg(long, value_name = "PATH")]
+    pub(crate) output: Option<PathBuf>,
+    /// Print a patch adding the annotations instead of writing copies
+    #[arg(long, action, default_value = "false", conflicts_with = "output")]
+    pub(crate) patch: bool,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+}
+
+/// Format of the results written by `export`
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum ExportFormat {
+    /// `mutants.out` folder of cargo-mutants
+    #[default]
+    MutantsOut,
+    /// Report of the mutation-testing-report-schema of Stryker, with a page rendering it
+    Stryker,
+    /// Generic issue report of SonarQube, with an issue per surviving mutant
+    Sonarqube,
+    /// Native JSON format of the warnings-ng plugin of Jenkins, with an issue per surviving mutant
+    WarningsNg,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct ExportArgs {
+    /// Format of the export
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) format: ExportFormat,
+    /// Folder of the export [default: mutants.out, stryker, sonarqube or warnings-ng in the mutation path]
+    #[arg(long, value_name = "PATH")]
+    pub(crate) output: Option<PathBuf>,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct ImportArgs {
+    /// `mutants.out` folder written by cargo-mutants
+    #[arg(long = "from", value_name = "PATH")]
+    pub(crate) mutants_out: PathBuf,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct RerunArgs {
+    /// Numeric or stable ID of a mutant, can be repeated
+    #[arg(
+        long = "mutant",
+        short,
+        value_name = "ID",
+        value_delimiter = ',',
+        required_unless_present = "missing",
+        conflicts_with = "missing"
+    )]
+    pub(crate) mutants: Vec<String>,
+    /// Verify again all the mutants which weren't caught
+    #[arg(long, action, default_value = "false")]
+    pub(crate) missing: bool,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+    #[command(flatten)]
+    pub(crate) verify: VerifyArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct VerifySingleArgs {
+    /// Position of the mutated code, relative to the project
+    #[arg(value_name = "FILE:LINE:COLUMN", value_parser = parse_source_position)]
+    pub(crate) position: SourcePosition,
+    /// Code replacing the original one, when several mutants share the position
+    #[arg(long, value_name = "CODE")]
+    pub(crate) replacement: Option<String>,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+    #[command(flatten)]
+    pub(crate) verify: VerifyArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct WatchArgs {
+    /// Delay between two checks of the project
+    #[arg(long, value_name = "SECONDS", default_value_t = 2)]
+    pub(crate) interval: u64,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+    #[command(flatten)]
+    pub(crate) verify: VerifyArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct ServeArgs {
+    /// Address the HTTP API listens on
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1:7878")]
+    pub(crate) listen: String,
+    #[command(flatten)]
+    pub(crate) project: ProjectArgs,
+    #[command(flatten)]
+    pub(crate) verify: VerifyArgs,
 }
@@ -0,0 +1,1356 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eyre::{eyre, WrapErr};
+use serde::Deserialize;
+
+use crate::actions::cargo_config::carried_config;
+use crate::actions::hooks::Hooks;
+use crate::actions::manifest::{normalize, rewrite_external_paths, standalone_manifest};
+use crate::actions::runs::{start_run, LATEST};
+use crate::actions::validate::{validate_project, Package};
+use crate::actions::verify::{
+    absolute_homes, property_tests_env, snapshot_tests_env, DEFAULT_PROPERTY_CASES,
+};
+use crate::cli::{
+    get_default_mutation_path, parse_env_var, Keep, LineRange, MessageFormat, Order, Preset,
+    ProjectArgs, Runner, SortKey, VerifyArgs, Visibility, Warnings,
+};
+use crate::report::SkipReason;
+
+static CONFIG_FILES: [&str; 2] = ["darwin.toml", ".darwin.toml"];
+/// Number of files with the lowest score listed at the end of the run if not configured
+static DEFAULT_WEAKEST_FILES: usize = 5;
+/// Finished runs kept in the mutation path if not configured, only the latest one
+static DEFAULT_KEEP_REPORTS: u64 = 1;
+/// Build scripts and generated files, not mutated unless asked, mutating them alters the
+/// compilation rather than the behavior
+static GENERATED_FILES: [&str; 3] = ["**/build.rs", "**/*_generated.rs", "**/*.pb.rs"];
+
+/// Hooks section of the configuration file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct HooksConfig {
+    pub(crate) pre_mutant: Option<String>,
+    pub(crate) post_mutant: Option<String>,
+    pub(crate) post_run: Option<String>,
+}
+
+/// `keep` option, either a flag or the mutants kept
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum KeepConfig {
+    Flag(bool),
+    Only(Keep),
+}
+
+impl From<KeepConfig> for Option<Keep> {
+    fn from(value: KeepConfig) -> Self {
+        match value {
+            KeepConfig::Flag(true) => Some(Keep::All),
+            KeepConfig::Flag(false) => None,
+            KeepConfig::Only(keep) => Some(keep),
+        }
+    }
+}
+
+/// Operators families enabled or disabled for files matching a glob
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct PathConfig {
+    /// Glob of files, relative to the project
+    pub(crate) glob: String,
+    /// Only these operators families are enabled
+    pub(crate) operators: Option<Vec<String>>,
+    /// These operators families are disabled
+    pub(crate) disable: Vec<String>,
+}
+
+/// Compiled [`PathConfig`]
+#[derive(Debug, Clone)]
+pub(crate) struct PathRule {
+    glob: globset::GlobMatcher,
+    operators: Option<Vec<String>>,
+    disable: Vec<String>,
+}
+
+impl TryFrom<PathConfig> for PathRule {
+    type Error = eyre::Report;
+
+    fn try_from(value: PathConfig) -> Result<Self, Self::Error> {
+        let glob = globset::Glob::new(&value.glob)
+            .wrap_err(eyre!("Invalid path glob {}", value.glob))?
+            .compile_matcher();
+        Ok(PathRule {
+            glob,
+            operators: value.operators,
+            disable: value.disable,
+        })
+    }
+}
+
+impl PathRule {
+    /// Whether the rule forbids the operator on the file
+    fn forbids(&self, relative_path: &Path, operator: &str) -> bool {
+        if !self.glob.is_match(relative_path) {
+            return false;
+        }
+        let not_allowed = self
+            .operators
+            .as_ref()
+            .is_some_and(|operators| !operators.iter().any(|name| name == operator));
+        not_allowed || self.disable.iter().any(|name| name == operator)
+    }
+}
+
+/// Named set of options selected with `--profile`
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ProfileConfig {
+    pub(crate) operators: Option<Vec<String>>,
+    /// Fraction of mutants verified, between 0 and 1
+    pub(crate) sample: Option<f64>,
+    pub(crate) timeout: Option<u64>,
+    pub(crate) jobs: Option<usize>,
+    /// Run doctests along other tests
+    pub(crate) doctests: Option<bool>,
+}
+
+impl ProfileConfig {
+    /// Profiles available without configuration
+    fn builtin(name: &str) -> Option<Self> {
+        let parallelism = std::thread::available_parallelism().map_or(1, usize::from);
+        match name {
+            "quick" => Some(ProfileConfig {
+                sample: Some(0.1),
+                timeout: Some(30),
+                jobs: Some(parallelism),
+                doctests: Some(false),
+                ..Default::default()
+            }),
+            "thorough" => Some(ProfileConfig {
+                sample: Some(1.0),
+                timeout: Some(300),
+                ..Default::default()
+            }),
+            "ci" => Some(ProfileConfig {
+                sample: Some(1.0),
+                timeout: Some(120),
+                jobs: Some(parallelism),
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Subset of a `Cargo.toml` holding darwin configuration
+#[derive(Debug, Default, Deserialize)]
+struct Manifest {
+    package: Option<ManifestSection>,
+    workspace: Option<ManifestSection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ManifestSection {
+    metadata: Option<ManifestMetadata>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ManifestMetadata {
+    darwin: Option<Config>,
+}
+
+impl Manifest {
+    fn from_file(path: &Path) -> eyre::Result<Self> {
+        let content =
+            std::fs::read_to_string(path).wrap_err(eyre!("Unable to read manifest {path:?}"))?;
+        toml::from_str(&content).wrap_err(eyre!("Invalid darwin metadata in {path:?}"))
+    }
+
+    fn package_config(self) -> Option<Config> {
+        self.package?.metadata?.darwin
+    }
+
+    fn workspace_config(self) -> Option<Config> {
+        self.workspace?.metadata?.darwin
+    }
+
+    fn is_workspace(&self) -> bool {
+        self.workspace.is_some()
+    }
+}
+
+/// Content of a `darwin.toml` configuration file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    /// Root path to mutated projects
+    pub(crate) mutation_path: Option<PathBuf>,
+    /// Keep project folders after test, `true` or `"all"`, `"missing"`, `"killed"`
+    pub(crate) keep: Option<KeepConfig>,
+    /// Number of finished runs kept in the mutation path, with their reports
+    pub(crate) keep_reports: Option<u64>,
+    /// Reuse cached verdicts of mutants while the project, the compiler and the verification options
+    /// don't change
+    pub(crate) cache: Option<bool>,
+    /// Store shared by runners where verdicts are cached too, `file://` or `http(s)://`
+    pub(crate) cache_url: Option<String>,
+    /// Skip mutants whose operator, replacement and context never built in previous runs
+    pub(crate) skip_unviable: Option<bool>,
+    /// Skip mutants of functions compiled in no test target, according to `cargo check`
+    pub(crate) skip_not_compiled: Option<bool>,
+    /// Skip mutants of functions never used, according to the `dead_code` lint
+    pub(crate) skip_dead_code: Option<bool>,
+    /// Run clippy on missing mutants and report the warnings the project without mutation doesn't have
+    pub(crate) clippy_survivors: Option<bool>,
+    /// Tests timeout in seconds
+    pub(crate) timeout: Option<u64>,
+    /// Number of mutants verified in parallel
+    pub(crate) jobs: Option<usize>,
+    /// Build each mutant in a target folder of its own, instead of the pre-built one of its worker
+    pub(crate) isolated_targets: Option<bool>,
+    /// Build mutants with `--frozen`, from the lock file and the dependencies already fetched
+    pub(crate) frozen: Option<bool>,
+    /// Run doctests along other tests, true if not defined
+    pub(crate) doctests: Option<bool>,
+    /// Also run `#[ignore]` tests
+    pub(crate) include_ignored: Option<bool>,
+    /// Run the tests once without mutation and compare the duration of the tests of mutants
+    pub(crate) timing: Option<bool>,
+    /// LCOV coverage of the tests, relative to the project, a file or a folder of one file per test
+    pub(crate) coverage: Option<PathBuf>,
+    /// Command running the tests of mutants, `"cargo"` or `"miri"`
+    pub(crate) runner: Option<Runner>,
+    /// Shell command judging mutants instead of their tests, run in the mutant project
+    pub(crate) oracle: Option<String>,
+    /// Number of cases of proptest and quickcheck tests
+    pub(crate) property_cases: Option<u32>,
+    /// Rustup toolchain of mutant builds
+    pub(crate) toolchain: Option<String>,
+    /// Compiler cache wrapping rustc in mutant builds, a command or a path relative to the project
+    pub(crate) compiler_cache: Option<String>,
+    /// Compiler warnings of mutant builds, `"allow"`, `"warn"` or `"deny"`
+    pub(crate) warnings: Option<Warnings>,
+    /// Additional compiler flags of mutant builds
+    pub(crate) rustflags: Vec<String>,
+    /// Environment variables of the cargo commands of mutants
+    pub(crate) env: BTreeMap<String, String>,
+    /// File of `KEY=VALUE` lines, relative to the project, defining environment variables
+    pub(crate) env_file: Option<PathBuf>,
+    /// Globs of files, relative to the project, never mutated
+    pub(crate) exclude: Vec<String>,
+    /// Globs of build scripts and generated files, not mutated unless `include_generated` is set
+    pub(crate) generated: Option<Vec<String>>,
+    /// Mutate build scripts and generated files as well
+    pub(crate) include_generated: Option<bool>,
+    /// Operators families enabled, all if not defined
+    pub(crate) operators: Option<Vec<String>>,
+    /// Operators approximating another tool, `"mutants"`, `"pit"` or `"stryker-like"`
+    pub(crate) preset: Option<Preset>,
+    /// Visibility of the functions mutated, `"pub"`, `"private"` or `"all"`
+    pub(crate) visibility: Option<Visibility>,
+    /// Fraction of mutants verified, between 0 and 1
+    pub(crate) sample: Option<f64>,
+    /// Maximum number of mutants verified, spread across files, functions and operators
+    pub(crate) budget: Option<usize>,
+    /// Order in which mutants are taken by `budget` and verified, `"spread"` or `"complexity"`
+    pub(crate) order: Option<Order>,
+    /// Additional attributes marking a function as a test
+    pub(crate) test_attributes: Vec<String>,
+    /// Walk through symbolic links and copy their target
+    pub(crate) follow_symlinks: Option<bool>,
+    /// Also mutate arguments of macros taking expressions
+    pub(crate) mutate_macro_args: Option<bool>,
+    /// Also mutate literals bound to local variables by a `let` or an assignment
+    pub(crate) mutate_literals: Option<bool>,
+    /// Dynamic libraries providing additional mutation operators
+    pub(crate) plugins: Vec<PathBuf>,
+    pub(crate) hooks: HooksConfig,
+    /// Number of files with the lowest score listed at the end of the run
+    pub(crate) weakest: Option<usize>,
+    /// Format of the messages printed at the end of the run, `"human"` or `"short"`
+    pub(crate) message_format: Option<MessageFormat>,
+    /// Order of the mutants in the summary and the survivors printed at the end of the run,
+    /// `"id"`, `"file"`, `"status"` or `"duration"`
+    pub(crate) sort: Option<SortKey>,
+    /// URL to which the summary of the run is posted as JSON once it finishes or is aborted
+    pub(crate) notify_webhook: Option<String>,
+    /// Reasons for which mutants or files left out of the run make darwin exit with code 3, as
+    /// `["parse_failure"]`
+    pub(crate) fail_on_skip: Option<Vec<SkipReason>>,
+    /// Operators rules per path, all matching rules apply
+    pub(crate) paths: Vec<PathConfig>,
+    /// Named profiles, `quick`, `thorough` and `ci` are built-in
+    pub(crate) profile: BTreeMap<String, ProfileConfig>,
+}
+
+impl Config {
+    /// Override fields defined in `other`, lists are concatenated
+    fn merge(self, other: Config) -> Config {
+        fn concat<T>(mut base: Vec<T>, other: Vec<T>) -> Vec<T> {
+            base.extend(other);
+            base
+        }
+        Config {
+            mutation_path: other.mutation_path.or(self.mutation_path),
+            keep: other.keep.or(self.keep),
+            keep_reports: other.keep_reports.or(self.keep_reports),
+            cache: other.cache.or(self.cache),
+            cache_url: other.cache_url.or(self.cache_url),
+            skip_unviable: other.skip_unviable.or(self.skip_unviable),
+            skip_not_compiled: other.skip_not_compiled.or(self.skip_not_compiled),
+            skip_dead_code: other.skip_dead_code.or(self.skip_dead_code),
+            clippy_survivors: other.clippy_survivors.or(self.clippy_survivors),
+            timeout: other.timeout.or(self.timeout),
+            jobs: other.jobs.or(self.jobs),
+            isolated_targets: other.isolated_targets.or(self.isolated_targets),
+            frozen: other.frozen.or(self.frozen),
+            doctests: other.doctests.or(self.doctests),
+            runner: other.runner.or(self.runner),
+            oracle: other.oracle.or(self.oracle),
+            property_cases: other.property_cases.or(self.property_cases),
+            toolchain: other.toolchain.or(self.toolchain),
+            compiler_cache: other.compiler_cache.or(self.compiler_cache),
+            warnings: other.warnings.or(self.warnings),
+            rustflags: concat(self.rustflags, other.rustflags),
+            env: {
+                let mut env = self.env;
+                env.extend(other.env);
+                env
+            },
+            env_file: other.env_file.or(self.env_file),
+            exclude: concat(self.exclude, other.exclude),
+            generated: other.generated.or(self.generated),
+            include_generated: other.include_generated.or(self.include_generated),
+            operators: other.operators.or(self.operators),
+            preset: other.preset.or(self.preset),
+            visibility: other.visibility.or(self.visibility),
+            sample: other.sample.or(self.sample),
+            budget: other.budget.or(self.budget),
+            order: other.order.or(self.order),
+            test_attributes: concat(self.test_attributes, other.test_attributes),
+            follow_symlinks: other.follow_symlinks.or(self.follow_symlinks),
+            include_ignored: other.include_ignored.or(self.include_ignored),
+            timing: other.timing.or(self.timing),
+            coverage: other.coverage.or(self.coverage),
+            mutate_macro_args: other.mutate_macro_args.or(self.mutate_macro_args),
+            mutate_literals: other.mutate_literals.or(self.mutate_literals),
+            plugins: concat(self.plugins, other.plugins),
+            hooks: HooksConfig {
+                pre_mutant: other.hooks.pre_mutant.or(self.hooks.pre_mutant),
+                post_mutant: other.hooks.post_mutant.or(self.hooks.post_mutant),
+                post_run: other.hooks.post_run.or(self.hooks.post_run),
+            },
+            weakest: other.weakest.or(self.weakest),
+            message_format: other.message_format.or(self.message_format),
+            sort: other.sort.or(self.sort),
+            notify_webhook: other.notify_webhook.or(self.notify_webhook),
+            fail_on_skip: other.fail_on_skip.or(self.fail_on_skip),
+            paths: concat(self.paths, other.paths),
+            profile: {
+                let mut profile = self.profile;
+                profile.extend(other.profile);
+                profile
+            },
+        }
+    }
+
+    /// Find a profile defined in configuration or built-in
+    fn profile(&self, name: &str) -> eyre::Result<ProfileConfig> {
+        self.profile
+            .get(name)
+            .cloned()
+            .or_else(|| ProfileConfig::builtin(name))
+            .ok_or(eyre!("Unknown profile {name}"))
+    }
+
+    /// Read `[package.metadata.darwin]` and `[workspace.metadata.darwin]` sections
+    ///
+    /// The workspace is either the project itself or the closest ancestor
+    /// declaring a `[workspace]`, package settings take precedence
+    fn from_manifests(root_path: &Path) -> eyre::Result<Self> {
+        let manifest_path = root_path.join("Cargo.toml");
+        if !manifest_path.is_file() {
+            return Ok(Config::default());
+        }
+
+        let manifest = Manifest::from_file(&manifest_path)?;
+        let workspace = if manifest.is_workspace() {
+            Manifest::from_file(&manifest_path)?.workspace_config()
+        } else {
+            root_path
+                .ancestors()
+                .skip(1)
+                .map(|ancestor| ancestor.join("Cargo.toml"))
+                .filter(|path| path.is_file())
+                .filter_map(|path| Manifest::from_file(&path).ok())
+                .find(Manifest::is_workspace)
+                .and_then(Manifest::workspace_config)
+        };
+
+        Ok(workspace
+            .unwrap_or_default()
+            .merge(manifest.package_config().unwrap_or_default()))
+    }
+
+    /// Parse a configuration file
+    pub(crate) fn from_file(path: &Path) -> eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err(eyre!("Unable to read configuration file {path:?}"))?;
+        toml::from_str(&content).wrap_err(eyre!("Invalid configuration file {path:?}"))
+    }
+
+    /// Load the configuration of the project
+    ///
+    /// Cargo manifests metadata are read first, then overridden by an explicit
+    /// configuration path which must exist, otherwise by `darwin.toml` or
+    /// `.darwin.toml` in the project root
+    pub(crate) fn load(root_path: &Path, config_path: Option<&Path>) -> eyre::Result<Self> {
+        let config = Config::from_manifests(root_path)?;
+
+        if let Some(config_path) = config_path {
+            return Ok(config.merge(Config::from_file(config_path)?));
+        }
+
+        for file_name in CONFIG_FILES {
+            let path = root_path.join(file_name);
+            if path.is_file() {
+                log::info!("Load configuration {}", path.display());
+                return Ok(config.merge(Config::from_file(&path)?));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Parse the mutant IDs of a file, one per line
+///
+/// Blank lines and `#` comments are skipped, anything after the ID on its line is ignored.
+fn parse_mutants_file(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Whether a shell command runs `cargo bench`
+fn runs_benchmarks(command: &str) -> bool {
+    let words = command
+        .split(|c: char| c.is_whitespace() || c == ';' || c == '&' || c == '|')
+        .filter(|word| !word.is_empty() && !word.starts_with('+'))
+        .collect::<Vec<_>>();
+    words.windows(2).any(|words| {
+        Path::new(words[0])
+            .file_stem()
+            .is_some_and(|name| name == "cargo")
+            && words[1] == "bench"
+    })
+}
+
+/// Path of a compiler cache, a command found in `path` or else a path relative to the project
+fn find_compiler_cache(
+    root_path: &Path,
+    name: &str,
+    path: Option<std::ffi::OsString>,
+) -> eyre::Result<PathBuf> {
+    let command = Path::new(name);
+    let found = match command.components().count() {
+        1 => std::env::split_paths(&path.unwrap_or_default())
+            .map(|folder| folder.join(format!("{name}{}", std::env::consts::EXE_SUFFIX)))
+            .find(|candidate| candidate.is_file()),
+        _ => Some(root_path.join(command)).filter(|candidate| candidate.is_file()),
+    };
+    found.ok_or(eyre!("Compiler cache {name} not found"))
+}
+
+/// Parse the `KEY=VALUE` lines of an env file
+///
+/// Blank lines and `#` comments are skipped, an `export` prefix and quotes around values are allowed.
+fn parse_env_file(content: &str) -> eyre::Result<Vec<(String, String)>> {
+    let mut env = vec![];
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) =
+            parse_env_var(line).map_err(|error| eyre!("Line {}: {error}", index + 1))?;
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|quote| {
+                value
+                    .strip_prefix(*quote)
+                    .and_then(|value| value.strip_suffix(*quote))
+            })
+            .unwrap_or(value);
+        env.push((key, value.to_string()));
+    }
+    Ok(env)
+}
+
+/// Globs of the benchmarks of packages, which no test runs: the `benches` folder next to their
+/// manifest and their `bench` targets, relative to the project
+fn benchmark_files(packages: &[&Package], root_path: &Path) -> Vec<String> {
+    let relative = |path: &Path| {
+        let path = path.strip_prefix(root_path).ok()?.to_string_lossy();
+        Some(globset::escape(&path.replace('\\', "/")))
+    };
+    let mut globs = vec![];
+    for package in packages {
+        if let Some(folder) = package.manifest_path.parent().and_then(relative) {
+            if folder.is_empty() {
+                globs.push("benches/**".to_string());
+            } else {
+                globs.push(format!("{folder}/benches/**"));
+            }
+        }
+        globs.extend(
+            package
+                .targets
+                .iter()
+                .filter(|target| target.kind.iter().any(|kind| kind == "bench"))
+                .filter_map(|target| relative(&target.src_path)),
+        );
+    }
+    globs
+}
+
+fn glob_set<'a>(patterns: impl Iterator<Item = &'a String>) -> eyre::Result<globset::GlobSet> {
+    let mut set = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        set.add(globset::Glob::new(pattern).wrap_err(eyre!("Invalid glob {pattern}"))?);
+    }
+    Ok(set.build()?)
+}
+
+/// Options of a darwin run, CLI flags take precedence over the configuration file
+#[derive(Debug, Clone)]
+pub(crate) struct Settings {
+    pub(crate) root_path: PathBuf,
+    /// Root of the workspace of the project, copied in each mutant project
+    pub(crate) workspace_root: PathBuf,
+    /// Path of the project relative to the workspace root
+    pub(crate) member_path: PathBuf,
+    /// Manifests, lock, toolchain and cargo configuration files written in mutant projects, relative
+    /// to the workspace root
+    pub(crate) manifest_rewrites: Vec<(PathBuf, String)>,
+    /// Folder of the runs, with the caches shared by them
+    pub(crate) mutation_root: PathBuf,
+    /// Folder of the current run, the `latest` link of the mutation root outside of a run
+    pub(crate) mutation_path: PathBuf,
+    pub(crate) dry_run: bool,
+    /// Mutant projects kept after their verification, none if not defined
+    pub(crate) keep: Option<Keep>,
+    /// Finished runs kept in the mutation path, the latest included
+    pub(crate) keep_reports: usize,
+    /// Mutants of unchanged files keep the verdict of the previous run
+    pub(crate) incremental: bool,
+    /// Caught and killed mutants keep a cached verdict while the project and the build don't change
+    pub(crate) cache: bool,
+    /// Store shared by runners where verdicts are cached too
+    pub(crate) cache_url: Option<String>,
+    /// Mutants of combinations which never built are skipped
+    pub(crate) skip_unviable: bool,
+    /// Mutants of functions compiled in no test target are skipped
+    pub(crate) skip_not_compiled: bool,
+    /// Mutants of functions never used are skipped
+    pub(crate) skip_dead_code: bool,
+    /// Clippy runs on missing mutants, whose new warnings are reported
+    pub(crate) clippy_survivors: bool,
+    pub(crate) timeout: Duration,
+    pub(crate) jobs: usize,
+    /// Each mutant is built in a target folder of its own, instead of the pre-built one of its worker
+    pub(crate) isolated_targets: bool,
+    /// Target folders of the workers are kept once mutants are verified, for the next verification
+    /// in the same mutation path
+    pub(crate) keep_targets: bool,
+    /// Cargo commands of mutants run with `--frozen`, never accessing the network nor the lock file
+    pub(crate) frozen: bool,
+    /// Doctests are run along other tests
+    pub(crate) doctests: bool,
+    /// `#[ignore]` tests run along the others
+    pub(crate) include_ignored: bool,
+    /// Tests run once without mutation, to compare their duration with the ones of mutants
+    pub(crate) timing: bool,
+    /// LCOV coverage of the tests, a file or a folder of one file per test
+    pub(crate) coverage: Option<PathBuf>,
+    pub(crate) runner: Runner,
+    /// Shell command judging mutants after their build, instead of the runner
+    pub(crate) oracle: Option<String>,
+    /// Environment of the cargo commands of mutants, later variables take precedence
+    pub(crate) env: Vec<(String, String)>,
+    /// Compiler flags of mutant builds, warnings policy included
+    pub(crate) rustflags: Vec<String>,
+    /// Rustup toolchain of mutant builds, the one of the project if not defined
+    pub(crate) toolchain: Option<String>,
+    pub(crate) exclude: globset::GlobSet,
+    /// Only files matching are mutated, all if empty
+    pub(crate) files: globset::GlobSet,
+    /// Only functions with these names are mutated, all if empty
+    pub(crate) functions: Vec<String>,
+    pub(crate) operators: Option<Vec<String>>,
+    /// Operators families never used
+    pub(crate) excluded_operators: Vec<String>,
+    /// Replacements approximating another tool
+    pub(crate) preset: Option<Preset>,
+    /// Visibility of the functions mutated
+    pub(crate) visibility: Visibility,
+    /// Only lines of these ranges are mutated, all if empty
+    pub(crate) line_ranges: Vec<LineRange>,
+    /// Only mutants with these numeric or stable IDs are verified, all if empty
+    pub(crate) mutants: Vec<String>,
+    pub(crate) sample: f64,
+    /// Maximum number of mutants verified
+    pub(crate) budget: Option<usize>,
+    /// Order in which mutants are taken by the budget and verified
+    pub(crate) order: Order,
+    pub(crate) path_rules: Vec<PathRule>,
+    pub(crate) test_attributes: Vec<String>,
+    /// Symbolic links are walked through and their target copied
+    pub(crate) follow_symlinks: bool,
+    /// Arguments of macros taking expressions are mutated
+    pub(crate) mutate_macro_args: bool,
+    /// Literals bound to local variables are mutated
+    pub(crate) mutate_literals: bool,
+    pub(crate) plugins: Vec<PathBuf>,
+    pub(crate) hooks: Hooks,
+    /// Number of files with the lowest score listed at the end of the run
+    pub(crate) weakest: usize,
+    /// Format of the messages printed at the end of the run
+    pub(crate) message_format: MessageFormat,
+    /// Order of the mutants in the summary and the results printed at the end of the run
+    pub(crate) sort: SortKey,
+    /// URL notified of the end of the run
+    pub(crate) notify_webhook: Option<String>,
+    /// Mutants or files left out for these reasons make the run exit with code 3
+    pub(crate) fail_on_skip: Vec<SkipReason>,
+}
+
+impl Settings {
+    pub(crate) fn new(cli: ProjectArgs, verify: VerifyArgs) -> eyre::Result<Self> {
+        let root_path = std::fs::canonicalize(&cli.root_path)
+            .wrap_err(eyre!("Unable to find project {:?}", cli.root_path))?;
+        let metadata = validate_project(&root_path)?;
+        let (root_path, workspace_root, mut manifest_rewrites) = match &cli.package {
+            // Only the member is copied, with a manifest building it outside of the workspace
+            Some(name) => {
+                let package = metadata.package(name)?;
+                let package_root = package
+                    .manifest_path
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| metadata.workspace_root.clone());
+                let mut rewrites = vec![(
+                    PathBuf::from("Cargo.toml"),
+                    standalone_manifest(&metadata, package)?,
+                )];
+                for file in ["Cargo.lock", "rust-toolchain.toml", "rust-toolchain"] {
+                    if let Ok(content) = std::fs::read_to_string(metadata.workspace_root.join(file))
+                    {
+                        rewrites.push((PathBuf::from(file), content));
+                    }
+                }
+                (package_root.clone(), package_root, rewrites)
+            }
+            None => {
+                let rewrites = rewrite_external_paths(&metadata)?;
+                (root_path, metadata.workspace_root.clone(), rewrites)
+            }
+        };
+        let config = Config::load(&root_path, cli.config.as_deref())?;
+        let packages = metadata
+            .packages
+            .iter()
+            .filter(|package| package.manifest_path.starts_with(&root_path))
+            .collect::<Vec<_>>();
+        let mut env = absolute_homes()?;
+        if let Some(wrapper) = verify.compiler_cache.or(config.compiler_cache.clone()) {
+            let wrapper = find_compiler_cache(&root_path, &wrapper, std::env::var_os("PATH"))?;
+            log::info!("Build mutants with compiler cache {}", wrapper.display());
+            env.push(("RUSTC_WRAPPER".to_string(), wrapper.display().to_string()));
+        }
+        env.extend(property_tests_env(
+            &packages,
+            config.property_cases.unwrap_or(DEFAULT_PROPERTY_CASES),
+            |variable| std::env::var_os(variable),
+        ));
+        env.extend(snapshot_tests_env(&packages));
+        env.extend(config.env.clone());
+        // Paths of the configuration file are relative to the project
+        let env_files = config
+            .env_file
+            .as_ref()
+            .map(|path| root_path.join(path))
+            .into_iter()
+            .chain(verify.env_file);
+        for path in env_files {
+            let content = std::fs::read_to_string(&path)
+                .wrap_err(eyre!("Unable to read env file {}", path.display()))?;
+            env.extend(
+                parse_env_file(&content).wrap_err(eyre!("Invalid env file {}", path.display()))?,
+            );
+        }
+        env.extend(verify.env);
+
+        let warnings = verify.warnings.or(config.warnings).unwrap_or_default();
+        let rustflags = warnings
+            .rustflags()
+            .map(String::from)
+            .into_iter()
+            .chain(config.rustflags.iter().cloned())
+            .chain(
+                verify
+                    .rustflags
+                    .iter()
+                    .flat_map(|flags| flags.split_whitespace().map(String::from)),
+            )
+            .collect();
+
+        let profile = match &cli.profile {
+            Some(name) => config.profile(name)?,
+            None => ProfileConfig::default(),
+        };
+        let preset = cli.preset.or(config.preset);
+        let sample = cli
+            .sample
+            .or(profile.sample)
+            .or(config.sample)
+            .unwrap_or(1.0);
+        if !(0.0..=1.0).contains(&sample) {
+            return Err(eyre!("Sample rate must be between 0 and 1, got {sample}"));
+        }
+
+        let generated = if cli.include_generated || config.include_generated.unwrap_or_default() {
+            vec![]
+        } else {
+            config
+                .generated
+                .unwrap_or_else(|| GENERATED_FILES.iter().map(ToString::to_string).collect())
+        };
+        let exclude = glob_set(
+            config
+                .exclude
+                .iter()
+                .chain(cli.exclude.iter())
+                .chain(generated.iter())
+                .chain(benchmark_files(&packages, &root_path).iter()),
+        )?;
+        let files = glob_set(cli.file.iter())?;
+
+        let mut test_attributes = vec![
+            "test".to_string(),
+            "tokio::test".to_string(),
+            "bench".to_string(),
+        ];
+        test_attributes.extend(config.test_attributes);
+
+        // Paths of the configuration file are relative to the project
+        let mut plugins = config
+            .plugins
+            .iter()
+            .map(|path| root_path.join(path))
+            .collect::<Vec<_>>();
+        plugins.extend(cli.plugins);
+
+        let mutation_path = cli
+            .mutation_path
+            .or(config.mutation_path.map(|path| root_path.join(path)))
+            .unwrap_or_else(get_default_mutation_path);
+        let mutation_root = normalize(&std::path::absolute(&mutation_path)?);
+        if let Some(cargo_config) = carried_config(&workspace_root, &mutation_root)? {
+            log::info!("Carry the cargo configuration of the parent folders to mutant projects");
+            manifest_rewrites.push(cargo_config);
+        }
+
+        let coverage = verify
+            .coverage
+            .or(config.coverage.map(|path| root_path.join(path)));
+
+        let runner = verify.runner.or(config.runner).unwrap_or_default();
+        let oracle = verify.oracle.or(config.oracle);
+        if let Some(oracle) = oracle.as_deref().filter(|oracle| runs_benchmarks(oracle)) {
+            return Err(eyre!(
+                "The oracle `{oracle}` runs `cargo bench`, which would run the benchmarks for each mutant, \
+                 use `cargo test --benches` to run them once"
+            ));
+        }
+
+        let keep_reports = verify
+            .keep_reports
+            .or(config.keep_reports)
+            .unwrap_or(DEFAULT_KEEP_REPORTS);
+        if keep_reports == 0 {
+            return Err(eyre!(
+                "At least the latest run must be kept, got keep_reports = 0"
+            ));
+        }
+
+        let member_path = root_path
+            .strip_prefix(&workspace_root)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let cache_url = verify.cache_url.or(config.cache_url.clone());
+        Ok(Settings {
+            root_path,
+            workspace_root,
+            member_path,
+            manifest_rewrites,
+            mutation_path: mutation_root.join(LATEST),
+            mutation_root,
+            dry_run: verify.dry_run,
+            keep: verify.keep.or(config.keep.and_then(Option::from)),
+            keep_reports: keep_reports as usize,
+            incremental: verify.incremental,
+            // A shared store is only given to be used
+            cache: verify.cache.or(config.cache).unwrap_or(cache_url.is_some()),
+            cache_url,
+            skip_unviable: verify
+                .skip_unviable
+                .or(config.skip_unviable)
+                .unwrap_or_default(),
+            skip_not_compiled: verify
+                .skip_not_compiled
+                .or(config.skip_not_compiled)
+                .unwrap_or_default(),
+            skip_dead_code: verify
+                .skip_dead_code
+                .or(config.skip_dead_code)
+                .unwrap_or_default(),
+            clippy_survivors: verify
+                .clippy_survivors
+                .or(config.clippy_survivors)
+                .unwrap_or_default(),
+            timeout: Duration::from_secs(
+                verify
+                    .timeout
+                    .or(profile.timeout)
+                    .or(config.timeout)
+                    .unwrap_or(runner.default_timeout()),
+            ),
+            jobs: verify
+                .jobs
+                .or(profile.jobs)
+                .or(config.jobs)
+                .unwrap_or(1)
+                .max(1),
+            isolated_targets: verify
+                .isolated_targets
+                .or(config.isolated_targets)
+                .unwrap_or_default(),
+            keep_targets: false,
+            frozen: verify.frozen.or(config.frozen).unwrap_or_default(),
+            doctests: verify
+                .doctests
+                .or(profile.doctests)
+                .or(config.doctests)
+                .unwrap_or(true),
+            include_ignored: verify
+                .include_ignored
+                .or(config.include_ignored)
+                .unwrap_or_default(),
+            timing: verify.timing.or(config.timing).unwrap_or_default(),
+            coverage,
+            runner,
+            oracle,
+            env,
+            rustflags,
+            toolchain: verify.toolchain.or(config.toolchain.clone()),
+            exclude,
+            files,
+            functions: cli.function,
+            operators: Some(cli.operator)
+                .filter(|operators| !operators.is_empty())
+                .or(profile.operators)
+                .or(config.operators)
+                .or(preset.map(|preset| preset.operators())),
+            excluded_operators: cli.exclude_operator,
+            preset,
+            visibility: cli.visibility.or(config.visibility).unwrap_or_default(),
+            line_ranges: cli.line_range,
+            mutants: vec![],
+            sample,
+            budget: cli.budget.or(config.budget),
+            order: cli.order.or(config.order).unwrap_or_default(),
+            path_rules: config
+                .paths
+                .into_iter()
+                .map(PathRule::try_from)
+                .collect::<eyre::Result<_>>()?,
+            test_attributes,
+            follow_symlinks: cli.follow_symlinks || config.follow_symlinks.unwrap_or_default(),
+            mutate_macro_args: cli.mutate_macro_args
+                || config.mutate_macro_args.unwrap_or_default(),
+            mutate_literals: cli.mutate_literals || config.mutate_literals.unwrap_or_default(),
+            plugins,
+            hooks: Hooks {
+                pre_mutant: verify.pre_mutant.or(config.hooks.pre_mutant),
+                post_mutant: verify.post_mutant.or(config.hooks.post_mutant),
+                post_run: verify.post_run.or(config.hooks.post_run),
+            },
+            weakest: verify
+                .weakest
+                .or(config.weakest)
+                .unwrap_or(DEFAULT_WEAKEST_FILES),
+            message_format: verify
+                .message_format
+                .or(config.message_format)
+                .unwrap_or_default(),
+            sort: verify.sort.or(config.sort).unwrap_or_default(),
+            notify_webhook: verify.notify_webhook.or(config.notify_webhook),
+            fail_on_skip: Some(verify.fail_on_skip)
+                .filter(|reasons| !reasons.is_empty())
+                .or(config.fail_on_skip)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Settings of a new run, whose mutant projects and reports are written in its own folder
+    pub(crate) fn new_run(&self) -> eyre::Result<Settings> {
+        Ok(Settings {
+            mutation_path: start_run(&self.mutation_root)?,
+            ..self.clone()
+        })
+    }
+
+    /// Only verify the mutants with these IDs and the ones whose IDs are listed in a file
+    pub(crate) fn select_mutants(
+        &mut self,
+        mut ids: Vec<String>,
+        file: Option<&Path>,
+    ) -> eyre::Result<()> {
+        if let Some(file) = file {
+            let content = std::fs::read_to_string(file)
+                .wrap_err(eyre!("Unable to read mutants file {}", file.display()))?;
+            ids.extend(parse_mutants_file(&content));
+        }
+        self.mutants = ids;
+        Ok(())
+    }
+
+    /// Whether a mutant is selected by its numeric or stable ID
+    pub(crate) fn mutant_selected(&self, id: usize, stable_id: &str) -> bool {
+        self.mutants.is_empty()
+            || self
+                .mutants
+                .iter()
+                .any(|selected| *selected == id.to_string() || selected == stable_id)
+    }
+
+    /// Whether an operator family is selected
+    pub(crate) fn operator_selected(&self, operator: &str) -> bool {
+        self.operators
+            .as_ref()
+            .is_none_or(|operators| operators.iter().any(|name| name == operator))
+            && !self.excluded_operators.iter().any(|name| name == operator)
+    }
+
+    /// Whether the preset keeps the replacement of a mutant, all are kept without preset
+    pub(crate) fn replacement_selected(
+        &self,
+        operator: &str,
+        original: &str,
+        replacement: &str,
+    ) -> bool {
+        self.preset
+            .is_none_or(|preset| preset.replaces(operator, original, replacement))
+    }
+
+    /// Whether a line of a file, relative to the project root, is selected for mutation
+    pub(crate) fn line_selected(&self, relative_path: &Path, line: usize) -> bool {
+        self.line_ranges.is_empty()
+            || self
+                .line_ranges
+                .iter()
+                .any(|range| range.contains(relative_path, line))
+    }
+
+    /// Whether the path rules allow an operator family on a file relative to the project root
+    pub(crate) fn operator_allowed(&self, relative_path: &Path, operator: &str) -> bool {
+        !self
+            .path_rules
+            .iter()
+            .any(|rule| rule.forbids(relative_path, operator))
+    }
+
+    /// Whether a file, relative to the project root, is excluded from mutation
+    pub(crate) fn is_excluded(&self, relative_path: &Path) -> bool {
+        self.exclude.is_match(relative_path)
+            || (!self.files.is_empty() && !self.files.is_match(relative_path))
+    }
+
+    /// Whether a function is selected for mutation
+    pub(crate) fn function_selected(&self, function_name: &str) -> bool {
+        // Default methods of traits match by their name alone too
+        let method_name = function_name.rsplit("::").next();
+        self.functions.is_empty()
+            || self
+                .functions
+                .iter()
+                .any(|name| name == function_name || Some(name.as_str()) == method_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use clap::Parser;
+
+    use crate::actions::validate::{Package, Target};
+    use crate::cli::{parse_line_range, Cli, Keep, Preset, Runner, VerifyArgs, Warnings};
+    use crate::config::{
+        benchmark_files, find_compiler_cache, glob_set, parse_env_file, parse_mutants_file,
+        runs_benchmarks, Config, Manifest, PathRule, Settings,
+    };
+    use crate::scratch::ScratchFolder;
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            timeout = 30
+            jobs = 4
+            exclude = ["src/generated/**"]
+            generated = ["**/*.g.rs"]
+            operators = ["arithmetic"]
+            runner = "miri"
+            warnings = "deny"
+            toolchain = "nightly"
+            rustflags = ["--cfg", "mutant"]
+
+            [hooks]
+            pre_mutant = "docker compose up -d"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.timeout, Some(30));
+        assert_eq!(config.runner, Some(Runner::Miri));
+        assert_eq!(config.warnings, Some(Warnings::Deny));
+        assert_eq!(config.toolchain.as_deref(), Some("nightly"));
+        assert_eq!(config.rustflags, vec!["--cfg", "mutant"]);
+        assert_eq!(config.jobs, Some(4));
+        assert_eq!(config.exclude, vec!["src/generated/**".to_string()]);
+        assert_eq!(config.generated, Some(vec!["**/*.g.rs".to_string()]));
+        assert_eq!(config.operators, Some(vec!["arithmetic".to_string()]));
+        assert_eq!(
+            config.hooks.pre_mutant,
+            Some("docker compose up -d".to_string())
+        );
+    }
+
+    #[test]
+    fn test_package_metadata_override_workspace() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [package]
+            name = "toto"
+
+            [package.metadata.darwin]
+            timeout = 10
+
+            [workspace.metadata.darwin]
+            timeout = 20
+            jobs = 2
+            "#,
+        )
+        .unwrap();
+        assert!(manifest.is_workspace());
+        let manifest_bis: Manifest = toml::from_str(
+            r#"
+            [workspace.metadata.darwin]
+            timeout = 20
+            jobs = 2
+            "#,
+        )
+        .unwrap();
+        let config = manifest_bis
+            .workspace_config()
+            .unwrap()
+            .merge(manifest.package_config().unwrap());
+        assert_eq!(config.timeout, Some(10));
+        assert_eq!(config.jobs, Some(2));
+    }
+
+    #[test]
+    fn test_from_manifests() {
+        let workspace = ScratchFolder::new("manifests");
+        let member = workspace.join("crates").join("a");
+        std::fs::create_dir_all(&member).unwrap();
+        std::fs::write(
+            workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/a\"]\n\n[workspace.metadata.darwin]\ntimeout = 20\njobs = 2\n",
+        )
+        .unwrap();
+        std::fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"a\"\n\n[package.metadata.darwin]\ntimeout = 10\n",
+        )
+        .unwrap();
+
+        let member_config = Config::from_manifests(&member).unwrap();
+        let workspace_config = Config::from_manifests(&workspace).unwrap();
+        // No manifest
+        let folder_config = Config::from_manifests(&workspace.join("crates")).unwrap();
+
+        assert_eq!(member_config.timeout, Some(10));
+        assert_eq!(member_config.jobs, Some(2));
+        assert_eq!(workspace_config.timeout, Some(20));
+        assert_eq!(workspace_config.jobs, Some(2));
+        assert_eq!(folder_config.timeout, None);
+    }
+
+    #[test]
+    fn test_path_rules() {
+        let config: Config = toml::from_str(
+            r#"
+            [[paths]]
+            glob = "src/generated/**"
+            disable = ["literal"]
+
+            [[paths]]
+            glob = "src/math/**"
+            operators = ["arithmetic"]
+            "#,
+        )
+        .unwrap();
+        let rules = config
+            .paths
+            .into_iter()
+            .map(|path| PathRule::try_from(path).unwrap())
+            .collect::<Vec<_>>();
+        let forbids = |path: &str, operator: &str| {
+            rules
+                .iter()
+                .any(|rule| rule.forbids(Path::new(path), operator))
+        };
+        assert!(forbids("src/generated/a.rs", "literal"));
+        assert!(!forbids("src/generated/a.rs", "arithmetic"));
+        assert!(forbids("src/math/a.rs", "literal"));
+        assert!(!forbids("src/math/a.rs", "arithmetic"));
+        assert!(!forbids("src/lib.rs", "literal"));
+    }
+
+    #[test]
+    fn test_profile() {
+        let config: Config = toml::from_str(
+            r#"
+            timeout = 60
+
+            [profile.quick]
+            sample = 0.2
+            timeout = 10
+            "#,
+        )
+        .unwrap();
+        let quick = config.profile("quick").unwrap();
+        assert_eq!(quick.sample, Some(0.2));
+        assert_eq!(quick.timeout, Some(10));
+        assert_eq!(quick.doctests, None);
+        assert_eq!(config.profile("thorough").unwrap().timeout, Some(300));
+        assert_eq!(
+            Config::default().profile("quick").unwrap().doctests,
+            Some(false)
+        );
+        assert!(config.profile("unknown").is_err());
+    }
+
+    #[test]
+    fn test_keep() {
+        let keep = |source: &str| {
+            toml::from_str::<Config>(source)
+                .unwrap()
+                .keep
+                .and_then(Option::<Keep>::from)
+        };
+        assert_eq!(keep(""), None);
+        assert_eq!(keep("keep = false"), None);
+        assert_eq!(keep("keep = true"), Some(Keep::All));
+        assert_eq!(keep("keep = \"missing\""), Some(Keep::Missing));
+        assert!(toml::from_str::<Config>("keep = \"none\"").is_err());
+    }
+
+    #[test]
+    fn test_preset() {
+        let preset = |source: &str| toml::from_str::<Config>(source).unwrap().preset;
+        assert_eq!(preset(""), None);
+        assert_eq!(preset("preset = \"pit\""), Some(Preset::Pit));
+        assert_eq!(
+            preset("preset = \"stryker-like\""),
+            Some(Preset::StrykerLike)
+        );
+        assert!(toml::from_str::<Config>("preset = \"stryker\"").is_err());
+
+        assert_eq!(Preset::Mutants.operators(), vec!["arithmetic"]);
+        assert_eq!(Preset::Pit.operators(), vec!["arithmetic", "literal"]);
+        assert_eq!(
+            Preset::StrykerLike.operators(),
+            vec!["arithmetic", "literal", "length"]
+        );
+        assert!(Preset::Mutants.replaces("arithmetic", "+", "*"));
+        assert!(!Preset::Mutants.replaces("arithmetic", "-", "&&"));
+        assert!(!Preset::Pit.replaces("arithmetic", "+", "*"));
+        assert!(Preset::StrykerLike.replaces("arithmetic", "-", "+"));
+        assert!(Preset::Pit.replaces("literal", "3", "0"));
+        assert!(!Preset::Pit.replaces("literal", "3", "4"));
+        assert!(Preset::Pit.replaces("literal", "true", "false"));
+        assert!(!Preset::StrykerLike.replaces("literal", "3", "0"));
+        assert!(Preset::StrykerLike.replaces("literal", "false", "true"));
+        assert!(Preset::StrykerLike.replaces("length", "3", "2"));
+        assert!(Preset::Pit.replaces("plugin", "a", "b"));
+    }
+
+    #[test]
+    fn test_preset_operators() {
+        let project = ScratchFolder::new("preset");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"preset\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::write(project.join("src/lib.rs"), "").unwrap();
+        let operators = |extra: &[&str]| {
+            let args = ["cargo", "darwin", "--preset", "pit"]
+                .into_iter()
+                .chain(extra.iter().copied())
+                .chain([project.to_str().unwrap()]);
+            let Cli::Darwin(darwin) = Cli::try_parse_from(args).unwrap();
+            Settings::new(darwin.run.project, VerifyArgs::default())
+                .unwrap()
+                .operators
+        };
+
+        let preset = operators(&[]);
+        let cli = operators(&["--operator", "length"]);
+        std::fs::write(project.join("darwin.toml"), "operators = [\"variant\"]\n").unwrap();
+        let config = operators(&[]);
+
+        assert_eq!(
+            preset,
+            Some(vec!["arithmetic".to_string(), "literal".to_string()])
+        );
+        assert_eq!(cli, Some(vec!["length".to_string()]));
+        assert_eq!(config, Some(vec!["variant".to_string()]));
+    }
+
+    #[test]
+    fn test_reject_unknown_key() {
+        assert!(toml::from_str::<Config>("timout = 30").is_err());
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let env = parse_env_file(
+            r#"
+            # Database of the tests
+            DATABASE_URL=postgres://localhost/test?sslmode=disable
+            export TOKEN="secret"
+            EMPTY=
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            env,
+            vec![
+                (
+                    "DATABASE_URL".to_string(),
+                    "postgres://localhost/test?sslmode=disable".to_string()
+                ),
+                ("TOKEN".to_string(), "secret".to_string()),
+                ("EMPTY".to_string(), "".to_string()),
+            ]
+        );
+        assert!(parse_env_file("NO_VALUE").is_err());
+    }
+
+    #[test]
+    fn test_parse_mutants_file() {
+        let ids = parse_mutants_file(
+            "# Survivors of last night\n3\n\nsrc-lib-add-5-7-sub : replace + by -\n",
+        );
+        assert_eq!(ids, vec!["3", "src-lib-add-5-7-sub"]);
+    }
+
+    #[test]
+    fn test_parse_line_range() {
+        let range = parse_line_range("src/lib.rs:10-20").unwrap();
+        assert!(range.contains(Path::new("src/lib.rs"), 10));
+        assert!(range.contains(Path::new("src/lib.rs"), 20));
+        assert!(!range.contains(Path::new("src/lib.rs"), 21));
+        assert!(!range.contains(Path::new("src/main.rs"), 15));
+        assert!(parse_line_range("src/lib.rs:20-10").is_err());
+        assert!(parse_line_range("src/lib.rs:10").is_err());
+        assert!(parse_line_range("10-20").is_err());
+    }
+
+    #[test]
+    fn test_benchmark_files() {
+        let package = |manifest_path: &str, benches: &[&str]| Package {
+            name: "a".to_string(),
+            manifest_path: PathBuf::from(manifest_path),
+            dependencies: vec![],
+            targets: benches
+                .iter()
+                .map(|src_path| Target {
+                    kind: vec!["bench".to_string()],
+                    src_path: PathBuf::from(src_path),
+                })
+                .chain([Target {
+                    kind: vec!["lib".to_string()],
+                    src_path: PathBuf::from("/ws/src/lib.rs"),
+                }])
+                .collect(),
+        };
+        let root = package("/ws/Cargo.toml", &["/ws/perf/main.rs"]);
+        let member = package("/ws/crates/[a]/Cargo.toml", &[]);
+        let globs = benchmark_files(&[&root, &member], Path::new("/ws"));
+        assert_eq!(
+            globs,
+            vec!["benches/**", "perf/main.rs", "crates/[[]a[]]/benches/**"]
+        );
+        let exclude = glob_set(globs.iter()).unwrap();
+        assert!(exclude.is_match("benches/parse.rs"));
+        assert!(exclude.is_match("crates/[a]/benches/common/mod.rs"));
+        assert!(exclude.is_match("perf/main.rs"));
+        assert!(!exclude.is_match("src/benches/mod.rs"));
+        assert!(!exclude.is_match("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_runs_benchmarks() {
+        assert!(runs_benchmarks("cargo bench"));
+        assert!(runs_benchmarks(
+            "./build.sh && ~/.cargo/bin/cargo +nightly bench -- a"
+        ));
+        assert!(!runs_benchmarks("cargo test --benches"));
+        assert!(!runs_benchmarks("./scripts/bench.sh"));
+    }
+
+    #[test]
+    fn test_find_compiler_cache() {
+        // The test binary stands for the compiler cache
+        let executable = std::env::current_exe().unwrap();
+        let folder = executable.parent().unwrap();
+        let file_name = executable.file_name().unwrap().to_str().unwrap();
+        let name = executable.file_stem().unwrap().to_str().unwrap();
+        let path = Some(std::env::join_paths(["/nowhere", folder.to_str().unwrap()]).unwrap());
+        assert_eq!(
+            find_compiler_cache(Path::new("/project"), name, path.clone()).unwrap(),
+            executable
+        );
+        assert_eq!(
+            find_compiler_cache(folder, &format!("./{file_name}"), None).unwrap(),
+            folder.join(format!("./{file_name}"))
+        );
+        assert!(find_compiler_cache(Path::new("/project"), "no-such-cache", path).is_err());
+    }
+}
@@ -13,13 +13,13 @@
 //! [Timeout] : Mutation introduces infinite loop, inconclusive
 //! [Killed]  : Mutation introduces non buildable modification
 //!   ---
-//! [OK] : Mutation #0 replace - by + in function "sub" of file src\a\toto.rs at line 11:6
-//! [OK] : Mutation #1 replace - by * in function "sub" of file src\a\toto.rs at line 11:6
-//! [Killed] : Mutation #2 replace - by && in function "sub" of file src\a\toto.rs at line 11:6
-//! [Missing] : Mutation #3 replace + by - in function "add" of file src\lib.rs at line 5:6
-//! [Missing] : Mutation #4 replace + by * in function "add" of file src\lib.rs at line 5:6
-//! [Missing] : Mutation #5 replace + by - in function "add" of file src\lib.rs at line 5:10
-//! [Missing] : Mutation #6 replace + by * in function "add" of file src\lib.rs at line 5:10
+//! [Missing] : Mutation #3 replace + by - in function "add" of file src/lib.rs at line 5:6
+//! [Missing] : Mutation #4 replace + by * in function "add" of file src/lib.rs at line 5:6
+//! [Missing] : Mutation #5 replace + by - in function "add" of file src/lib.rs at line 5:10
+//! [Missing] : Mutation #6 replace + by * in function "add" of file src/lib.rs at line 5:10
+//! [OK] : Mutation #0 replace - by + in function "sub" of file src/a/toto.rs at line 11:6
+//! [OK] : Mutation #1 replace - by * in function "sub" of file src/a/toto.rs at line 11:6
+//! [Killed] : Mutation #2 replace - by && in function "sub" of file src/a/toto.rs at line 11:6
 //! ```
 //!
 //! ## Details
@@ -64,7 +64,7 @@
 //! - project tests pass : the project is inefficiently tested as the mutation isn't catch
 //! - tests fail : the project has at least one test which catches the mutation
 //! - timeout : the mutation even if compiles, introduce a loop or something that makes the test run
-//! forever
+//!   forever
 //!
 //! ### Reports
 //!
@@ -96,16 +96,17 @@
 //!
 //! #### Summary
 //!
-//! Summarize the mutation applied and the result of each.
+//! Summarize the mutation applied and the result of each, the missing mutants first, then the
+//! timeouts, the caught mutants and the killed ones, each by file and line.
 //!
 //! ```bash
-//! [OK] : Mutation #0 replace - by + in function "sub" of file src\a\toto.rs at line 11:6
-//! [OK] : Mutation #1 replace - by * in function "sub" of file src\a\toto.rs at line 11:6
-//! [Killed] : Mutation #2 replace - by && in function "sub" of file src\a\toto.rs at line 11:6
-//! [Missing] : Mutation #3 replace + by - in function "add" of file src\lib.rs at line 5:6
-//! [Missing] : Mutation #4 replace + by * in function "add" of file src\lib.rs at line 5:6
-//! [Missing] : Mutation #5 replace + by - in function "add" of file src\lib.rs at line 5:10
-//! [Missing] : Mutation #6 replace + by * in function "add" of file src\lib.rs at line 5:10
+//! [Missing] : Mutation #3 replace + by - in function "add" of file src/lib.rs at line 5:6
+//! [Missing] : Mutation #4 replace + by * in function "add" of file src/lib.rs at line 5:6
+//! [Missing] : Mutation #5 replace + by - in function "add" of file src/lib.rs at line 5:10
+//! [Missing] : Mutation #6 replace + by * in function "add" of file src/lib.rs at line 5:10
+//! [OK] : Mutation #0 replace - by + in function "sub" of file src/a/toto.rs at line 11:6
+//! [OK] : Mutation #1 replace - by * in function "sub" of file src/a/toto.rs at line 11:6
+//! [Killed] : Mutation #2 replace - by && in function "sub" of file src/a/toto.rs at line 11:6
 //! ```
 //!
 //! For more information about the mutation, check the associated mutation_ID.log file
@@ -123,7 +124,7 @@
 //! Below an example of output
 //!
 //! ```log
-//! Mutation of file F:\Projets\Lab\Rust\darwin\playground\src\a\toto.rs
+//! Mutation of file src/a/toto.rs
 //! Mutation reason: replace - by *
 //! Status : OK => Mutation Caught
 //! Mutation diff:
@@ -175,18 +176,64 @@
 //!
 //! As a test has failed, the mutation has been caught, so the code is enough tested for this particular mutation
 //!
-use std::fs;
-
 use clap::Parser;
+use eyre::WrapErr;
+use std::ffi::OsString;
+use std::time::Duration;
 
-use actions::{analyze, generate, reporting};
-use cli::{Cli, Darwin};
+use actions::hooks::run_hook;
+use actions::notify::Notification;
+use actions::{
+    analyze, annotate, apply, capture, generate, init, list, mutants_out, notify, rerun, serve,
+    show, single, sonarqube, stryker, warnings_ng, watch,
+};
+use cli::{Cli, Command, ExportFormat, RunArgs, VerifyArgs};
+use config::Settings;
 use mutation::Mutation;
+use report::MutationStatus;
 
 mod actions;
 mod cli;
+mod config;
 mod mutation;
+mod plugin;
 mod report;
+#[cfg(test)]
+mod scratch;
+
+/// Verdict of a command reported by the exit code of darwin, returned as the error of [`run`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// The mutant checked by `verify` isn't caught by the tests
+    Missing,
+    /// Mutants or files were left out of the run for a reason given to `--fail-on-skip`
+    Skipped,
+    /// The run was interrupted by Ctrl-C
+    Interrupted,
+}
+
+impl Exit {
+    /// Exit code of the verdict, `1` being left to errors
+    pub fn code(&self) -> i32 {
+        match self {
+            Exit::Missing => 2,
+            Exit::Skipped => 3,
+            Exit::Interrupted => 130,
+        }
+    }
+}
+
+impl std::fmt::Display for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Exit::Missing => write!(f, "The mutant isn't caught by the tests"),
+            Exit::Skipped => write!(f, "Mutants or files were left out of the run"),
+            Exit::Interrupted => write!(f, "The run was interrupted"),
+        }
+    }
+}
+
+impl std::error::Error for Exit {}
 
 /// Display mutation but don't run tests
 fn display_mutations(mutations: &Vec<Mutation>) -> eyre::Result<()> {
@@ -198,25 +245,198 @@ fn display_mutations(mutations: &Vec<Mutation>) -> eyre::Result<()> {
 
 /// Main darwin function
 pub fn run() -> eyre::Result<()> {
-    let cli = Cli::parse();
+    run_cli(Cli::parse())
+}
+
+/// Run darwin with the given command line, the first argument being the program name, as
+/// `["cargo-darwin", "darwin", "--mutation-path", "/tmp/darwin", "/path/to/project"]`
+///
+/// Unlike [`run`], invalid arguments, `--help` and `--version` are returned as errors.
+pub fn run_from<I, T>(args: I) -> eyre::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    run_cli(Cli::try_parse_from(args)?)
+}
+
+fn run_cli(cli: Cli) -> eyre::Result<()> {
+    let Cli::Darwin(darwin) = cli;
+
+    match darwin.command {
+        Some(Command::Init(args)) => init::init(&args.root_path, args.force),
+        Some(Command::Completions(args)) => {
+            cli::completions(args.shell);
+            Ok(())
+        }
+        Some(Command::List(args)) => {
+            let settings = Settings::new(args.project, VerifyArgs::default())?;
+            let plugins = plugin::load_plugins(&settings.plugins)?;
+            let mutants = analyze::analyze(&settings, &plugins)?.mutants;
+            list::list(&mutants, args.format, args.diff)
+        }
+        Some(Command::Show(args)) => {
+            let settings = Settings::new(args.project, VerifyArgs::default())?;
+            show::show(&settings.mutation_path, &args.mutant)
+        }
+        Some(Command::Apply(args)) => {
+            let settings = Settings::new(args.project, VerifyArgs::default())?;
+            apply::apply(
+                &settings.root_path,
+                &settings.mutation_path,
+                &args.mutant,
+                args.revert,
+                args.yes,
+                args.allow_dirty,
+            )
+        }
+        Some(Command::Annotate(args)) => {
+            let settings = Settings::new(args.project, VerifyArgs::default())?;
+            let output = args
+                .output
+                .unwrap_or_else(|| settings.mutation_path.join("annotated"));
+            annotate::annotate(
+                &settings.root_path,
+                &settings.member_path,
+                &settings.mutation_path,
+                &output,
+                args.patch,
+            )
+        }
+        Some(Command::Export(args)) => {
+            let settings = Settings::new(args.project, VerifyArgs::default())?;
+            let folder = match args.format {
+                ExportFormat::MutantsOut => "mutants.out",
+                ExportFormat::Stryker => "stryker",
+                ExportFormat::Sonarqube => "sonarqube",
+                ExportFormat::WarningsNg => "warnings-ng",
+            };
+            let output = args
+                .output
+                .unwrap_or_else(|| settings.mutation_path.join(folder));
+            match args.format {
+                ExportFormat::MutantsOut => mutants_out::export(&settings.mutation_path, &output),
+                ExportFormat::Stryker => {
+                    stryker::export(&settings.root_path, &settings.mutation_path, &output)
+                }
+                ExportFormat::Sonarqube => sonarqube::export(&settings.mutation_path, &output),
+                ExportFormat::WarningsNg => warnings_ng::export(&settings.mutation_path, &output),
+            }
+        }
+        Some(Command::Import(args)) => {
+            let settings = Settings::new(args.project, VerifyArgs::default())?;
+            mutants_out::import(
+                &args.mutants_out,
+                &settings.root_path,
+                &settings.mutation_root,
+                settings.keep_reports,
+                settings.sort,
+            )
+        }
+        Some(Command::Rerun(args)) => {
+            let settings = Settings::new(args.project, args.verify)?;
+            rerun::rerun(&args.mutants, args.missing, &settings)
+        }
+        Some(Command::Verify(args)) => {
+            let mut settings = Settings::new(args.project, args.verify)?;
+            // A single mutant, other workers would only warm their target folder
+            settings.jobs = 1;
+            let plugins = plugin::load_plugins(&settings.plugins)?;
+            let result = single::verify_single(
+                &args.position,
+                args.replacement.as_deref(),
+                &settings,
+                &plugins,
+            )?;
+            println!(
+                "{} Mutant {}",
+                result.status.pretty(),
+                result.mutation.stable_id
+            );
+            if !result.caught_by.is_empty() {
+                println!("Caught by: {}", result.caught_by.join(", "));
+            }
+            if result.status == MutationStatus::Success {
+                return Err(Exit::Missing.into());
+            }
+            Ok(())
+        }
+        Some(Command::Watch(args)) => {
+            let settings = Settings::new(args.project, args.verify)?;
+            let plugins = plugin::load_plugins(&settings.plugins)?;
+            watch::watch(&settings, &plugins, Duration::from_secs(args.interval))
+        }
+        Some(Command::Serve(args)) => {
+            let settings = Settings::new(args.project, args.verify)?;
+            let plugins = plugin::load_plugins(&settings.plugins)?;
+            serve::serve(&settings, &plugins, &args.listen)
+        }
+        None => run_mutations(darwin.run),
+    }
+}
 
-    let Cli::Darwin(Darwin {
-        mutation_path,
-        root_path,
-        dry_run,
-        keep,
-    }) = cli;
+/// Analyze, mutate and verify the project
+fn run_mutations(args: RunArgs) -> eyre::Result<()> {
+    let mut settings = Settings::new(args.project, args.verify)?;
+    settings.select_mutants(args.mutants, args.mutants_file.as_deref())?;
+    let root_path = &settings.root_path;
+    let mutation_path = &settings.mutation_path;
 
-    let root_path = fs::canonicalize(root_path)?;
-    let mut mutants = analyze::analyze(&root_path)?;
+    let plugins = plugin::load_plugins(&settings.plugins)?;
 
-    if !dry_run {
+    if !settings.dry_run {
         println!("{}---", cli::help());
-        generate::generate_and_verify_mutants(&mut mutants, &root_path, &mutation_path, keep)?;
-        reporting::generate_reports(&mutants, &mutation_path, &root_path)?;
+        // Commands of mutants run in process groups of their own, out of reach of Ctrl-C
+        let handled = ctrlc::set_handler(|| {
+            if capture::interrupt() {
+                std::process::exit(Exit::Interrupted.code());
+            }
+            println!(
+                "Interrupted, stopping the mutants being verified, Ctrl-C again exits at once"
+            );
+        });
+        // Installed by a previous run of the same process
+        if !matches!(handled, Ok(()) | Err(ctrlc::Error::MultipleHandlers)) {
+            handled.wrap_err("Unable to handle interruptions")?;
+        }
+        let results = generate::verify_project(&settings, &plugins).map_err(|error| {
+            if capture::interrupted() {
+                Exit::Interrupted.into()
+            } else {
+                error
+            }
+        });
+        if let Some(url) = &settings.notify_webhook {
+            let notification = match &results {
+                Ok(results) => Notification::finished(root_path, results),
+                Err(error) if error.downcast_ref::<Exit>().is_some() => {
+                    Notification::aborted(root_path, None)
+                }
+                Err(error) => Notification::aborted(root_path, Some(format!("{error:#}"))),
+            };
+            // The run itself went through, only its notification is lost
+            if let Err(error) = notify::notify(url, &notification) {
+                println!("{error:#}");
+            }
+        }
+        let results = results?;
+        run_hook(
+            "post_run",
+            &settings.hooks.post_run,
+            root_path,
+            &[
+                ("DARWIN_PROJECT_PATH", root_path.display().to_string()),
+                ("DARWIN_MUTATION_PATH", mutation_path.display().to_string()),
+            ],
+        )?;
+        let skipped = results.skipped_for(&settings.fail_on_skip);
+        if skipped > 0 {
+            println!("{skipped} mutants or files left out of the run, failing as asked by --fail-on-skip");
+            return Err(Exit::Skipped.into());
+        }
     } else {
         log::info!("Run Darwin in dry run");
-        display_mutations(&mutants)?;
+        display_mutations(&analyze::analyze(&settings, &plugins)?.mutants)?;
     }
 
     Ok(())
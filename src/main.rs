@@ -1,8 +1,11 @@
-use cargo_darwin::run;
+use cargo_darwin::{run, Exit};
 
 fn main() {
     env_logger::init();
     if let Err(report) = run() {
+        if let Some(exit) = report.downcast_ref::<Exit>() {
+            std::process::exit(exit.code());
+        }
         let _ = dbg!(report);
     }
 }
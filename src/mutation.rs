@@ -1,36 +1,54 @@
-use eyre::eyre;
-use std::fs::File;
-use std::io::Read;
+use eyre::{eyre, WrapErr};
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
 
 use crate::actions::reporting::sink::UnifiedColorDiff;
 use crate::report::MutationReport;
 
+/// Rough complexity of a function, functions with more branches, then more syntax nodes, are the
+/// most complex
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) struct Complexity {
+    /// Conditions, match arms, loops, `?` and lazy boolean operators
+    pub(crate) branches: usize,
+    /// Named syntax nodes
+    pub(crate) nodes: usize,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Mutation {
     mutation: String,
+    original: String,
     pub(crate) chunk: MutationChunk,
     pub(crate) reason: String,
-    mutated_file: Option<String>,
+    /// Content of the original file, shared by the mutants of the file
+    source: Option<Arc<str>>,
     file_path: Option<PathBuf>,
+    relative_path: Option<PathBuf>,
     mutation_project_path: Option<PathBuf>,
     report: Option<MutationReport>,
     pub(crate) function_name: String,
+    /// Whether the mutated function is declared `pub`
+    pub(crate) public: bool,
+    /// Complexity of the mutated function
+    pub(crate) complexity: Complexity,
+    pub(crate) operator: String,
+    /// Kind of the syntax node enclosing the mutated code, such as `let_declaration`
+    pub(crate) context: String,
+    /// Whether the mutated code may decide when a loop ends
+    pub(crate) loop_control: bool,
     id: usize,
+    /// Stable identifier made unique among mutants of files whose paths give the same slug
+    disambiguated_id: Option<String>,
 }
 
 impl Mutation {
     pub fn display(&self, pretty_diff: bool) -> eyre::Result<String> {
-        let file_path = self
-            .file_path
-            .as_ref()
-            .ok_or(eyre!("Mutated file not specified"))?;
-
-        let file_path_string = dunce::simplified(file_path)
-            .to_str()
-            .ok_or(eyre!("Unable to make a string from file_path"))?;
-        let mutated_file = format!("Mutation of file {}", file_path_string);
+        let mutated_file = format!("Mutation of file {}", self.relative_file()?);
 
         let mut mutation_status = "".to_string();
         if let Some(report) = &self.report {
@@ -41,18 +59,62 @@ impl Mutation {
         let reason = &self.reason;
         let reason_string = format!("Mutation reason: {reason}");
 
-        let mutated_content = self
-            .mutated_file
-            .as_ref()
-            .ok_or(eyre!("Mutation result missing"))?;
-        let mut file = File::open(file_path).unwrap();
-        let mut original_content = String::new();
-        file.read_to_string(&mut original_content)?;
-
-        let input = imara_diff::intern::InternedInput::new(
-            original_content.as_str(),
-            mutated_content.as_str(),
-        );
+        let diff = self.diff(pretty_diff)?;
+        let mutation_diff = format!("Mutation diff:\n{diff}");
+
+        let mut report_str = "".to_string();
+        if let Some(report) = &self.report {
+            let MutationReport {
+                stdout,
+                stderr,
+                warnings,
+                ..
+            } = report;
+            if !warnings.is_empty() {
+                report_str = format!("New warnings:\n{}\n", warnings.join("\n"));
+            }
+            report_str.push_str(&format!("stderr:\n{stderr}\nstdout:\n{stdout}--\n"));
+        }
+
+        Ok(format!(
+            "{mutated_file}\n{reason_string}\n{mutation_status}\n{mutation_diff}{report_str}"
+        ))
+    }
+
+    /// Description of the mutant written beside its kept project
+    pub(crate) fn manifest(&self) -> eyre::Result<String> {
+        let record = self.record()?;
+        let status = match &self.report {
+            Some(report) => report.status.name(),
+            None => "-",
+        };
+        Ok(format!(
+            "Mutation #{} {}\n\
+            {} in function \"{}\" of file {} at line {}:{}\n\
+            Original: {}\n\
+            Replacement: {}\n\
+            Status: {status}\n\
+            Mutation diff:\n{}",
+            record.id,
+            record.stable_id,
+            record.reason,
+            record.function,
+            record.file,
+            record.line,
+            record.column,
+            record.original,
+            record.replacement,
+            self.diff(false)?
+        ))
+    }
+
+    /// Unified diff between the original and the mutated file
+    pub(crate) fn diff(&self, pretty_diff: bool) -> eyre::Result<String> {
+        let original_content = self.source()?;
+        let mutated_content = self.get_mutated_file()?;
+
+        let input =
+            imara_diff::intern::InternedInput::new(original_content, mutated_content.as_str());
 
         let diff = if pretty_diff {
             imara_diff::diff(
@@ -68,71 +130,233 @@ impl Mutation {
             )
         };
 
-        let mutation_diff = format!("Mutation diff:\n{diff}");
-
-        let mut report_str = "".to_string();
-        if let Some(report) = &self.report {
-            let MutationReport {
-                stdout,
-                stderr,
-                status: _status,
-            } = report;
-            report_str = format!("stderr:\n{stderr}\nstdout:\n{stdout}--\n");
-        }
-
-        Ok(format!(
-            "{mutated_file}\n{reason_string}\n{mutation_status}\n{mutation_diff}{report_str}"
-        ))
+        Ok(diff)
     }
 
-    fn get_details(&self, project_path: &PathBuf) -> eyre::Result<String> {
+    fn get_details(&self) -> eyre::Result<String> {
         let details = format!(
             "Mutation #{} {} in function \"{}\" of file {} at line {}:{}",
             &self.id,
             &self.reason,
             &self.function_name,
-            dunce::simplified(self.get_file_path()?.strip_prefix(project_path)?).display(),
+            self.relative_file()?,
             self.chunk.start_point.row + 1,
-            self.chunk.start_point.column
+            self.chunk.start_point.column + 1
         );
         Ok(details)
     }
 
-    pub(crate) fn pretty(&self, project_path: &PathBuf) -> eyre::Result<()> {
-        let details = self.get_details(project_path)?;
+    pub(crate) fn pretty(&self) -> eyre::Result<()> {
+        let details = self.get_details()?;
 
-        let status = self
-            .report
-            .as_ref()
-            .ok_or(eyre!("No report defined"))?
-            .pretty();
+        let report = self.report.as_ref().ok_or(eyre!("No report defined"))?;
+        let status = report.pretty();
+        let errors = report.error_codes();
 
-        println!("{status} : {details}");
+        let mut line = format!("{status} : {details}");
+        if !errors.is_empty() {
+            line.push_str(&format!(" ({})", errors.join(", ")));
+        }
+        if let Some(timeout) = &report.timeout {
+            line.push_str(&format!(", {}", timeout.hint(report.status)));
+        }
+        println!("{line}");
 
         Ok(())
     }
+}
 
-    pub(crate) fn simple(&self, project_path: &PathBuf) -> eyre::Result<String> {
-        let details = self.get_details(project_path)?;
+/// Serializable description of a mutation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MutationRecord {
+    pub(crate) id: usize,
+    pub(crate) stable_id: String,
+    /// Path relative to the project root, with forward slashes
+    pub(crate) file: String,
+    pub(crate) function: String,
+    pub(crate) operator: String,
+    pub(crate) reason: String,
+    /// 1-based line of the mutated chunk start
+    pub(crate) line: usize,
+    /// 1-based column of the mutated chunk start, in bytes
+    pub(crate) column: usize,
+    /// 1-based line of the mutated chunk end
+    #[serde(default)]
+    pub(crate) end_line: usize,
+    /// 1-based column of the mutated chunk end, exclusive, in bytes
+    #[serde(default)]
+    pub(crate) end_column: usize,
+    /// Byte offset of the mutated chunk start
+    pub(crate) start: usize,
+    /// Byte offset of the mutated chunk end, exclusive
+    pub(crate) end: usize,
+    /// Code replaced by the mutation
+    pub(crate) original: String,
+    /// Code replacing the original one
+    pub(crate) replacement: String,
+    /// Kind of the syntax node enclosing the mutated code
+    #[serde(default)]
+    pub(crate) context: String,
+    /// Whether the mutated code may decide when a loop ends
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) loop_control: bool,
+}
 
-        let status = self
-            .report
-            .as_ref()
-            .ok_or(eyre!("No report defined"))?
-            .simple();
+impl Mutation {
+    /// Identifier of the mutation, stable across runs as long as the mutated code doesn't move
+    ///
+    /// Built from the file, the function, the position and the replacement,
+    /// for example `src-lib-add-5-7-sub`
+    pub(crate) fn stable_id(&self) -> eyre::Result<String> {
+        if let Some(id) = &self.disambiguated_id {
+            return Ok(id.clone());
+        }
+        Ok(stable_id(
+            self.get_relative_path()?,
+            &self.function_name,
+            self.chunk.start_point.row + 1,
+            self.chunk.start_point.column + 1,
+            &self.mutation,
+        ))
+    }
+
+    /// Rebuild a mutation from a record, against the current file content
+    ///
+    /// Fails if the mutated code has changed since the record was made
+    pub(crate) fn from_record(record: &MutationRecord, project_path: &Path) -> eyre::Result<Self> {
+        let file_path = project_path.join(&record.file);
+        let content = std::fs::read_to_string(&file_path)
+            .wrap_err(eyre!("Unable to read {}", file_path.display()))?;
+        if content.get(record.start..record.end) != Some(record.original.as_str()) {
+            return Err(eyre!(
+                "Mutant #{} is outdated, {} has changed since the run",
+                record.id,
+                record.file
+            ));
+        }
+
+        let mut mutation = Mutation::new(
+            &record.replacement,
+            MutationChunk::from_range(&content, record.start..record.end),
+        )
+        .with_reason(&record.reason)
+        .with_function_name(&record.function)
+        .with_operator(&record.operator)
+        .with_context(&record.context)
+        .with_loop_control(record.loop_control);
+        mutation.set_mutation_id(record.id);
+        mutation.set_file_path(&file_path, Path::new(&record.file));
+        mutation.disambiguated_id = Some(record.stable_id.clone());
+        mutation.mutate_file(&content.into());
+        Ok(mutation)
+    }
 
-        let result = format!("{status} : {details}");
+    pub(crate) fn record(&self) -> eyre::Result<MutationRecord> {
+        Ok(MutationRecord {
+            id: self.id,
+            stable_id: self.stable_id()?,
+            file: self.relative_file()?,
+            function: self.function_name.clone(),
+            operator: self.operator.clone(),
+            reason: self.reason.clone(),
+            line: self.chunk.start_point.row + 1,
+            column: self.chunk.start_point.column + 1,
+            end_line: self.chunk.end_point.row + 1,
+            end_column: self.chunk.end_point.column + 1,
+            start: self.chunk.start,
+            end: self.chunk.end,
+            original: self.original.clone(),
+            replacement: self.mutation.clone(),
+            context: self.context.clone(),
+            loop_control: self.loop_control,
+        })
+    }
+}
+
+/// Stable identifier of a mutant from its file relative to the project, function, 1-based
+/// position and replacement
+pub(crate) fn stable_id(
+    relative_path: &Path,
+    function_name: &str,
+    line: usize,
+    column: usize,
+    replacement: &str,
+) -> String {
+    let raw = format!(
+        "{}-{function_name}-{line}-{column}-{}",
+        relative_path.with_extension("").display(),
+        replacement_name(replacement)
+    );
+    slugify(&raw)
+}
+
+/// Append a short hash of their file to the stable IDs of mutants whose file path gives the same
+/// slug as another one, as `src/foo_bar.rs` and `src/foo/bar.rs`, so that IDs stay unique file names
+pub(crate) fn disambiguate_stable_ids(mutants: &mut [Mutation]) -> eyre::Result<()> {
+    let path_slug = |file: &str| slugify(&Path::new(file).with_extension("").to_string_lossy());
+    let mut files: HashMap<String, HashSet<String>> = HashMap::new();
+    for mutation in mutants.iter() {
+        let file = mutation.relative_file()?;
+        files.entry(path_slug(&file)).or_default().insert(file);
+    }
+    for mutation in mutants.iter_mut() {
+        let file = mutation.relative_file()?;
+        if files[&path_slug(&file)].len() > 1 {
+            mutation.disambiguated_id = Some(format!(
+                "{}-{:08x}",
+                mutation.stable_id()?,
+                fnv1a(file.as_bytes()) as u32
+            ));
+        }
+    }
+    Ok(())
+}
 
-        Ok(result)
+/// Short name of a replacement usable in identifiers
+fn replacement_name(replacement: &str) -> String {
+    match replacement {
+        "+" => "add".to_string(),
+        "-" => "sub".to_string(),
+        "*" => "mul".to_string(),
+        "/" => "div".to_string(),
+        "%" => "rem".to_string(),
+        "&&" => "and".to_string(),
+        "||" => "or".to_string(),
+        _ => {
+            // Told apart once slugified, as `n - 1` and `n + 1`
+            let spelled = replacement.replace(" + ", " add ").replace(" - ", " sub ");
+            let slug = slugify(&spelled);
+            if slug.is_empty() || slug.len() > 16 {
+                format!("{:08x}", fnv1a(replacement.as_bytes()) as u32)
+            } else {
+                slug
+            }
+        }
     }
 }
 
+/// Lowercase alphanumeric characters separated by single dashes
+fn slugify(raw: &str) -> String {
+    raw.to_lowercase()
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// FNV-1a hash, stable across platforms and Rust versions
+pub(crate) fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
 #[derive(Debug, PartialEq, Default)]
 pub(crate) struct MutationChunk {
     start: usize,
     end: usize,
     pub(crate) start_point: Point,
-    end_point: Point,
+    pub(crate) end_point: Point,
 }
 
 impl MutationChunk {
@@ -144,6 +368,16 @@ impl MutationChunk {
             ..Default::default()
         }
     }
+
+    /// Build a chunk from a byte range of the file content
+    pub(crate) fn from_range(content: &str, range: Range<usize>) -> Self {
+        MutationChunk {
+            start: range.start,
+            end: range.end,
+            start_point: Point::from_offset(content, range.start),
+            end_point: Point::from_offset(content, range.end),
+        }
+    }
 }
 
 impl<'a> From<tree_sitter::Node<'a>> for MutationChunk {
@@ -174,6 +408,18 @@ pub(crate) struct Point {
     pub(crate) column: usize,
 }
 
+impl Point {
+    /// Compute row and column of a byte offset, with the same convention as tree-sitter
+    fn from_offset(content: &str, offset: usize) -> Self {
+        let before = &content[..offset];
+        let row = before.matches('\n').count();
+        let column = before
+            .rfind('\n')
+            .map_or(offset, |index| offset - index - 1);
+        Point { row, column }
+    }
+}
+
 impl From<tree_sitter::Point> for Point {
     fn from(value: tree_sitter::Point) -> Self {
         Point {
@@ -187,14 +433,22 @@ impl Mutation {
     pub(crate) fn new<N: Into<MutationChunk>>(mutation_chunk: &str, node: N) -> Self {
         Mutation {
             mutation: String::from(mutation_chunk),
+            original: "".to_string(),
             chunk: node.into(),
             reason: "".to_string(),
-            mutated_file: None,
+            source: None,
             file_path: None,
+            relative_path: None,
             mutation_project_path: None,
             report: None,
             function_name: "".to_string(),
+            public: false,
+            complexity: Complexity::default(),
+            operator: "".to_string(),
+            context: "".to_string(),
+            loop_control: false,
             id: 0,
+            disambiguated_id: None,
         }
     }
 
@@ -212,32 +466,56 @@ impl Mutation {
         }
     }
 
-    pub(crate) fn mutate_file(&mut self, file: &String) {
-        let mut file_clone = file.clone();
-        let mutated_range = self.chunk.start..self.chunk.end;
-        // The mutation chunk as the same size as the mutated area
-        // we can swap the chunk in place
-        if self.mutation.len() == mutated_range.len() {
-            file_clone.replace_range(mutated_range.clone(), &self.mutation);
+    pub(crate) fn with_operator(self, operator: &str) -> Self {
+        Mutation {
+            operator: operator.to_string(),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_public(self, public: bool) -> Self {
+        Mutation { public, ..self }
+    }
+
+    pub(crate) fn with_complexity(self, complexity: Complexity) -> Self {
+        Mutation { complexity, ..self }
+    }
+
+    pub(crate) fn with_context(self, context: &str) -> Self {
+        Mutation {
+            context: context.to_string(),
+            ..self
         }
-        // The mutation chunk takes more or less place than the mutated area
-        // we have to recreate a new string
-        else {
-            let (start_part, end_part) = file_clone.split_at(self.chunk.end);
-            let mut start_part = String::from(start_part);
-            start_part.truncate(start_part.len() - mutated_range.len());
-            start_part.push_str(&self.mutation);
-            start_part.push_str(end_part);
-            file_clone = start_part;
+    }
+
+    pub(crate) fn with_loop_control(self, loop_control: bool) -> Self {
+        Mutation {
+            loop_control,
+            ..self
         }
+    }
 
-        self.mutated_file = Some(file_clone)
+    /// Define the original content of the mutated file
+    pub(crate) fn mutate_file(&mut self, file: &Arc<str>) {
+        self.original = file[self.chunk.start..self.chunk.end].to_string();
+        self.source = Some(file.clone());
     }
 
-    pub(crate) fn get_mutated_file(&self) -> eyre::Result<&String> {
-        self.mutated_file
-            .as_ref()
-            .ok_or(eyre!("No mutate file generated yet"))
+    /// Content of the original file
+    pub(crate) fn source(&self) -> eyre::Result<&str> {
+        self.source
+            .as_deref()
+            .ok_or(eyre!("No original file defined yet"))
+    }
+
+    /// Content of the file once mutated, built on demand rather than kept by each mutant
+    pub(crate) fn get_mutated_file(&self) -> eyre::Result<String> {
+        let source = self.source()?;
+        let mut mutated = String::with_capacity(source.len() + self.mutation.len());
+        mutated.push_str(&source[..self.chunk.start]);
+        mutated.push_str(&self.mutation);
+        mutated.push_str(&source[self.chunk.end..]);
+        Ok(mutated)
     }
 
     pub(crate) fn get_file_path(&self) -> eyre::Result<&PathBuf> {
@@ -252,18 +530,60 @@ impl Mutation {
             .ok_or(eyre!("No mutation project path defined yet"))
     }
 
-    pub(crate) fn set_file_path(&mut self, path: &PathBuf) {
-        self.file_path = Some(path.clone())
+    /// Temporary directory of the tests of the mutant, in the target directory of its project
+    pub(crate) fn get_scratch_path(&self) -> eyre::Result<PathBuf> {
+        Ok(self
+            .get_mutation_project_path()?
+            .join("target")
+            .join("darwin-scratch"))
     }
 
-    pub(crate) fn set_mutation_project_path(&mut self, path: &PathBuf) {
-        self.mutation_project_path = Some(path.clone())
+    pub(crate) fn get_relative_path(&self) -> eyre::Result<&PathBuf> {
+        self.relative_path
+            .as_ref()
+            .ok_or(eyre!("No mutation file path defined yet"))
+    }
+
+    /// Path of the mutated file relative to the project root, with `/` separators on every platform
+    pub(crate) fn relative_file(&self) -> eyre::Result<String> {
+        Ok(self
+            .get_relative_path()?
+            .to_string_lossy()
+            .replace('\\', "/"))
+    }
+
+    /// Define the mutated file, and its path relative to the project root
+    pub(crate) fn set_file_path(&mut self, path: &Path, relative_path: &Path) {
+        self.file_path = Some(path.to_path_buf());
+        self.relative_path = Some(relative_path.to_path_buf());
+    }
+
+    pub(crate) fn set_mutation_project_path(&mut self, path: &Path) {
+        self.mutation_project_path = Some(path.to_path_buf())
+    }
+
+    pub(crate) fn get_report(&self) -> Option<&MutationReport> {
+        self.report.as_ref()
+    }
+
+    /// Drop the output of the tests of the mutant, once written to its log
+    pub(crate) fn release_output(&mut self) {
+        if let Some(report) = &mut self.report {
+            report.release_output();
+        }
     }
 
     pub(crate) fn set_report(&mut self, report: MutationReport) {
         self.report = Some(report)
     }
 
+    /// Attach warnings to the report of the mutant, if any
+    pub(crate) fn set_warnings(&mut self, warnings: Vec<String>) {
+        if let Some(report) = &mut self.report {
+            report.warnings = warnings;
+        }
+    }
+
     pub(crate) fn set_mutation_id(&mut self, id: usize) {
         self.id = id
     }
@@ -271,42 +591,102 @@ impl Mutation {
     pub(crate) fn get_mutation_id(&self) -> usize {
         self.id
     }
+
+    /// Code replacing the original one
+    pub(crate) fn get_replacement(&self) -> &str {
+        &self.mutation
+    }
+
+    /// Byte range of the original code in the file
+    pub(crate) fn get_range(&self) -> Range<usize> {
+        self.chunk.start..self.chunk.end
+    }
+
+    /// Combination of operator, replacement and syntactic context, whose mutants build or not alike
+    pub(crate) fn viability_key(&self) -> String {
+        format!(
+            "{} {} -> {} in {}",
+            self.operator, self.original, self.mutation, self.context
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::mutation::{Mutation, MutationChunk};
+    use std::path::Path;
+
+    use crate::mutation::{disambiguate_stable_ids, fnv1a, Mutation, MutationChunk};
 
     #[test]
     fn test_mutation_in_place() {
         let file = r#"Hello, world"#.to_string();
         let mut mutation = Mutation::new("|", MutationChunk::new_chunk(5..6));
-        mutation.mutate_file(&file);
-        assert_eq!(
-            mutation.get_mutated_file().unwrap(),
-            &r#"Hello| world"#.to_string()
-        );
+        mutation.mutate_file(&file.as_str().into());
+        assert_eq!(mutation.get_mutated_file().unwrap(), r#"Hello| world"#);
     }
 
     #[test]
     fn test_mutation_insert() {
         let file = r#"Hello, world"#.to_string();
         let mut mutation = Mutation::new("|||", MutationChunk::new_chunk(5..6));
-        mutation.mutate_file(&file);
-        assert_eq!(
-            mutation.get_mutated_file().unwrap(),
-            &r#"Hello||| world"#.to_string()
-        );
+        mutation.mutate_file(&file.as_str().into());
+        assert_eq!(mutation.get_mutated_file().unwrap(), r#"Hello||| world"#);
     }
 
     #[test]
     fn test_let_assign() {
         let file = r#"let x = 666;"#.to_string();
         let mut mutation = Mutation::new("42", MutationChunk::new_chunk(8..11));
-        mutation.mutate_file(&file);
-        assert_eq!(
-            mutation.get_mutated_file().unwrap(),
-            &r#"let x = 42;"#.to_string()
-        );
+        mutation.mutate_file(&file.as_str().into());
+        assert_eq!(mutation.get_mutated_file().unwrap(), r#"let x = 42;"#);
+    }
+
+    #[test]
+    fn test_stable_id() {
+        let file = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}".to_string();
+        let mut mutation =
+            Mutation::new("-", MutationChunk::from_range(&file, 35..36)).with_function_name("add");
+        mutation.set_file_path(Path::new("/project/src/lib.rs"), Path::new("src/lib.rs"));
+        assert_eq!(mutation.stable_id().unwrap(), "src-lib-add-2-7-sub");
+
+        let mut mutation =
+            Mutation::new("a much longer replacement", MutationChunk::new_chunk(0..1))
+                .with_function_name("add");
+        mutation.set_file_path(Path::new("/project/src/lib.rs"), Path::new("src/lib.rs"));
+        assert_eq!(mutation.stable_id().unwrap(), "src-lib-add-1-1-5cfe80ee");
+
+        for (replacement, name) in [("y - 1", "y-sub-1"), ("y + 1", "y-add-1")] {
+            let mut mutation = Mutation::new(replacement, MutationChunk::from_range(&file, 37..38))
+                .with_function_name("add");
+            mutation.set_file_path(Path::new("/project/src/lib.rs"), Path::new("src/lib.rs"));
+            assert_eq!(
+                mutation.stable_id().unwrap(),
+                format!("src-lib-add-2-9-{name}")
+            );
+        }
+    }
+
+    #[test]
+    fn test_disambiguate_stable_ids() {
+        let file = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}".to_string();
+        let mut mutants = ["src/foo_bar.rs", "src/foo/bar.rs", "src/lib.rs"].map(|relative| {
+            let mut mutation = Mutation::new("-", MutationChunk::from_range(&file, 35..36))
+                .with_function_name("add");
+            mutation.set_file_path(&Path::new("/project").join(relative), Path::new(relative));
+            mutation
+        });
+        disambiguate_stable_ids(&mut mutants).unwrap();
+        let ids = mutants
+            .iter()
+            .map(|mutation| mutation.stable_id().unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(ids[0].starts_with("src-foo-bar-add-2-7-sub-"));
+        assert!(ids[1].starts_with("src-foo-bar-add-2-7-sub-"));
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!(ids[2], "src-lib-add-2-7-sub");
+        // The same file gets the same ID on every run
+        assert_eq!(ids[0].len(), "src-foo-bar-add-2-7-sub-".len() + 8);
+        assert!(ids[0].ends_with(&format!("{:08x}", fnv1a(b"src/foo_bar.rs") as u32)));
     }
 }
@@ -0,0 +1,274 @@
+//! External mutation operators loaded at runtime from dynamic libraries
+//!
+//! A plugin is a `cdylib` exporting the following C ABI functions, data is exchanged
+//! as nul terminated JSON strings
+//!
+//! ```c
+//! // Optional, name of the plugin used in logs and mutation reasons
+//! const char* darwin_plugin_name();
+//! // Receive the file path, its content and the mutable function bodies spans
+//! // `[{"function": "add", "start": 12, "end": 40}]`
+//! // return the mutations to apply
+//! // `[{"start": 20, "end": 21, "replacement": "-", "reason": "replace + by -"}]`
+//! char* darwin_plugin_mutate(const char* path, const char* source, const char* spans);
+//! // Release a string returned by `darwin_plugin_mutate`
+//! void darwin_plugin_free(char* data);
+//! ```
+use std::ffi::{c_char, CStr, CString};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use eyre::{eyre, WrapErr};
+use libloading::{Library, Symbol};
+use serde::{Deserialize, Serialize};
+
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type MutateFn = unsafe extern "C" fn(*const c_char, *const c_char, *const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// Mutable function body given to plugins
+#[derive(Debug, Serialize)]
+pub(crate) struct FunctionSpan {
+    pub(crate) function: String,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// Mutation returned by plugins
+#[derive(Debug, Deserialize)]
+pub(crate) struct PluginMutation {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) replacement: String,
+    pub(crate) reason: String,
+}
+
+impl PluginMutation {
+    pub(crate) fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Plugin {
+    name: String,
+    library: Library,
+    /// Files are analyzed in parallel, plugins aren't required to be thread safe
+    calls: Mutex<()>,
+}
+
+impl Plugin {
+    /// Load a plugin from a dynamic library path
+    pub(crate) fn load(path: &Path) -> eyre::Result<Self> {
+        log::info!("Load plugin {}", path.display());
+        // SAFETY: loading a library runs its initialisation code, plugins are
+        // explicitly provided by the user and trusted
+        let library =
+            unsafe { Library::new(path) }.wrap_err(eyre!("Unable to load plugin {path:?}"))?;
+
+        // SAFETY: the symbol signature is part of the documented plugin ABI, a non null name is a
+        // nul terminated string owned by the plugin
+        let name = unsafe {
+            library
+                .get::<NameFn>(b"darwin_plugin_name")
+                .ok()
+                .map(|name| name())
+                .filter(|name| !name.is_null())
+                .map(|name| CStr::from_ptr(name).to_string_lossy().to_string())
+        }
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+
+        let plugin = Plugin {
+            name,
+            library,
+            calls: Mutex::new(()),
+        };
+        // Check required symbols right away rather than on first file
+        plugin.mutate_fn()?;
+        plugin.free_fn()?;
+
+        Ok(plugin)
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn mutate_fn(&self) -> eyre::Result<Symbol<'_, MutateFn>> {
+        // SAFETY: the symbol signature is part of the documented plugin ABI
+        unsafe { self.library.get::<MutateFn>(b"darwin_plugin_mutate") }
+            .wrap_err(eyre!("Plugin {} has no darwin_plugin_mutate", self.name))
+    }
+
+    fn free_fn(&self) -> eyre::Result<Symbol<'_, FreeFn>> {
+        // SAFETY: the symbol signature is part of the documented plugin ABI
+        unsafe { self.library.get::<FreeFn>(b"darwin_plugin_free") }
+            .wrap_err(eyre!("Plugin {} has no darwin_plugin_free", self.name))
+    }
+
+    /// Ask the plugin for mutations of the given functions bodies
+    pub(crate) fn mutate(
+        &self,
+        path: &Path,
+        content: &str,
+        spans: &[FunctionSpan],
+    ) -> eyre::Result<Vec<PluginMutation>> {
+        let c_path = CString::new(path.to_string_lossy().as_bytes())?;
+        let c_content = CString::new(content)?;
+        let c_spans = CString::new(serde_json::to_string(spans)?)?;
+
+        let mutate = self.mutate_fn()?;
+        let free = self.free_fn()?;
+
+        let _call = self
+            .calls
+            .lock()
+            .map_err(|_| eyre!("Plugin {} panicked", self.name))?;
+        // SAFETY: all pointers are valid nul terminated strings living until the end of
+        // the call, the returned string is owned by the plugin and released by it
+        let response = unsafe {
+            let data = mutate(c_path.as_ptr(), c_content.as_ptr(), c_spans.as_ptr());
+            if data.is_null() {
+                return Ok(vec![]);
+            }
+            let response = CStr::from_ptr(data).to_string_lossy().to_string();
+            free(data);
+            response
+        };
+
+        let mutations: Vec<PluginMutation> = serde_json::from_str(&response)
+            .wrap_err(eyre!("Plugin {} returned malformed mutations", self.name))?;
+
+        // Only keep mutations inside the provided spans
+        let mutations = mutations
+            .into_iter()
+            .filter(|mutation| {
+                let valid = mutation.start <= mutation.end
+                    && spans
+                        .iter()
+                        .any(|span| span.start <= mutation.start && mutation.end <= span.end)
+                    && content.is_char_boundary(mutation.start)
+                    && content.is_char_boundary(mutation.end);
+                if !valid {
+                    log::warn!(
+                        "Plugin {} returned an invalid span {}..{}, ignored",
+                        self.name,
+                        mutation.start,
+                        mutation.end
+                    );
+                }
+                valid
+            })
+            .collect();
+
+        Ok(mutations)
+    }
+}
+
+/// Load all plugins provided by the user
+pub(crate) fn load_plugins(paths: &[PathBuf]) -> eyre::Result<Vec<Plugin>> {
+    paths.iter().map(|path| Plugin::load(path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::plugin::{FunctionSpan, Plugin};
+    use crate::scratch::ScratchFolder;
+
+    /// Replaces the first `+` of the file, and returns a mutation out of the function bodies
+    static PLUGIN: &str = r##"
+use std::ffi::{c_char, CStr, CString};
+
+#[no_mangle]
+pub extern "C" fn darwin_plugin_name() -> *const c_char {
+    b"swap\0".as_ptr() as *const c_char
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn darwin_plugin_mutate(
+    _path: *const c_char,
+    source: *const c_char,
+    _spans: *const c_char,
+) -> *mut c_char {
+    let source = CStr::from_ptr(source).to_str().unwrap();
+    let start = source.find('+').unwrap();
+    let mutations = format!(
+        r#"[{{"start": {start}, "end": {}, "replacement": "-", "reason": "replace + by -"}},
+            {{"start": 0, "end": 2, "replacement": "", "reason": "out of the functions"}}]"#,
+        start + 1
+    );
+    CString::new(mutations).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn darwin_plugin_free(data: *mut c_char) {
+    drop(CString::from_raw(data));
+}
+"##;
+
+    /// Build a plugin from its source, returns the path of the library
+    fn build_plugin(folder: &Path, name: &str, source: &str) -> PathBuf {
+        let source_path = folder.join(format!("{name}.rs"));
+        std::fs::write(&source_path, source).unwrap();
+        let library = folder.join(format!(
+            "{}{name}{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        ));
+        let status = std::process::Command::new("rustc")
+            .args(["--edition", "2021", "--crate-type", "cdylib", "-o"])
+            .arg(&library)
+            .arg(source_path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        library
+    }
+
+    #[test]
+    fn test_plugin() {
+        let folder = ScratchFolder::new("plugin");
+        let library = build_plugin(&folder, "swap", PLUGIN);
+
+        let content = "fn add(x: u8, y: u8) -> u8 {\n    x + y\n}\n";
+        let spans = [FunctionSpan {
+            function: "add".to_string(),
+            start: 27,
+            end: 40,
+        }];
+        let plugin = Plugin::load(&library).unwrap();
+        let name = plugin.name().to_string();
+        let mutations = plugin.mutate(Path::new("src/lib.rs"), content, &spans);
+        let missing = Plugin::load(&folder.join("missing.so"));
+        drop(plugin);
+
+        assert_eq!(name, "swap");
+        let mutations = mutations.unwrap();
+        // The mutation out of the functions bodies is dropped
+        assert_eq!(mutations.len(), 1);
+        assert_eq!(mutations[0].range(), 35..36);
+        assert_eq!(mutations[0].replacement, "-");
+        assert_eq!(mutations[0].reason, "replace + by -");
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_plugin_without_name() {
+        let folder = ScratchFolder::new("plugin-name");
+        let source = PLUGIN.replace(r#"b"swap\0".as_ptr() as *const c_char"#, "std::ptr::null()");
+        let library = build_plugin(&folder, "unnamed", &source);
+
+        // Named after its file when its name is null
+        assert_eq!(
+            Plugin::load(&library).unwrap().name(),
+            format!("{}unnamed", std::env::consts::DLL_PREFIX)
+        );
+    }
+}
@@ -1,11 +1,17 @@
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub(crate) enum MutationStatus {
+    #[serde(rename = "missing")]
     Success,
+    #[serde(rename = "ok")]
     Fail,
+    #[serde(rename = "timeout")]
     Timeout,
+    #[serde(rename = "killed")]
     CompilationFailed,
 }
 
@@ -20,61 +26,359 @@ impl Display for MutationStatus {
     }
 }
 
+impl MutationStatus {
+    pub(crate) fn simple(&self) -> String {
+        match self {
+            MutationStatus::Success => {
+                // Tests pass, the mutation hasn't been caught, suspicion of missing test
+                "[Missing]".to_string()
+            }
+            MutationStatus::Fail => {
+                // Tests failed, the mutation has been caught
+                "[OK]".to_string()
+            }
+            MutationStatus::Timeout => {
+                // Mutation introduces infinite loop, inconclusive
+                "[Timeout]".to_string()
+            }
+            MutationStatus::CompilationFailed => {
+                // Mutation introduces non compilable project
+                "[Killed]".to_string()
+            }
+        }
+    }
+
+    /// Colored label of the status, padded to the same width, as printed in the console
+    pub(crate) fn pretty(&self) -> String {
+        match self {
+            MutationStatus::Success => format!("{}", "[Missing]".yellow()),
+            MutationStatus::Fail => format!("{}", "[OK]     ".green()),
+            MutationStatus::Timeout => format!("{}", "[Timeout]".white()),
+            MutationStatus::CompilationFailed => format!("{}", "[Killed] ".white()),
+        }
+    }
+
+    /// Rank of the status by how much it calls for action: missing mutants first, then the
+    /// inconclusive timeouts, the caught mutants and last the ones which don't build
+    pub(crate) fn severity(&self) -> u8 {
+        match self {
+            MutationStatus::Success => 0,
+            MutationStatus::Timeout => 1,
+            MutationStatus::Fail => 2,
+            MutationStatus::CompilationFailed => 3,
+        }
+    }
+
+    /// Short lowercase name of the status, usable in scripts
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            MutationStatus::Success => "missing",
+            MutationStatus::Fail => "ok",
+            MutationStatus::Timeout => "timeout",
+            MutationStatus::CompilationFailed => "killed",
+        }
+    }
+}
+
+/// Why a mutant or a source file was left out of the run, never built nor tested
+#[derive(clap::ValueEnum, Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub(crate) enum SkipReason {
+    /// Function, operator, visibility, line or ID not selected
+    Filter,
+    /// Operator disabled on the file by the configuration
+    Config,
+    /// Identical to another mutant or leaving the code unchanged
+    Equivalent,
+    /// Combination of operator, replacement and context which never built
+    Unviable,
+    /// Function compiled in no test target
+    NotCompiled,
+    /// Function never used
+    DeadCode,
+    /// Code only executed by `#[ignore]` tests, which don't run
+    IgnoredTests,
+    /// Left out by `--sample`
+    Sample,
+    /// Left out by `--budget`
+    Budget,
+    /// Source file which can't be read, binary or not UTF-8
+    Unreadable,
+    /// Source file which isn't valid Rust
+    ParseFailure,
+    /// The `pre_mutant` hook failed
+    Hook,
+}
+
+impl SkipReason {
+    /// Lowercase name of the reason, as in the summary file
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            SkipReason::Filter => "filter",
+            SkipReason::Config => "config",
+            SkipReason::Equivalent => "equivalent",
+            SkipReason::Unviable => "unviable",
+            SkipReason::NotCompiled => "not compiled",
+            SkipReason::DeadCode => "dead code",
+            SkipReason::IgnoredTests => "ignored tests",
+            SkipReason::Sample => "sample",
+            SkipReason::Budget => "budget",
+            SkipReason::Unreadable => "unreadable",
+            SkipReason::ParseFailure => "parse failure",
+            SkipReason::Hook => "hook",
+        }
+    }
+
+    /// Whether darwin left the mutant out by itself, rather than following the selection of the
+    /// settings
+    pub(crate) fn automatic(&self) -> bool {
+        matches!(
+            self,
+            SkipReason::Equivalent
+                | SkipReason::Unviable
+                | SkipReason::NotCompiled
+                | SkipReason::DeadCode
+                | SkipReason::IgnoredTests
+                | SkipReason::Hook
+        )
+    }
+}
+
+/// Why the tests of a mutant ran past the timeout
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum TimeoutCause {
+    /// The mutated code decides when a loop ends
+    InfiniteLoop,
+    /// The mutated code controls no loop, the tests are just slow
+    Slow,
+}
+
+impl TimeoutCause {
+    /// Hint on a mutant, whose tests finished or not once given twice the timeout
+    pub(crate) fn hint(&self, status: MutationStatus) -> &'static str {
+        match (status, self) {
+            (MutationStatus::Timeout, TimeoutCause::InfiniteLoop) => "likely an infinite loop",
+            (MutationStatus::Timeout, TimeoutCause::Slow) => "likely slow tests",
+            _ => "tests only finished with twice the timeout",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) struct MutationReport {
     pub(crate) stdout: String,
     pub(crate) stderr: String,
     pub(crate) status: MutationStatus,
+    /// Duration of the tests, none if they didn't run
+    pub(crate) duration: Option<Duration>,
+    /// Duration of the build, none if not measured
+    pub(crate) build_duration: Option<Duration>,
+    /// Warnings of clippy which the project without mutation doesn't have
+    pub(crate) warnings: Vec<String>,
+    /// Errors of the compiler on killed mutants
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    /// Why the tests ran past the timeout, none if they finished within it
+    pub(crate) timeout: Option<TimeoutCause>,
+    /// Parsed from the output, which may be released once written
+    failed_tests: Vec<String>,
+    error_codes: Vec<String>,
 }
 
 impl MutationReport {
     pub(crate) fn new(stdout: String, stderr: String, status: MutationStatus) -> Self {
         MutationReport {
+            failed_tests: parse_failed_tests(&stdout),
+            error_codes: parse_error_codes(&stderr),
             stdout,
             stderr,
             status,
+            duration: None,
+            build_duration: None,
+            warnings: vec![],
+            diagnostics: vec![],
+            timeout: None,
         }
     }
 
+    pub(crate) fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub(crate) fn with_diagnostics(mut self, diagnostics: Vec<Diagnostic>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    pub(crate) fn with_timeout(mut self, timeout: TimeoutCause) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn with_build_duration(mut self, build_duration: Duration) -> Self {
+        self.build_duration = Some(build_duration);
+        self
+    }
+
+    /// Names of the tests reported as failed by `cargo test`
+    pub(crate) fn failed_tests(&self) -> Vec<String> {
+        self.failed_tests.clone()
+    }
+
+    /// Codes of the compiler errors, such as `E0308`, in order of first appearance
+    pub(crate) fn error_codes(&self) -> Vec<String> {
+        self.error_codes.clone()
+    }
+
+    /// Drop the output of the commands once written to the log of the mutant
+    pub(crate) fn release_output(&mut self) {
+        self.stdout = String::new();
+        self.stderr = String::new();
+    }
+
     pub(crate) fn pretty(&self) -> String {
-        match self.status {
-            MutationStatus::Success => {
-                // Tests pass, the mutation hasn't been caught, suspicion of missing test
-                format!("{}", "[Missing]".yellow())
-            }
-            MutationStatus::Fail => {
-                // Tests failed, the mutation has been caught
-                format!("{}", "[OK]     ".green())
-            }
-            MutationStatus::Timeout => {
-                // Mutation introduces infinite loop, inconclusive
-                format!("{}", "[Timeout]".white())
-            }
-            MutationStatus::CompilationFailed => {
-                // Mutation introduces non compilable project
-                format!("{}", "[Killed] ".white())
-            }
-        }
+        self.status.pretty()
     }
+}
+
+/// Diagnostic of the compiler, from the JSON messages of cargo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Diagnostic {
+    /// `error` or `warning`
+    pub(crate) level: String,
+    /// Error code or lint, such as `E0308` or `clippy::eq_op`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) code: Option<String>,
+    pub(crate) message: String,
+    /// File of the primary span, relative to the workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) column: Option<usize>,
+    /// Human-readable text, as cargo prints it without JSON
+    #[serde(skip)]
+    pub(crate) rendered: String,
+}
 
+impl Diagnostic {
+    /// One line description, `code: message at file:line`
     pub(crate) fn simple(&self) -> String {
-        match self.status {
-            MutationStatus::Success => {
-                // Tests pass, the mutation hasn't been caught, suspicion of missing test
-                format!("{}", "[Missing]")
-            }
-            MutationStatus::Fail => {
-                // Tests failed, the mutation has been caught
-                format!("{}", "[OK]")
-            }
-            MutationStatus::Timeout => {
-                // Mutation introduces infinite loop, inconclusive
-                format!("{}", "[Timeout]")
-            }
-            MutationStatus::CompilationFailed => {
-                // Mutation introduces non compilable project
-                format!("{}", "[Killed]")
+        let mut line = match &self.code {
+            Some(code) => format!("{code}: {}", self.message),
+            None => self.message.clone(),
+        };
+        if let (Some(file), Some(line_number)) = (&self.file, self.line) {
+            line.push_str(&format!(" at {file}:{line_number}"));
+        }
+        line
+    }
+}
+
+/// Diagnostics of the compiler in the JSON messages of cargo
+///
+/// Summaries such as `aborting due to 2 previous errors`, without code nor location, are left out.
+pub(crate) fn parse_diagnostics(messages: &str) -> Vec<Diagnostic> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-message")
+        .filter_map(|message| {
+            let message = &message["message"];
+            let span = message["spans"]
+                .as_array()
+                .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true));
+            let code = message["code"]["code"].as_str().map(String::from);
+            if code.is_none() && span.is_none() {
+                return None;
             }
+            let number = |key: &str| {
+                span.and_then(|span| span[key].as_u64())
+                    .map(|number| number as usize)
+            };
+            Some(Diagnostic {
+                level: message["level"].as_str()?.to_string(),
+                code,
+                message: message["message"].as_str()?.to_string(),
+                file: span.and_then(|span| Some(span["file_name"].as_str()?.to_string())),
+                line: number("line_start"),
+                column: number("column_start"),
+                rendered: message["rendered"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Names of the tests reported as failed in the output of `cargo test`
+fn parse_failed_tests(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("test ")?
+                .strip_suffix(" ... FAILED")
+                .map(String::from)
+        })
+        .collect()
+}
+
+/// Codes of the compiler errors of the output of cargo, in order of first appearance
+fn parse_error_codes(stderr: &str) -> Vec<String> {
+    let mut codes: Vec<String> = vec![];
+    for line in stderr.lines() {
+        let Some(code) = line
+            .strip_prefix("error[")
+            .and_then(|line| line.split_once("]:"))
+            .map(|(code, _)| code)
+        else {
+            continue;
+        };
+        if !codes.iter().any(|known| known == code) {
+            codes.push(code.to_string());
         }
     }
+    codes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::{parse_diagnostics, MutationReport, MutationStatus};
+
+    #[test]
+    fn test_error_codes() {
+        let report = MutationReport::new(
+            "".to_string(),
+            r#"   Compiling playground v0.1.0
+error[E0308]: mismatched types
+ --> src/lib.rs:2:5
+error[E0277]: cannot add `bool` to `u8`
+error[E0308]: mismatched types
+error: could not compile `playground` due to 3 previous errors"#
+                .to_string(),
+            MutationStatus::CompilationFailed,
+        );
+        assert_eq!(report.error_codes(), vec!["E0308", "E0277"]);
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let messages = [
+            r#"{"reason":"compiler-artifact","target":{"name":"a"}}"#,
+            r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types","code":{"code":"E0308"},"rendered":"error[E0308]: mismatched types\n","spans":[{"file_name":"src/lib.rs","line_start":2,"column_start":5,"is_primary":true}]}}"#,
+            r#"{"reason":"compiler-message","message":{"level":"error","message":"aborting due to 1 previous error","code":null,"spans":[]}}"#,
+            r#"{"reason":"build-finished","success":false}"#,
+        ]
+        .join("\n");
+        let diagnostics = parse_diagnostics(&messages);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, "error");
+        assert_eq!(diagnostics[0].column, Some(5));
+        assert_eq!(diagnostics[0].rendered, "error[E0308]: mismatched types\n");
+        assert_eq!(
+            diagnostics[0].simple(),
+            "E0308: mismatched types at src/lib.rs:2"
+        );
+    }
 }
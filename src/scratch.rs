@@ -0,0 +1,49 @@
+//! Scratch folders of unit tests
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Folders created by this process, numbered so that tests running in parallel never share one
+static FOLDERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Empty folder of the temporary directory, removed with its content when dropped, even when an
+/// assertion fails first
+pub(crate) struct ScratchFolder(PathBuf);
+
+impl ScratchFolder {
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "darwin-{name}-{}-{}",
+            std::process::id(),
+            FOLDERS.fetch_add(1, Ordering::Relaxed)
+        ));
+        // Left by a killed process of the same id
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+        std::fs::create_dir_all(&path).unwrap();
+        ScratchFolder(path)
+    }
+}
+
+impl Deref for ScratchFolder {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchFolder {
+    fn drop(&mut self) {
+        if let Err(error) = std::fs::remove_dir_all(&self.0) {
+            eprintln!("Unable to remove {}: {error}", self.0.display());
+        }
+    }
+}
+
+impl AsRef<Path> for ScratchFolder {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
@@ -0,0 +1,69 @@
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Asserted by the tests, its mutants are caught
+pub fn add(x: u8, y: u8) -> u8 {
+    x + y
+}
+
+/// Executed by the tests but not asserted, its mutants are missing
+pub fn sub(x: u8, y: u8) -> u8 {
+    x - y
+}
+
+/// Fields have unknown types, `&&` is tried and doesn't build
+pub fn width(point: &Point) -> i32 {
+    point.x - point.y
+}
+
+/// Returned values, `let` initializers and tails of nested blocks are mutated too
+pub fn distance(x: u8, y: u8) -> u8 {
+    if x < y {
+        return y - x;
+    }
+    let distance = x - y;
+    if distance > 9 {
+        distance - 9
+    } else {
+        distance
+    }
+}
+
+/// Receivers and arguments of the calls giving a value are mutated too
+pub fn label(x: u8, y: u8) -> String {
+    (x + y).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(add(1, 2), 3);
+    }
+
+    #[test]
+    fn test_sub() {
+        let _ = sub(3, 1);
+    }
+
+    #[test]
+    fn test_width() {
+        assert_eq!(width(&Point { x: 3, y: 1 }), 2);
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance(1, 3), 2);
+        assert_eq!(distance(5, 2), 3);
+        assert_eq!(distance(20, 1), 10);
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!(label(1, 2), "3");
+    }
+}
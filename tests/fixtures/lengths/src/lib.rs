@@ -0,0 +1,22 @@
+/// The size is asserted, its mutants are caught
+pub fn header() -> Vec<u8> {
+    [0u8; 4].to_vec()
+}
+
+/// Only called, its mutants are missing
+pub fn zeros(count: usize) -> Vec<u8> {
+    vec![0; count]
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_header() {
+        assert_eq!(super::header().len(), 4);
+    }
+
+    #[test]
+    fn test_zeros() {
+        let _ = super::zeros(2);
+    }
+}
@@ -0,0 +1,24 @@
+/// The count is asserted, its mutants are caught
+pub fn retries() -> u8 {
+    let retries = 3;
+    retries
+}
+
+/// Only called, its mutant is missing
+pub fn verbose() -> bool {
+    let verbose = false;
+    verbose
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_retries() {
+        assert_eq!(super::retries(), 3);
+    }
+
+    #[test]
+    fn test_verbose() {
+        let _ = super::verbose();
+    }
+}
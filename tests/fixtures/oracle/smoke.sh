@@ -0,0 +1,4 @@
+#!/bin/sh
+# Smoke test of the binary, built in the target folder of the mutant given by CARGO_TARGET_DIR
+test -n "$CARGO_TARGET_DIR" || exit 2
+cargo run -q -- 7 3 | grep -q '^4 '
@@ -0,0 +1,20 @@
+//! Binary without tests, judged by the smoke test of `smoke.sh`
+
+/// Checked by the smoke test, its mutants are caught
+fn gap(x: i32, y: i32) -> i32 {
+    x - y
+}
+
+/// Not checked by the smoke test, its mutants are missing
+fn sum(x: i32, y: i32) -> i32 {
+    x + y
+}
+
+fn main() {
+    let args = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse().unwrap_or_default())
+        .collect::<Vec<i32>>();
+    let (x, y) = (args[0], args[1]);
+    println!("{} {}", gap(x, y), sum(x, y));
+}
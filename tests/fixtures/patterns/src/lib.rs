@@ -0,0 +1,17 @@
+/// Reason of an HTTP status, only the one of `200` is asserted
+pub fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_reason() {
+        assert_eq!(super::reason(200), "OK");
+        let _ = super::reason(404);
+    }
+}
@@ -0,0 +1,2 @@
+// Out of the module tree and not Rust, the file is left out
+fn broken( {
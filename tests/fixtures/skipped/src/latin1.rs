@@ -0,0 +1,4 @@
+// Out of the module tree and encoded in Latin-1 as in caf, the file is left out
+pub fn latin1(x: u8) -> u8 {
+    x + 1
+}
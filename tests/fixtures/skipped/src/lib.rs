@@ -0,0 +1,16 @@
+pub fn add(x: u8, y: u8) -> u8 {
+    x + y
+}
+
+/// Never used, its mutants are left out
+fn double(x: u8) -> u8 {
+    x + x
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_add() {
+        assert_eq!(super::add(1, 2), 3);
+    }
+}
@@ -0,0 +1,18 @@
+/// Replacing `+` by `*` pushes nothing, the loop never ends
+pub fn grow(n: usize) -> usize {
+    let mut v: Vec<usize> = vec![];
+    while v.len() < n {
+        let w = vec![v.len() + 1, 0];
+        v.push(w[0]);
+        v.truncate(w[0]);
+    }
+    v.len()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_grow() {
+        assert_eq!(super::grow(3), 3);
+    }
+}
@@ -0,0 +1,23 @@
+#[derive(Debug, PartialEq)]
+pub enum Door {
+    Open,
+    Closed,
+}
+
+/// Only the opened door is asserted, the mutant of the closed one is missing
+pub fn toggle(door: Door) -> Door {
+    match door {
+        Door::Open => Door::Closed,
+        Door::Closed => Door::Open,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Door;
+
+    #[test]
+    fn test_toggle() {
+        assert_eq!(super::toggle(Door::Open), Door::Closed);
+    }
+}
@@ -0,0 +1,287 @@
+//! Darwin run on small crates, the fixtures, whose mutants and their statuses are known
+//!
+//! Each folder of `tests/fixtures` is a crate, out of the workspace of darwin, with an
+//! `expected.txt` listing the stable ID and the status of each of its mutants, one per line, with
+//! the mutants left out as `skipped:<reason>` and the files left out as `<file> skipped`:
+//!
+//! ```text
+//! # Stable ID and status of each mutant
+//! src-lib-add-8-7-mul ok
+//! src-lib-sub-13-7-add missing
+//! src-lib-double-7-7-mul skipped:dead_code
+//! src/broken.rs skipped
+//! ```
+//!
+//! A `darwin.toml` in the fixture configures its run, as in any project. A new case is a new
+//! folder, `DARWIN_UPDATE_FIXTURES=1 cargo test --test self_test` writes the `expected.txt` of the
+//! fixtures from their results, to review before committing.
+
+use std::path::{Path, PathBuf};
+
+static EXPECTED_FILE: &str = "expected.txt";
+static EXPECTED_HEADER: &str = "# Stable ID and status of each mutant";
+
+/// Copy a fixture, leaving out what its builds leave behind
+fn copy_fixture(fixture: &Path, project: &Path) {
+    if project.exists() {
+        std::fs::remove_dir_all(project).unwrap();
+    }
+    let entries = walkdir::WalkDir::new(fixture)
+        .into_iter()
+        .filter_entry(|entry| {
+            !["target", "Cargo.lock"].contains(&entry.file_name().to_str().unwrap())
+        });
+    for entry in entries {
+        let entry = entry.unwrap();
+        let path = project.join(entry.path().strip_prefix(fixture).unwrap());
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(path).unwrap();
+        } else {
+            std::fs::copy(entry.path(), path).unwrap();
+        }
+    }
+}
+
+/// Entries of a list of the results, none if the list is left out
+fn entries<'a>(summary: &'a serde_json::Value, key: &str) -> &'a [serde_json::Value] {
+    summary[key].as_array().map_or(&[], Vec::as_slice)
+}
+
+/// Stable ID and status of the mutants of the last run, `skipped:<reason>` for the ones not
+/// attempted, then the files skipped, sorted
+fn outcomes(mutation_path: &Path) -> Vec<String> {
+    let summary = std::fs::read_to_string(mutation_path.join("summary.json")).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(&summary).unwrap();
+    let field = |entry: &serde_json::Value, key: &str| entry[key].as_str().unwrap().to_string();
+    let mut outcomes = entries(&summary, "mutants")
+        .iter()
+        .map(|mutant| format!("{} {}", field(mutant, "stable_id"), field(mutant, "status")))
+        .chain(entries(&summary, "not_attempted").iter().map(|mutant| {
+            format!(
+                "{} skipped:{}",
+                field(mutant, "stable_id"),
+                field(mutant, "reason")
+            )
+        }))
+        .chain(
+            entries(&summary, "skipped")
+                .iter()
+                .map(|file| format!("{} skipped", field(file, "file"))),
+        )
+        .collect::<Vec<_>>();
+    outcomes.sort();
+    outcomes
+}
+
+/// Outcomes listed by a fixture, sorted
+fn expected(fixture: &Path) -> Vec<String> {
+    let content = std::fs::read_to_string(fixture.join(EXPECTED_FILE)).unwrap_or_default();
+    let mut expected = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect::<Vec<_>>();
+    expected.sort();
+    expected
+}
+
+fn fixtures() -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+    let mut fixtures = std::fs::read_dir(root)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.join("Cargo.toml").exists())
+        .collect::<Vec<_>>();
+    fixtures.sort();
+    fixtures
+}
+
+#[test]
+fn test_fixtures() {
+    let update = std::env::var("DARWIN_UPDATE_FIXTURES").is_ok_and(|value| value == "1");
+    let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fixtures");
+    let mut failures = vec![];
+    for fixture in fixtures() {
+        let name = fixture.file_name().unwrap().to_string_lossy().to_string();
+        let project = scratch.join(&name).join("project");
+        let mutation_path = scratch.join(&name).join("darwin");
+        copy_fixture(&fixture, &project);
+        // Without the caches of the previous run, every mutant is verified
+        if mutation_path.exists() {
+            std::fs::remove_dir_all(&mutation_path).unwrap();
+        }
+
+        let args = [
+            "cargo-darwin".into(),
+            "darwin".into(),
+            "--mutation-path".into(),
+            mutation_path.clone().into_os_string(),
+            project.into_os_string(),
+        ];
+        if let Err(error) = cargo_darwin::run_from(args) {
+            failures.push(format!("Fixture {name} failed to run: {error:#}"));
+            continue;
+        }
+
+        let outcomes = outcomes(&mutation_path.join("latest"));
+        if update {
+            let mut content = vec![EXPECTED_HEADER.to_string()];
+            content.extend(outcomes);
+            content.push(String::new());
+            std::fs::write(fixture.join(EXPECTED_FILE), content.join("\n")).unwrap();
+        } else if outcomes != expected(&fixture) {
+            failures.push(format!(
+                "Fixture {name} expected\n  {}\nbut got\n  {}",
+                expected(&fixture).join("\n  "),
+                outcomes.join("\n  ")
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Each worker builds its mutants in a target folder copied from the build of the project without
+/// mutation, even when the configuration isolates the targets and the CLI overrides it. Target
+/// folders are removed once mutants are verified.
+#[cfg(unix)]
+#[test]
+fn test_warm_targets() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("arithmetic");
+    let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("warm_targets");
+    let project = scratch.join("project");
+    let mutation_path = scratch.join("darwin");
+    copy_fixture(&fixture, &project);
+    // Hooks run in the project of the worker, next to the target folders
+    std::fs::write(
+        project.join("darwin.toml"),
+        r#"isolated_targets = true
+
+[hooks]
+pre_mutant = "for worker in 0 1; do ls ../targets/$worker/debug/deps | grep -q '^libarithmetic-' && touch ../warmed_$worker; done; true"
+"#,
+    )
+    .unwrap();
+    if mutation_path.exists() {
+        std::fs::remove_dir_all(&mutation_path).unwrap();
+    }
+
+    let args = [
+        "cargo-darwin".into(),
+        "darwin".into(),
+        "--jobs".into(),
+        "2".into(),
+        "--isolated-targets=false".into(),
+        "--mutation-path".into(),
+        mutation_path.clone().into_os_string(),
+        project.into_os_string(),
+    ];
+    cargo_darwin::run_from(args).unwrap();
+
+    let latest = mutation_path.join("latest");
+    assert_eq!(outcomes(&latest), expected(&fixture));
+    for worker in ["0", "1"] {
+        assert!(
+            latest.join(format!("warmed_{worker}")).exists(),
+            "Worker {worker} has no pre-built target"
+        );
+    }
+    assert!(!latest.join("targets").exists());
+}
+
+/// Kept mutant projects are named after the stable ID and the status of their mutant, with a
+/// manifest describing it
+#[test]
+fn test_kept_mutants() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("arithmetic");
+    let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("kept_mutants");
+    let project = scratch.join("project");
+    let mutation_path = scratch.join("darwin");
+    copy_fixture(&fixture, &project);
+    if mutation_path.exists() {
+        std::fs::remove_dir_all(&mutation_path).unwrap();
+    }
+
+    let args = [
+        "cargo-darwin".into(),
+        "darwin".into(),
+        "--keep=missing".into(),
+        "--mutation-path".into(),
+        mutation_path.clone().into_os_string(),
+        project.into_os_string(),
+    ];
+    cargo_darwin::run_from(args).unwrap();
+
+    let latest = mutation_path.join("latest");
+    let mut kept = std::fs::read_dir(&latest)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("mutant_"))
+        .collect::<Vec<_>>();
+    kept.sort();
+    assert_eq!(
+        kept,
+        vec![
+            "mutant_src-lib-sub-13-7-add_missing",
+            "mutant_src-lib-sub-13-7-mul_missing"
+        ]
+    );
+    let manifest = std::fs::read_to_string(latest.join(&kept[0]).join("MUTATION.txt")).unwrap();
+    assert!(manifest.contains("src-lib-sub-13-7-add"), "{manifest}");
+    assert!(manifest.contains("Status: missing"), "{manifest}");
+    assert!(manifest.contains("+    x + y"), "{manifest}");
+}
+
+/// A failing `pre_mutant` hook leaves its mutant out with the `hook` reason, the other mutants are
+/// verified
+#[cfg(unix)]
+#[test]
+fn test_failing_pre_mutant_hook() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("arithmetic");
+    let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("failing_hook");
+    let project = scratch.join("project");
+    let mutation_path = scratch.join("darwin");
+    copy_fixture(&fixture, &project);
+    if mutation_path.exists() {
+        std::fs::remove_dir_all(&mutation_path).unwrap();
+    }
+
+    let args = [
+        "cargo-darwin".into(),
+        "darwin".into(),
+        "--pre-mutant".into(),
+        "test \"$DARWIN_MUTANT_ID\" != 0".into(),
+        "--mutation-path".into(),
+        mutation_path.clone().into_os_string(),
+        project.into_os_string(),
+    ];
+    cargo_darwin::run_from(args).unwrap();
+
+    let outcomes = outcomes(&mutation_path.join("latest"));
+    let skipped = outcomes
+        .iter()
+        .filter(|outcome| outcome.ends_with(" skipped:hook"))
+        .collect::<Vec<_>>();
+    assert_eq!(skipped.len(), 1, "{outcomes:?}");
+    let stable_id = skipped[0].trim_end_matches(" skipped:hook");
+    let mut expected = expected(&fixture)
+        .into_iter()
+        .map(|outcome| match outcome.split_once(' ') {
+            Some((id, _)) if id == stable_id => format!("{id} skipped:hook"),
+            _ => outcome,
+        })
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(outcomes, expected);
+}